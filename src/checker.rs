@@ -0,0 +1,485 @@
+//! Read-only verification that a directory matches a Strux document (`strux check`).
+//!
+//! Actions are folded, in document order, into one expected final state per path (so a
+//! `Create` followed by an `Append` expects the combined content), which is then compared
+//! with what is on disk. Nothing is ever written.
+
+use crate::core_types::{Action, ActionType, Content};
+use crate::errors::AppError;
+use crate::processor::{edit, insert, patch};
+use crate::reporter::{Event, Reporter};
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Component, Path};
+
+/// What the document says a path should look like after it has been applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expectation {
+    /// A file with exactly these bytes.
+    Content(Vec<u8>),
+    /// A file starting with `prefix` and ending with `suffix` (from Append/Prepend or a Move of
+    /// a file the document does not describe; both empty means "any file").
+    Partial { prefix: Vec<u8>, suffix: Vec<u8> },
+    /// A file holding every `present` text and none of the `absent` ones (from Edit, Patch and
+    /// Insert actions on a file the document does not describe: the text they put in place
+    /// must be there, and the text an edit replaced must be gone).
+    Contains {
+        present: Vec<String>,
+        absent: Vec<String>,
+    },
+    /// A directory (Directory targets).
+    Directory,
+    /// A symbolic link pointing to exactly this target (Symlink link paths).
+    Symlink(String),
+    /// Nothing at all (Delete targets, Move sources and Deleted Directory targets).
+    Absent,
+}
+
+/// Result of comparing one path with its expectation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Match,
+    ContentDiffers, // The file exists but its content is not what the document says
+    Missing,        // A file was expected but nothing is there
+    UnexpectedlyExists, // The path should be absent but exists
+    NotAFile,       // A file was expected but the path is a directory (or other type)
+    NotADirectory,  // A directory was expected but the path is something else
+    NotASymlink,    // A symlink was expected but the path is something else
+    LinkTargetDiffers, // The symlink exists but points somewhere else
+    InvalidPath,    // Absolute or escaping path; never read
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            CheckStatus::Match => "ok",
+            CheckStatus::ContentDiffers => "content differs",
+            CheckStatus::Missing => "missing",
+            CheckStatus::UnexpectedlyExists => "should not exist",
+            CheckStatus::NotAFile => "not a regular file",
+            CheckStatus::NotADirectory => "not a directory",
+            CheckStatus::NotASymlink => "not a symlink",
+            CheckStatus::LinkTargetDiffers => "link target differs",
+            CheckStatus::InvalidPath => "invalid or unsafe path",
+        };
+        f.write_str(text)
+    }
+}
+
+/// One checked path.
+#[derive(Debug)]
+pub struct CheckEntry {
+    pub path: String,
+    pub expected: Expectation,
+    pub status: CheckStatus,
+}
+
+/// Every checked path, in order of first appearance in the document.
+#[derive(Debug, Default)]
+pub struct CheckReport {
+    pub entries: Vec<CheckEntry>,
+}
+
+impl CheckReport {
+    /// Number of paths that do not match the document.
+    pub fn mismatches(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.status != CheckStatus::Match)
+            .count()
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.mismatches() == 0
+    }
+}
+
+/// Compares the final state described by `actions` with the files below `base_dir`.
+/// `fuzz` is the patch fuzz the document is applied with (see `ProcessOptions::fuzz`).
+pub fn check_actions(
+    base_dir: &Path,
+    actions: &[Action],
+    fuzz: usize,
+    reporter: &dyn Reporter,
+) -> Result<CheckReport, AppError> {
+    if !base_dir.is_dir() {
+        return Err(AppError::Argument(format!(
+            "Cannot check '{}': not a directory",
+            base_dir.display()
+        )));
+    }
+
+    let expectations = fold_expectations(actions, fuzz);
+    let mut report = CheckReport::default();
+    for (path, expected) in expectations {
+        let status = check_path(base_dir, &path, &expected)?;
+        reporter.report(&Event::Decision(format!(
+            "Checked '{}': expected {:?}, {}",
+            path, expected, status
+        )));
+        report.entries.push(CheckEntry {
+            path,
+            expected,
+            status,
+        });
+    }
+    Ok(report)
+}
+
+/// Applies the actions in order to build the expected final state of every path they touch.
+fn fold_expectations(actions: &[Action], fuzz: usize) -> Vec<(String, Expectation)> {
+    // `None` means the document has not described the path (yet)
+    let mut described: Vec<(String, Option<Expectation>)> = Vec::new();
+
+    for action in actions {
+        if action.action_type == ActionType::Include {
+            continue; // Unresolved includes describe no path (see `resolve_includes`)
+        }
+        let content = action
+            .content
+            .as_ref()
+            .map_or_else(Vec::new, |content| content.as_bytes().to_vec());
+        let text = action
+            .content
+            .as_ref()
+            .and_then(Content::as_text)
+            .unwrap_or_default();
+        let index = entry_index(&mut described, &action.path);
+        let current = described[index].1.take();
+        let next = match action.action_type {
+            ActionType::Create => Expectation::Content(content),
+            ActionType::Delete => Expectation::Absent,
+            ActionType::Include => unreachable!("Include actions are skipped above"),
+            ActionType::CreateDirectory => Expectation::Directory,
+            ActionType::Symlink => match current {
+                // A file or directory at the link path makes the action fail
+                Some(
+                    expectation @ (Expectation::Content(_)
+                    | Expectation::Partial { .. }
+                    | Expectation::Contains { .. }
+                    | Expectation::Directory),
+                ) => expectation,
+                _ => Expectation::Symlink(action.dest_path.clone().unwrap_or_default()),
+            },
+            ActionType::DeleteDirectory => {
+                // Everything the document put inside the directory is gone with it
+                let inside = format!("{}/", action.path.trim_end_matches('/'));
+                for (path, expectation) in described.iter_mut() {
+                    if path.starts_with(&inside) {
+                        *expectation = Some(Expectation::Absent);
+                    }
+                }
+                Expectation::Absent
+            }
+            ActionType::Append => match current {
+                Some(Expectation::Content(existing)) => {
+                    Expectation::Content([existing, content].concat())
+                }
+                Some(Expectation::Absent) => Expectation::Content(content), // Append creates it
+                Some(Expectation::Directory) => Expectation::Directory, // Append fails, no change
+                Some(link @ Expectation::Symlink(_)) => link, // Writes go to the link's target
+                Some(Expectation::Partial { prefix, suffix }) => Expectation::Partial {
+                    prefix,
+                    suffix: [suffix, content].concat(),
+                },
+                Some(contains @ Expectation::Contains { .. }) => {
+                    narrow(Some(contains), false, vec![text.to_string()], Vec::new())
+                }
+                None => Expectation::Partial {
+                    prefix: Vec::new(),
+                    suffix: content,
+                },
+            },
+            ActionType::Prepend => match current {
+                Some(Expectation::Content(existing)) => {
+                    Expectation::Content([content, existing].concat())
+                }
+                Some(Expectation::Absent) => Expectation::Content(content), // Prepend creates it
+                Some(Expectation::Directory) => Expectation::Directory, // Prepend fails, no change
+                Some(link @ Expectation::Symlink(_)) => link, // Writes go to the link's target
+                Some(Expectation::Partial { prefix, suffix }) => Expectation::Partial {
+                    prefix: [content, prefix].concat(),
+                    suffix,
+                },
+                Some(contains @ Expectation::Contains { .. }) => {
+                    narrow(Some(contains), false, vec![text.to_string()], Vec::new())
+                }
+                None => Expectation::Partial {
+                    prefix: content,
+                    suffix: Vec::new(),
+                },
+            },
+            ActionType::MoveDirectory => {
+                // Everything the document put inside the source now lives below the destination
+                let inside = format!("{}/", action.path.trim_end_matches('/'));
+                let dest_dir = action.dest_path.clone().unwrap_or_default();
+                let moved: Vec<(String, Option<Expectation>)> = described
+                    .iter_mut()
+                    .filter(|(path, _)| path.starts_with(&inside))
+                    .map(|(path, expectation)| {
+                        let landing = format!("{}/{}", dest_dir, &path[inside.len()..]);
+                        (landing, expectation.replace(Expectation::Absent))
+                    })
+                    .collect();
+                for (landing, expectation) in moved {
+                    let landing_index = entry_index(&mut described, &landing);
+                    described[landing_index].1 = expectation;
+                }
+                let dest_index = entry_index(&mut described, &dest_dir);
+                described[dest_index].1 = Some(Expectation::Directory);
+                Expectation::Absent
+            }
+            ActionType::Copy => {
+                // The destination gets whatever the source is expected to be; the source stays
+                let copied = match &current {
+                    Some(
+                        file @ (Expectation::Content(_)
+                        | Expectation::Partial { .. }
+                        | Expectation::Contains { .. }),
+                    ) => file.clone(),
+                    _ => any_file(),
+                };
+                if let Some(dest_path) = &action.dest_path {
+                    if *dest_path != action.path {
+                        let dest_index = entry_index(&mut described, dest_path);
+                        described[dest_index].1 = Some(copied);
+                    }
+                }
+                current.unwrap_or_else(any_file)
+            }
+            ActionType::Edit => match current {
+                // A failing edit leaves the file as it was
+                Some(Expectation::Content(existing)) => {
+                    Expectation::Content(map_text(existing, |existing| {
+                        edit::edit_text(existing, text, Path::new(&action.path)).ok()
+                    }))
+                }
+                current @ (None | Some(Expectation::Contains { .. })) => {
+                    match edit::hunk_texts(text, Path::new(&action.path)) {
+                        Ok(hunks) => {
+                            let (present, absent) = edit_traces(&hunks);
+                            narrow(current, true, present, absent)
+                        }
+                        Err(_) => current.unwrap_or_else(any_file), // The edit fails
+                    }
+                }
+                Some(expectation) => expectation,
+            },
+            ActionType::Patch => match current {
+                // A failing patch leaves the file as it was
+                Some(Expectation::Content(existing)) => {
+                    Expectation::Content(map_text(existing, |existing| {
+                        patch::patch_text(Some(existing), text, fuzz, Path::new(&action.path)).ok()
+                    }))
+                }
+                Some(Expectation::Absent) => {
+                    // Only a diff against /dev/null creates the file
+                    match patch::patch_text(None, text, 0, Path::new(&action.path)) {
+                        Ok(created) => Expectation::Content(created.into_bytes()),
+                        Err(_) => Expectation::Absent,
+                    }
+                }
+                current @ (None | Some(Expectation::Contains { .. })) => {
+                    match patch::changed_runs(text, Path::new(&action.path)) {
+                        Ok((removed, added)) => {
+                            narrow(current, !removed.is_empty(), added, Vec::new())
+                        }
+                        Err(_) => current.unwrap_or_else(any_file), // The patch fails
+                    }
+                }
+                Some(expectation) => expectation,
+            },
+            ActionType::InsertAfter | ActionType::InsertBefore => match current {
+                // A missing anchor fails the action (`check` has no --force) and changes nothing
+                Some(Expectation::Content(existing)) => {
+                    Expectation::Content(map_text(existing, |existing| {
+                        insert::insert_text(existing, action, false, Path::new(&action.path))
+                            .ok()
+                            .map(|(inserted, _)| inserted)
+                    }))
+                }
+                current @ (None | Some(Expectation::Contains { .. })) => {
+                    narrow(current, false, vec![text.to_string()], Vec::new())
+                }
+                Some(expectation) => expectation,
+            },
+            // Only files are moved; a directory source is skipped and stays where it is
+            ActionType::Move if current == Some(Expectation::Directory) => Expectation::Directory,
+            ActionType::Move => {
+                // The destination inherits whatever the source was expected to be
+                let moved = match current {
+                    Some(Expectation::Absent) | None => any_file(),
+                    Some(expectation) => expectation,
+                };
+                if let Some(dest_path) = &action.dest_path {
+                    let dest_index = entry_index(&mut described, dest_path);
+                    described[dest_index].1 = Some(moved);
+                }
+                Expectation::Absent
+            }
+        };
+        described[index].1 = Some(next);
+    }
+
+    described
+        .into_iter()
+        .map(|(path, expectation)| (path, expectation.unwrap_or(Expectation::Absent)))
+        .collect()
+}
+
+/// A file the document says nothing about: anything goes as long as it is a file.
+fn any_file() -> Expectation {
+    Expectation::Partial {
+        prefix: Vec::new(),
+        suffix: Vec::new(),
+    }
+}
+
+/// What an edit leaves behind in a file the document does not describe: every REPLACE text
+/// (unless a later hunk edits it again) and no SEARCH text (unless a REPLACE text holds it).
+fn edit_traces(hunks: &[(String, String)]) -> (Vec<String>, Vec<String>) {
+    let present = hunks
+        .iter()
+        .enumerate()
+        .filter(|(index, (_, replace))| {
+            !hunks[index + 1..].iter().any(|(later_search, _)| {
+                replace.contains(later_search.as_str()) || later_search.contains(replace.as_str())
+            })
+        })
+        .map(|(_, (_, replace))| replace.clone())
+        .collect();
+    let absent = hunks
+        .iter()
+        .filter(|(search, _)| {
+            !hunks
+                .iter()
+                .any(|(_, replace)| replace.contains(search.as_str()))
+        })
+        .map(|(search, _)| search.clone())
+        .collect();
+    (present, absent)
+}
+
+/// Adds what a change to an undescribed file must leave behind to `current` (`None` or
+/// `Contains`). A change that `removes` text may remove earlier `present` texts, and new
+/// text may bring back an `absent` one, so those are no longer required.
+fn narrow(
+    current: Option<Expectation>,
+    removes: bool,
+    present: Vec<String>,
+    absent: Vec<String>,
+) -> Expectation {
+    let (mut all_present, mut all_absent) = match current {
+        Some(Expectation::Contains { present, absent }) if !removes => (present, absent),
+        Some(Expectation::Contains { absent, .. }) => (Vec::new(), absent),
+        _ => (Vec::new(), Vec::new()),
+    };
+    all_absent.retain(|gone| !present.iter().any(|text| text.contains(gone.as_str())));
+    all_present.extend(present.into_iter().filter(|text| !text.is_empty()));
+    all_absent.extend(absent);
+    Expectation::Contains {
+        present: all_present,
+        absent: all_absent,
+    }
+}
+
+/// Applies a text transformation (Edit/Patch/Insert) to expected file content. Content that
+/// is not UTF-8 text, or a transformation that fails, leaves the content as it was.
+fn map_text(existing: Vec<u8>, transform: impl FnOnce(&str) -> Option<String>) -> Vec<u8> {
+    match std::str::from_utf8(&existing).ok().and_then(transform) {
+        Some(changed) => changed.into_bytes(),
+        None => existing,
+    }
+}
+
+/// Index of `path` in `described`, adding it (undescribed) on first appearance.
+fn entry_index(described: &mut Vec<(String, Option<Expectation>)>, path: &str) -> usize {
+    match described.iter().position(|(known, _)| known == path) {
+        Some(index) => index,
+        None => {
+            described.push((path.to_string(), None));
+            described.len() - 1
+        }
+    }
+}
+
+/// Compares a single path on disk with its expectation.
+fn check_path(
+    base_dir: &Path,
+    relative: &str,
+    expected: &Expectation,
+) -> Result<CheckStatus, AppError> {
+    let is_plain_relative = !relative.is_empty()
+        && Path::new(relative)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if !is_plain_relative {
+        return Ok(CheckStatus::InvalidPath);
+    }
+    let path = base_dir.join(relative);
+
+    let metadata = match fs::symlink_metadata(&path) {
+        Ok(metadata) => Some(metadata),
+        Err(e) if e.kind() == ErrorKind::NotFound || e.kind() == ErrorKind::NotADirectory => None,
+        Err(e) => return Err(AppError::Io(e)),
+    };
+    let status = match (expected, metadata) {
+        (Expectation::Absent, None) => CheckStatus::Match,
+        (Expectation::Absent, Some(_)) => CheckStatus::UnexpectedlyExists,
+        (_, None) => CheckStatus::Missing,
+        (Expectation::Symlink(target), Some(metadata)) => {
+            if !metadata.file_type().is_symlink() {
+                CheckStatus::NotASymlink
+            } else if fs::read_link(&path)? == Path::new(target) {
+                CheckStatus::Match
+            } else {
+                CheckStatus::LinkTargetDiffers
+            }
+        }
+        (Expectation::Directory, Some(metadata)) => {
+            if metadata.is_dir() {
+                CheckStatus::Match
+            } else {
+                CheckStatus::NotADirectory
+            }
+        }
+        (_, Some(metadata)) if !metadata.is_file() => CheckStatus::NotAFile,
+        (Expectation::Content(content), Some(_)) => {
+            if fs::read(&path)? == *content {
+                CheckStatus::Match
+            } else {
+                CheckStatus::ContentDiffers
+            }
+        }
+        (Expectation::Partial { prefix, suffix }, Some(_)) => {
+            let actual = fs::read(&path)?;
+            if actual.len() >= prefix.len() + suffix.len()
+                && actual.starts_with(prefix)
+                && actual.ends_with(suffix)
+            {
+                CheckStatus::Match
+            } else {
+                CheckStatus::ContentDiffers
+            }
+        }
+        (Expectation::Contains { present, absent }, Some(_)) => {
+            let actual = String::from_utf8_lossy(&fs::read(&path)?).into_owned();
+            // Edits and inserts use the file's line endings
+            let crlf = actual.contains("\r\n");
+            let holds = |text: &String| {
+                if crlf && !text.contains("\r\n") {
+                    actual.contains(&text.replace('\n', "\r\n"))
+                } else {
+                    actual.contains(text.as_str())
+                }
+            };
+            if present.iter().all(holds) && !absent.iter().any(holds) {
+                CheckStatus::Match
+            } else {
+                CheckStatus::ContentDiffers
+            }
+        }
+    };
+    Ok(status)
+}
@@ -1,22 +1,191 @@
 //! Defines the command-line arguments structure.
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
+use strux::{parse_variable_assignment, ActionType, LineEndings, Verbosity, DEFAULT_PATCH_FUZZ};
+
+/// The output directory when neither `-o` nor the front matter sets one.
+const DEFAULT_OUTPUT_DIR: &str = "./project-generated";
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None,
     after_help = "Processes a structured markdown file to generate or delete files.\n\
-                  Recognizes various header formats (see README/docs)."
+                  Recognizes various header formats (see README/docs).",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
 )]
 /// Holds the parsed command-line arguments.
 pub struct Cli {
-    /// Path to the markdown file containing the project structure.
-    pub markdown_file: PathBuf,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Markdown files containing the project structure ('-' reads standard input).
+    /// Actions from several files are merged in the order given.
+    #[arg(value_name = "MARKDOWN_FILE", required = true)]
+    pub markdown_files: Vec<PathBuf>,
 
-    /// The base directory to create/delete files in (default: ./project-generated).
-    #[arg(short, long, value_name = "DIR", default_value = "./project-generated")]
-    pub output_dir: PathBuf,
+    /// The base directory to create/delete files in (default: the front matter's
+    /// 'output_dir', or ./project-generated).
+    #[arg(short, long, value_name = "DIR", global = true)]
+    pub output_dir: Option<PathBuf>,
 
-    /// Overwrite existing files for 'File' actions.
+    /// Overwrite existing files for 'File' actions, replace existing links for 'Symlink'
+    /// actions (and insert at the end of the file when an 'Insert After'/'Insert Before'
+    /// anchor is not found).
     #[arg(short, long)]
     pub force: bool,
+
+    /// Do not overwrite, even if the document's front matter sets 'force: true'.
+    #[arg(long, conflicts_with = "force")]
+    pub no_force: bool,
+
+    /// Convert the line endings of written text: keep, lf or crlf (default: the front
+    /// matter's 'line_endings', or keep).
+    #[arg(long, value_name = "POLICY", value_parser = parse_line_endings, global = true)]
+    pub line_endings: Option<LineEndings>,
+
+    /// Only accept these action types (comma-separated, e.g. 'create,append'); overrides
+    /// the front matter's 'allowed_actions'.
+    #[arg(long, value_name = "TYPES", value_delimiter = ',', value_parser = parse_action_type, global = true)]
+    pub allow_actions: Option<Vec<ActionType>>,
+
+    /// Show what would be done without modifying the filesystem.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Do not save an undo journal (see `strux undo`) in the output directory.
+    #[arg(long)]
+    pub no_journal: bool,
+
+    /// Undo every change if any action fails, leaving the output directory untouched.
+    #[arg(long)]
+    pub atomic: bool,
+
+    /// Context lines a 'Patch File' hunk may ignore on each side when it does not match exactly.
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_PATCH_FUZZ)]
+    pub fuzz: usize,
+
+    /// Skip 'Append File'/'Prepend File' blocks already at the end/start of the file, so a
+    /// document can be applied again without duplicating them.
+    #[arg(long)]
+    pub idempotent: bool,
+
+    /// Make written files that start with a '#!' shebang executable. A 'mode=NNN' attribute
+    /// on the block's fence takes precedence.
+    #[arg(long)]
+    pub auto_exec: bool,
+
+    /// Set a template variable: '{{KEY}}' in paths and block contents becomes VALUE
+    /// (repeatable; overrides --vars-file). Write '\{{' for literal braces.
+    #[arg(long = "var", value_name = "KEY=VALUE", value_parser = parse_variable_assignment, global = true)]
+    pub vars: Vec<(String, String)>,
+
+    /// Read template variables from a file of 'KEY=VALUE' lines ('#' starts a comment line).
+    #[arg(long, value_name = "FILE", global = true)]
+    pub vars_file: Option<PathBuf>,
+
+    /// Leave '{{...}}' and '\{{' in the document as written, even when front matter
+    /// defines variables.
+    #[arg(long, conflicts_with_all = ["vars", "vars_file"], global = true)]
+    pub no_vars: bool,
+
+    /// Keep the document's '<!-- strux:if FLAG -->' regions (repeatable or comma-separated).
+    /// Regions of flags that are not enabled are dropped, keeping their 'strux:else' part.
+    #[arg(long, value_name = "FLAG", value_delimiter = ',', global = true)]
+    pub enable: Vec<String>,
+
+    /// Let a content header take a following code block indented by 4 spaces (or a tab)
+    /// when no fenced block is attached to it.
+    #[arg(long, global = true)]
+    pub indented_blocks: bool,
+
+    /// Show a unified diff of the changes without modifying the filesystem.
+    #[arg(long, conflicts_with = "format")]
+    pub diff: bool,
+
+    /// Output format for the run report.
+    #[arg(long, value_enum, value_name = "FORMAT", default_value_t = OutputFormat::Human)]
+    pub format: OutputFormat,
+
+    /// Only print errors and the final summary.
+    #[arg(short, long, conflicts_with_all = ["verbose", "debug"], global = true)]
+    pub quiet: bool,
+
+    /// Also print parser association decisions and per-action progress.
+    #[arg(short, long, conflicts_with = "debug", global = true)]
+    pub verbose: bool,
+
+    /// Print everything, including parser pass banners and fence-matching traces.
+    #[arg(long, global = true)]
+    pub debug: bool,
+}
+
+/// Subcommands; without one, strux processes the given markdown files.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Revert the most recent run using the undo journal in the output directory.
+    Undo,
+    /// Verify, without changing anything, that the output directory matches the markdown files.
+    /// Exits with a non-zero status if any path does not match.
+    Check {
+        /// Markdown files describing the expected structure ('-' reads standard input).
+        #[arg(value_name = "MARKDOWN_FILE", required = true)]
+        markdown_files: Vec<PathBuf>,
+    },
+    /// Print a Strux markdown document that recreates the files in a directory.
+    Pack {
+        /// The directory to pack.
+        dir: PathBuf,
+
+        /// Only pack files matching this glob (relative to DIR; repeatable).
+        #[arg(long, value_name = "GLOB")]
+        include: Vec<String>,
+
+        /// Skip files and directories matching this glob (relative to DIR; repeatable).
+        #[arg(long, value_name = "GLOB")]
+        exclude: Vec<String>,
+    },
+}
+
+impl Cli {
+    /// The output directory given with `-o`, or the default one.
+    pub fn output_dir(&self) -> PathBuf {
+        self.output_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_OUTPUT_DIR))
+    }
+
+    /// The verbosity selected by `--quiet`, `--verbose` or `--debug` (default: normal).
+    pub fn verbosity(&self) -> Verbosity {
+        if self.quiet {
+            Verbosity::Quiet
+        } else if self.debug {
+            Verbosity::Debug
+        } else if self.verbose {
+            Verbosity::Verbose
+        } else {
+            Verbosity::Normal
+        }
+    }
+}
+
+/// Parses a `--line-endings` policy.
+fn parse_line_endings(name: &str) -> Result<LineEndings, String> {
+    LineEndings::from_name(name).ok_or_else(|| "expected keep, lf or crlf".to_string())
+}
+
+/// Parses one `--allow-actions` label, as used in the JSON report (e.g. `insert_after`).
+fn parse_action_type(label: &str) -> Result<ActionType, String> {
+    ActionType::from_label(label.trim()).ok_or_else(|| {
+        let known: Vec<_> = ActionType::ALL.iter().map(ActionType::label).collect();
+        format!("expected one of: {}", known.join(", "))
+    })
+}
+
+/// Selects how the run report is printed.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable progress and summary table.
+    Human,
+    /// A single JSON document describing every action and the summary counters.
+    Json,
 }
@@ -0,0 +1,134 @@
+//! Resolves the effective configuration of a run: front matter supplies defaults and the
+//! command line overrides them.
+use crate::cli::args::Cli;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use strux::{parse_variable_assignment, ActionType, AppError, FrontMatter, LineEndings};
+
+/// Where a configuration value comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    FrontMatter,
+    CommandLine,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Source::Default => "default",
+            Source::FrontMatter => "front matter",
+            Source::CommandLine => "command line",
+        })
+    }
+}
+
+/// A configuration value and where it comes from.
+#[derive(Debug)]
+pub struct Setting<T> {
+    pub value: T,
+    pub source: Source,
+}
+
+/// The settings a run uses, after front matter and command-line flags are combined.
+#[derive(Debug)]
+pub struct RunConfig {
+    pub output_dir: Setting<PathBuf>,
+    pub force: Setting<bool>,
+    pub variables: BTreeMap<String, String>,
+    pub substitute: bool, // Whether '{{' is processed at all (off with --no-vars)
+    pub line_endings: Setting<LineEndings>,
+    pub allowed_actions: Setting<Option<Vec<ActionType>>>, // None = every action type
+    pub enabled_flags: BTreeSet<String>,                   // Conditional regions to keep (--enable)
+}
+
+impl RunConfig {
+    /// Combines the (merged) front matter of the inputs with the command line, which wins.
+    /// Variables are layered: front matter, then `--vars-file`, then `--var`.
+    pub fn resolve(cli: &Cli, front_matter: FrontMatter) -> Result<RunConfig, AppError> {
+        let force_flag = if cli.force {
+            Some(true)
+        } else if cli.no_force {
+            Some(false)
+        } else {
+            None
+        };
+        let mut variables = front_matter.variables;
+        if cli.no_vars {
+            variables.clear(); // Front matter values would have nothing to substitute into
+        } else {
+            load_variables(cli, &mut variables)?;
+        }
+        Ok(RunConfig {
+            output_dir: pick(
+                cli.output_dir.clone(),
+                front_matter.output_dir.map(PathBuf::from),
+                cli.output_dir(),
+            ),
+            force: pick(force_flag, front_matter.force, false),
+            variables,
+            substitute: !cli.no_vars,
+            line_endings: pick(
+                cli.line_endings,
+                front_matter.line_endings,
+                LineEndings::default(),
+            ),
+            allowed_actions: pick(
+                cli.allow_actions.clone().map(Some),
+                front_matter.allowed_actions.map(Some),
+                None,
+            ),
+            enabled_flags: cli.enable.iter().cloned().collect(),
+        })
+    }
+}
+
+/// The command-line value if given, else the front matter's, else `default`.
+fn pick<T>(command_line: Option<T>, front_matter: Option<T>, default: T) -> Setting<T> {
+    match (command_line, front_matter) {
+        (Some(value), _) => Setting {
+            value,
+            source: Source::CommandLine,
+        },
+        (None, Some(value)) => Setting {
+            value,
+            source: Source::FrontMatter,
+        },
+        (None, None) => Setting {
+            value: default,
+            source: Source::Default,
+        },
+    }
+}
+
+/// Adds the template variables of `--vars-file`, then `--var` (which wins on conflicts).
+fn load_variables(cli: &Cli, variables: &mut BTreeMap<String, String>) -> Result<(), AppError> {
+    if let Some(vars_file) = &cli.vars_file {
+        let content = fs::read_to_string(vars_file).map_err(|e| {
+            AppError::Argument(format!(
+                "Cannot read vars file '{}': {}",
+                vars_file.display(),
+                e
+            ))
+        })?;
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, value) = parse_variable_assignment(line).map_err(|e| {
+                AppError::Argument(format!(
+                    "Invalid line {} in vars file '{}': {}",
+                    index + 1,
+                    vars_file.display(),
+                    e
+                ))
+            })?;
+            variables.insert(name, value);
+        }
+    }
+    variables.extend(cli.vars.iter().cloned());
+    Ok(())
+}
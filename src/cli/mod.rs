@@ -1,3 +1,4 @@
 //! Command-line interface related modules.
 pub mod args;
+pub mod config;
 pub mod output;
@@ -1,11 +1,122 @@
 //! Handles printing output like the summary.
+use crate::cli::config::RunConfig;
+use serde_json::json;
 use std::path::Path;
-use strux::Summary; // Use library's Summary
+use strux::{Action, ActionType, FileChange, ProcessReport, Summary}; // Use library's Summary
+
+/// Prints the effective run configuration and where each setting comes from.
+pub fn print_configuration(config: &RunConfig) {
+    let allowed = match &config.allowed_actions.value {
+        Some(types) => types
+            .iter()
+            .map(ActionType::label)
+            .collect::<Vec<_>>()
+            .join(", "),
+        None => "all".to_string(),
+    };
+    println!("\nConfiguration:");
+    println!(
+        "  Output directory: {} ({})",
+        config.output_dir.value.display(),
+        config.output_dir.source
+    );
+    println!(
+        "  Force:            {} ({})",
+        if config.force.value { "yes" } else { "no" },
+        config.force.source
+    );
+    println!(
+        "  Line endings:     {} ({})",
+        config.line_endings.value, config.line_endings.source
+    );
+    println!(
+        "  Allowed actions:  {} ({})",
+        allowed, config.allowed_actions.source
+    );
+    if !config.enabled_flags.is_empty() {
+        let flags: Vec<_> = config.enabled_flags.iter().map(String::as_str).collect();
+        println!("  Enabled flags:    {}", flags.join(", "));
+    }
+    if !config.substitute {
+        println!("  Variables:        off (--no-vars)");
+    } else if !config.variables.is_empty() {
+        let names: Vec<_> = config.variables.keys().map(String::as_str).collect();
+        println!("  Variables:        {}", names.join(", "));
+    }
+}
+
+/// Prints the unified diff of every previewed file change.
+pub fn print_diffs(changes: &[FileChange]) {
+    println!("{}", "-".repeat(40));
+    println!("Pending Changes (diff):");
+    if changes.is_empty() {
+        println!("  No file contents would change.");
+    }
+    for change in changes {
+        println!();
+        print!("{}", change.unified_diff());
+    }
+}
+
+/// Version of the JSON report layout; bumped on any incompatible change.
+const JSON_REPORT_VERSION: u32 = 1;
+
+/// Prints the run report as a single JSON document (see README, "JSON Report").
+pub fn print_json_report(
+    actions: &[Action],
+    report: &ProcessReport,
+    resolved_base: &Path,
+    dry_run: bool,
+) {
+    let actions_json: Vec<_> = actions
+        .iter()
+        .zip(&report.outcomes)
+        .enumerate()
+        .map(|(index, (action, outcome))| {
+            json!({
+                "index": index,
+                "action_type": action.action_type.label(),
+                "path": action.path,
+                "dest_path": action.dest_path,
+                "anchor": action.anchor,
+                "mode": action.mode.map(|mode| format!("{:03o}", mode)),
+                "original_pos": action.original_pos,
+                "source_file": action.source_file,
+                "outcome": outcome.label(),
+                "error": outcome.error().map(|error| error.to_string()),
+            })
+        })
+        .collect();
+    let summary_json: serde_json::Map<_, _> = report
+        .summary
+        .counters()
+        .iter()
+        .map(|(name, count)| (name.to_string(), json!(count)))
+        .collect();
+
+    let document = json!({
+        "version": JSON_REPORT_VERSION,
+        "dry_run": dry_run,
+        "rolled_back": report.rolled_back,
+        "journal_dir": report.journal_dir.as_ref().map(|dir| dir.display().to_string()),
+        "base_dir": resolved_base.display().to_string(),
+        "actions": actions_json,
+        "summary": summary_json,
+    });
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&document).expect("JSON report is always serializable")
+    );
+}
 
 /// Prints the final processing summary to the console.
-pub fn print_summary(summary: &Summary, resolved_base: &Path) {
+pub fn print_summary(summary: &Summary, resolved_base: &Path, dry_run: bool) {
     println!("{}", "-".repeat(40));
-    println!("Processing Summary:");
+    if dry_run {
+        println!("Dry-Run Summary (no changes were made):");
+    } else {
+        println!("Processing Summary:");
+    }
     println!(
         "  Base Directory:                     {}",
         resolved_base.display()
@@ -20,17 +131,61 @@ pub fn print_summary(summary: &Summary, resolved_base: &Path) {
         "  Files prepended:                    {}",
         summary.prepended
     ); // New
+    println!("  Files edited:                       {}", summary.edited);
+    println!("  Files patched:                      {}", summary.patched);
+    println!("  Files inserted into:                {}", summary.inserted);
+    println!(
+        "  Files inserted into (at end):       {}",
+        summary.inserted_at_end
+    );
     println!("  Files deleted:                      {}", summary.deleted);
     println!("  Files moved:                        {}", summary.moved);
+    println!("  Files copied:                       {}", summary.copied);
+    println!(
+        "  Files copied (dest overwritten):    {}",
+        summary.copied_overwritten
+    );
     println!(
         "  Files moved (dest overwritten):     {}",
         summary.moved_overwritten
     );
+    println!(
+        "  Symlinks created:                   {}",
+        summary.symlinks_created
+    );
+    println!(
+        "  Symlinks replaced (--force):        {}",
+        summary.symlinks_replaced
+    );
+    println!(
+        "  Directories created:                {}",
+        summary.dirs_created
+    );
+    println!(
+        "  Directories deleted:                {}",
+        summary.dirs_deleted
+    );
+    println!(
+        "  Directories moved:                  {}",
+        summary.dirs_moved
+    );
+    println!(
+        "  Directories merged (--force):       {}",
+        summary.dirs_merged
+    );
     println!("{}", "-".repeat(14) + " Skipped " + &"-".repeat(19));
     println!(
         "  Skipped (create, exists):           {}",
         summary.skipped_exists
     );
+    println!(
+        "  Skipped (append, already present):  {}",
+        summary.skipped_append_present
+    );
+    println!(
+        "  Skipped (prepend, already present): {}",
+        summary.skipped_prepend_present
+    );
     println!(
         "  Skipped (delete, not found):        {}",
         summary.skipped_not_found
@@ -59,6 +214,66 @@ pub fn print_summary(summary: &Summary, resolved_base: &Path) {
         "  Skipped (move, dest is dir):        {}",
         summary.skipped_move_dst_isdir
     );
+    println!(
+        "  Skipped (copy, src not found):      {}",
+        summary.skipped_copy_src_not_found
+    );
+    println!(
+        "  Skipped (copy, src is dir):         {}",
+        summary.skipped_copy_src_is_dir
+    );
+    println!(
+        "  Skipped (copy, dest exists):        {}",
+        summary.skipped_copy_dst_exists
+    );
+    println!(
+        "  Skipped (copy, dest is dir):        {}",
+        summary.skipped_copy_dst_isdir
+    );
+    println!(
+        "  Skipped (symlink, unchanged):       {}",
+        summary.skipped_symlink_unchanged
+    );
+    println!(
+        "  Skipped (dir, exists):              {}",
+        summary.skipped_dir_exists
+    );
+    println!(
+        "  Skipped (dir delete, not found):    {}",
+        summary.skipped_dir_not_found
+    );
+    println!(
+        "  Skipped (dir delete, not empty):    {}",
+        summary.skipped_dir_not_empty
+    );
+    println!(
+        "  Skipped (dir delete, not a dir):    {}",
+        summary.skipped_dir_not_dir
+    );
+    println!(
+        "  Skipped (dir move, src not found):  {}",
+        summary.skipped_dir_move_src_not_found
+    );
+    println!(
+        "  Skipped (dir move, src not a dir):  {}",
+        summary.skipped_dir_move_src_not_dir
+    );
+    println!(
+        "  Skipped (dir move, dest exists):    {}",
+        summary.skipped_dir_move_dst_exists
+    );
+    println!(
+        "  Skipped (dir move, dest is file):   {}",
+        summary.skipped_dir_move_dst_is_file
+    );
+    println!(
+        "  Skipped (dir move, into itself):    {}",
+        summary.skipped_dir_move_into_self
+    );
+    println!(
+        "  Skipped (dir merge, conflict):      {}",
+        summary.skipped_dir_merge_conflict
+    );
     println!("{}", "-".repeat(12) + " Failed/Errors " + &"-".repeat(13));
     println!(
         "  Failed (unsafe/invalid path):       {}",
@@ -80,6 +295,42 @@ pub fn print_summary(summary: &Summary, resolved_base: &Path) {
         "  Failed (create, parent is file):    {}",
         summary.failed_parent_isdir
     );
+    println!(
+        "  Failed (dir, target is file):       {}",
+        summary.failed_dir_target_is_file
+    );
+    println!(
+        "  Failed (edit, search not found):    {}",
+        summary.failed_edit_no_match
+    );
+    println!(
+        "  Failed (edit, search ambiguous):    {}",
+        summary.failed_edit_ambiguous
+    );
+    println!(
+        "  Failed (edit, invalid block):       {}",
+        summary.failed_edit_invalid
+    );
+    println!(
+        "  Failed (patch, hunks rejected):     {}",
+        summary.failed_patch
+    );
+    println!(
+        "  Failed (patch, invalid diff):       {}",
+        summary.failed_patch_invalid
+    );
+    println!(
+        "  Failed (insert, anchor not found):  {}",
+        summary.failed_insert_no_anchor
+    );
+    println!(
+        "  Failed (insert, invalid anchor):    {}",
+        summary.failed_insert_invalid_anchor
+    );
+    println!(
+        "  Failed (symlink, path exists):      {}",
+        summary.failed_symlink_exists
+    );
     println!(
         "  Failed (I/O or Path error):         {}",
         summary.failed_io
@@ -9,11 +9,48 @@ pub const ACTION_DELETED_FILE: &str = "Deleted File";
 pub const ACTION_MOVED_FILE: &str = "Moved File";
 pub const ACTION_APPEND_FILE: &str = "Append File";
 pub const ACTION_PREPEND_FILE: &str = "Prepend File";
+pub const ACTION_COPIED_FILE: &str = "Copied File";
+pub const ACTION_EDIT_FILE: &str = "Edit File";
+pub const ACTION_PATCH_FILE: &str = "Patch File";
+pub const ACTION_INSERT_AFTER: &str = "Insert After";
+pub const ACTION_INSERT_BEFORE: &str = "Insert Before";
+pub const ACTION_DIRECTORY: &str = "Directory";
+pub const ACTION_DELETED_DIRECTORY: &str = "Deleted Directory";
+pub const ACTION_MOVED_DIRECTORY: &str = "Moved Directory";
+pub const ACTION_SYMLINK: &str = "Symlink";
+pub const ACTION_INCLUDE: &str = "Include";
 pub const ACTION_CREATE: &str = "Create";
 pub const ACTION_UPDATE: &str = "Update";
 
 // --- Parsing ---
 pub const INTERNAL_COMMENT_ACTION_PREFIX: &str = "// File:";
+/// Fence info-string attribute (e.g. ```` ```rust no-eol ````) marking block content that has
+/// no final line break. Written by `pack` for files that do not end with a newline.
+pub const FENCE_ATTRIBUTE_NO_EOL: &str = "no-eol";
+/// Fence info-string attribute prefix setting the Unix permissions of the written file
+/// (e.g. ```` ```bash mode=755 ````), in octal.
+pub const FENCE_ATTRIBUTE_MODE: &str = "mode=";
+/// Fence language of a block holding base64-encoded file content (```` ```base64 ````), decoded
+/// to raw bytes before writing. Written by `pack` for files that are not valid UTF-8.
+pub const FENCE_LANG_BASE64: &str = "base64";
+/// Lines delimiting a SEARCH/REPLACE hunk in an `Edit File` block.
+pub const EDIT_SEARCH_MARKER: &str = "<<<<<<< SEARCH";
+pub const EDIT_DIVIDER_MARKER: &str = "=======";
+pub const EDIT_REPLACE_MARKER: &str = ">>>>>>> REPLACE";
+/// Fence languages of code blocks holding unified diffs. Such a block that no header
+/// claims becomes one `Patch File` action per file named in its `---`/`+++` lines.
+pub const DIFF_FENCE_LANGS: [&str; 2] = ["diff", "patch"];
+/// Separates the path from the anchor line in `Insert After`/`Insert Before` headers
+/// (`## Insert After: src/lib.rs @ /^mod /`).
+pub const INSERT_ANCHOR_SEPARATOR: &str = " @ ";
+/// How deeply `## Include:` documents may nest (a document including itself is a cycle, not
+/// a deep nesting, and is reported as such).
+pub const MAX_INCLUDE_DEPTH: usize = 16;
+
+// --- Processing ---
+/// Context lines a patch hunk may ignore on each side when it does not match exactly
+/// (the same default as GNU patch).
+pub const DEFAULT_PATCH_FUZZ: usize = 2;
 // Consider if we need // Append File: or // Prepend File: prefixes later. For now, stick to File.
 
 // Helper to build the VALID_ACTIONS_REGEX string component once.
@@ -25,6 +62,16 @@ pub static VALID_ACTIONS_REGEX_STR: Lazy<String> = Lazy::new(|| {
         ACTION_MOVED_FILE,
         ACTION_APPEND_FILE,
         ACTION_PREPEND_FILE,
+        ACTION_COPIED_FILE,
+        ACTION_EDIT_FILE,
+        ACTION_PATCH_FILE,
+        ACTION_INSERT_AFTER,
+        ACTION_INSERT_BEFORE,
+        ACTION_DIRECTORY,
+        ACTION_DELETED_DIRECTORY,
+        ACTION_MOVED_DIRECTORY,
+        ACTION_SYMLINK,
+        ACTION_INCLUDE,
         ACTION_CREATE, // Added
         ACTION_UPDATE, // Added
     ]
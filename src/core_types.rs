@@ -1,6 +1,10 @@
 //! Defines core data structures like Action, Summary, and status enums.
 
-// Removed unused ProcessError import
+use crate::constants::DEFAULT_PATCH_FUZZ;
+use crate::errors::ProcessError;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::PathBuf;
 // Removed unused Path import
 
 // --- Core Types ---
@@ -10,17 +14,113 @@ pub enum ActionType {
     Create,
     Delete,
     Move,
-    Append,  // New action type
-    Prepend, // New action type
+    Append,          // New action type
+    Prepend,         // New action type
+    CreateDirectory, // `## Directory: path`, creates an (empty) directory
+    DeleteDirectory, // `## Deleted Directory: path`, removes a directory tree
+    MoveDirectory,   // `## Moved Directory: src to dest`, moves (or merges) a directory tree
+    Copy,            // `## Copied File: src to dest`, copies a file and keeps the source
+    Edit,            // `## Edit File: path`, applies SEARCH/REPLACE hunks to an existing file
+    Patch, // `## Patch File: path` or a header-less diff block, applies unified diff hunks
+    InsertAfter, // `## Insert After: path @ anchor`, inserts content after the last matching line
+    InsertBefore, // `## Insert Before: path @ anchor`, inserts content before the first matching line
+    Symlink,      // `## Symlink: link to target`, creates a symbolic link
+    Include, // `## Include: other.md`, replaced by the other document's actions (`resolve_includes`)
+}
+
+impl ActionType {
+    /// Every action type, in declaration order.
+    pub const ALL: [ActionType; 15] = [
+        ActionType::Create,
+        ActionType::Delete,
+        ActionType::Move,
+        ActionType::Append,
+        ActionType::Prepend,
+        ActionType::CreateDirectory,
+        ActionType::DeleteDirectory,
+        ActionType::MoveDirectory,
+        ActionType::Copy,
+        ActionType::Edit,
+        ActionType::Patch,
+        ActionType::InsertAfter,
+        ActionType::InsertBefore,
+        ActionType::Symlink,
+        ActionType::Include,
+    ];
+
+    /// The action type with the given `label` (e.g. "insert_after").
+    pub fn from_label(label: &str) -> Option<ActionType> {
+        ActionType::ALL
+            .into_iter()
+            .find(|action_type| action_type.label() == label)
+    }
+
+    /// Stable lowercase name used in machine-readable reports.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ActionType::Create => "create",
+            ActionType::Delete => "delete",
+            ActionType::Move => "move",
+            ActionType::Append => "append",
+            ActionType::Prepend => "prepend",
+            ActionType::CreateDirectory => "create_directory",
+            ActionType::DeleteDirectory => "delete_directory",
+            ActionType::MoveDirectory => "move_directory",
+            ActionType::Copy => "copy",
+            ActionType::Edit => "edit",
+            ActionType::Patch => "patch",
+            ActionType::InsertAfter => "insert_after",
+            ActionType::InsertBefore => "insert_before",
+            ActionType::Symlink => "symlink",
+            ActionType::Include => "include",
+        }
+    }
+}
+
+/// The content an action carries: text as written in its block, or the decoded bytes of a
+/// `base64` block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Content {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl Content {
+    /// The content as text; `None` for binary content.
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Content::Text(text) => Some(text),
+            Content::Binary(_) => None,
+        }
+    }
+
+    /// The exact bytes to write.
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Content::Text(text) => text.as_bytes(),
+            Content::Binary(bytes) => bytes,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.as_bytes().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Action {
     pub action_type: ActionType,
-    pub path: String, // Source path for Move, target path for Create/Delete/Append/Prepend
-    pub dest_path: Option<String>, // Destination path for Move
-    pub content: Option<String>, // Content for Create/Append/Prepend
+    pub path: String, // Source path for Move/MoveDirectory/Copy, link path for Symlink, target path for the other actions
+    pub dest_path: Option<String>, // Destination path for Move/MoveDirectory/Copy, link target (as written) for Symlink
+    pub content: Option<Content>, // Content for Create/Append/Prepend/Insert (binary only for the first three), hunks for Edit/Patch
+    pub anchor: Option<String>, // Anchor line for InsertAfter/InsertBefore (literal, or `/regex/`)
+    pub mode: Option<u32>, // Unix permissions for Create/Append/Prepend (`mode=` fence attribute)
     pub original_pos: usize, // Byte offset in original markdown content
+    pub source_file: Option<String>, // Markdown input the action came from (None when parsed from a string)
 }
 
 #[derive(Debug, Default)]
@@ -47,17 +147,172 @@ pub struct Summary {
     pub failed_isdir_append: u32,  // New summary field
     pub failed_isdir_prepend: u32, // New summary field
     pub error_other: u32,
+    pub dirs_created: u32,
+    pub dirs_deleted: u32,
+    pub skipped_dir_exists: u32,
+    pub skipped_dir_not_found: u32,
+    pub skipped_dir_not_empty: u32, // Non-empty directory kept (needs --force)
+    pub skipped_dir_not_dir: u32,   // Deleted Directory target is a file
+    pub failed_dir_target_is_file: u32,
+    pub dirs_moved: u32,
+    pub dirs_merged: u32, // Moved into an existing directory (--force)
+    pub skipped_dir_move_src_not_found: u32,
+    pub skipped_dir_move_src_not_dir: u32,
+    pub skipped_dir_move_dst_exists: u32,
+    pub skipped_dir_move_dst_is_file: u32,
+    pub skipped_dir_move_into_self: u32,
+    pub skipped_dir_merge_conflict: u32, // A file and a directory would collide while merging
+    pub copied: u32,
+    pub copied_overwritten: u32,
+    pub skipped_copy_src_not_found: u32,
+    pub skipped_copy_src_is_dir: u32,
+    pub skipped_copy_dst_exists: u32,
+    pub skipped_copy_dst_isdir: u32,
+    pub edited: u32,
+    pub failed_edit_no_match: u32,  // A search text was not found
+    pub failed_edit_ambiguous: u32, // A search text was found more than once
+    pub failed_edit_invalid: u32,   // The block is not a list of SEARCH/REPLACE hunks
+    pub patched: u32,
+    pub failed_patch: u32,         // At least one hunk did not apply
+    pub failed_patch_invalid: u32, // The block is not a unified diff
+    pub inserted: u32,
+    pub inserted_at_end: u32, // Anchor not found, content appended (--force)
+    pub failed_insert_no_anchor: u32, // Anchor not found and no --force
+    pub failed_insert_invalid_anchor: u32, // The anchor is not a valid regex
+    pub skipped_append_present: u32, // File already ends with the block (--idempotent)
+    pub skipped_prepend_present: u32, // File already starts with the block (--idempotent)
+    pub symlinks_created: u32,
+    pub symlinks_replaced: u32, // An existing link pointed elsewhere (--force)
+    pub skipped_symlink_unchanged: u32, // The link already points to the target
+    pub failed_symlink_exists: u32, // Something already exists at the link path
+}
+
+impl Summary {
+    /// Whether any action ended in one of the failed/error categories.
+    pub fn has_failures(&self) -> bool {
+        self.failed_io
+            + self.failed_unsafe
+            + self.failed_isdir_create
+            + self.failed_isdir_append
+            + self.failed_isdir_prepend
+            + self.failed_parent_isdir
+            + self.error_other
+            + self.failed_dir_target_is_file
+            + self.failed_edit_no_match
+            + self.failed_edit_ambiguous
+            + self.failed_edit_invalid
+            + self.failed_patch
+            + self.failed_patch_invalid
+            + self.failed_insert_no_anchor
+            + self.failed_insert_invalid_anchor
+            + self.failed_symlink_exists
+            > 0
+    }
+
+    /// Returns every counter with its stable name, in declaration order.
+    /// The names match the outcome labels returned by `ActionOutcome::label`.
+    pub fn counters(&self) -> [(&'static str, u32); 60] {
+        [
+            ("created", self.created),
+            ("overwritten", self.overwritten),
+            ("deleted", self.deleted),
+            ("moved", self.moved),
+            ("moved_overwritten", self.moved_overwritten),
+            ("appended", self.appended),
+            ("prepended", self.prepended),
+            ("skipped_exists", self.skipped_exists),
+            ("skipped_not_found", self.skipped_not_found),
+            ("skipped_isdir_delete", self.skipped_isdir_delete),
+            ("skipped_other_type", self.skipped_other_type),
+            (
+                "skipped_move_src_not_found",
+                self.skipped_move_src_not_found,
+            ),
+            ("skipped_move_src_is_dir", self.skipped_move_src_is_dir),
+            ("skipped_move_dst_exists", self.skipped_move_dst_exists),
+            ("skipped_move_dst_isdir", self.skipped_move_dst_isdir),
+            ("failed_io", self.failed_io),
+            ("failed_isdir_create", self.failed_isdir_create),
+            ("failed_parent_isdir", self.failed_parent_isdir),
+            ("failed_unsafe", self.failed_unsafe),
+            ("failed_isdir_append", self.failed_isdir_append),
+            ("failed_isdir_prepend", self.failed_isdir_prepend),
+            ("error_other", self.error_other),
+            ("dirs_created", self.dirs_created),
+            ("dirs_deleted", self.dirs_deleted),
+            ("skipped_dir_exists", self.skipped_dir_exists),
+            ("skipped_dir_not_found", self.skipped_dir_not_found),
+            ("skipped_dir_not_empty", self.skipped_dir_not_empty),
+            ("skipped_dir_not_dir", self.skipped_dir_not_dir),
+            ("failed_dir_target_is_file", self.failed_dir_target_is_file),
+            ("dirs_moved", self.dirs_moved),
+            ("dirs_merged", self.dirs_merged),
+            (
+                "skipped_dir_move_src_not_found",
+                self.skipped_dir_move_src_not_found,
+            ),
+            (
+                "skipped_dir_move_src_not_dir",
+                self.skipped_dir_move_src_not_dir,
+            ),
+            (
+                "skipped_dir_move_dst_exists",
+                self.skipped_dir_move_dst_exists,
+            ),
+            (
+                "skipped_dir_move_dst_is_file",
+                self.skipped_dir_move_dst_is_file,
+            ),
+            (
+                "skipped_dir_move_into_self",
+                self.skipped_dir_move_into_self,
+            ),
+            (
+                "skipped_dir_merge_conflict",
+                self.skipped_dir_merge_conflict,
+            ),
+            ("copied", self.copied),
+            ("copied_overwritten", self.copied_overwritten),
+            (
+                "skipped_copy_src_not_found",
+                self.skipped_copy_src_not_found,
+            ),
+            ("skipped_copy_src_is_dir", self.skipped_copy_src_is_dir),
+            ("skipped_copy_dst_exists", self.skipped_copy_dst_exists),
+            ("skipped_copy_dst_isdir", self.skipped_copy_dst_isdir),
+            ("edited", self.edited),
+            ("failed_edit_no_match", self.failed_edit_no_match),
+            ("failed_edit_ambiguous", self.failed_edit_ambiguous),
+            ("failed_edit_invalid", self.failed_edit_invalid),
+            ("patched", self.patched),
+            ("failed_patch", self.failed_patch),
+            ("failed_patch_invalid", self.failed_patch_invalid),
+            ("inserted", self.inserted),
+            ("inserted_at_end", self.inserted_at_end),
+            ("failed_insert_no_anchor", self.failed_insert_no_anchor),
+            (
+                "failed_insert_invalid_anchor",
+                self.failed_insert_invalid_anchor,
+            ),
+            ("skipped_append_present", self.skipped_append_present),
+            ("skipped_prepend_present", self.skipped_prepend_present),
+            ("symlinks_created", self.symlinks_created),
+            ("symlinks_replaced", self.symlinks_replaced),
+            ("skipped_symlink_unchanged", self.skipped_symlink_unchanged),
+            ("failed_symlink_exists", self.failed_symlink_exists),
+        ]
+    }
 }
 
 // --- Status Enums ---
 // Used by processor helpers to signal outcomes for summary counting.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CreateStatus {
     Created,
     Overwritten,
     SkippedExists,
 }
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DeleteStatus {
     Deleted,
     SkippedNotFound,
@@ -65,7 +320,7 @@ pub enum DeleteStatus {
     SkippedOtherType,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MoveStatus {
     Moved,
     MovedOverwritten,
@@ -75,14 +330,270 @@ pub enum MoveStatus {
     SkippedDestinationIsDir,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyStatus {
+    Copied,
+    CopiedOverwritten,
+    SkippedSourceNotFound,
+    SkippedSourceIsDir,
+    SkippedDestinationExists,
+    SkippedDestinationIsDir,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditStatus {
+    Edited, // Every hunk matched exactly once and was replaced
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatchStatus {
+    Patched, // Every hunk applied
+    Created, // File did not exist; the diff only added lines (e.g. against /dev/null)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InsertStatus {
+    Inserted,      // Content was inserted next to the anchor line
+    InsertedAtEnd, // Anchor not found; content was appended to the file (--force)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AppendStatus {
-    Appended, // Content was appended to an existing file
-    Created,  // File did not exist, so it was created with the content
+    Appended,              // Content was appended to an existing file
+    Created,               // File did not exist, so it was created with the content
+    SkippedAlreadyPresent, // The file already ends with the content (--idempotent)
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PrependStatus {
-    Prepended, // Content was prepended to an existing file
-    Created,   // File did not exist, so it was created with the content
+    Prepended,             // Content was prepended to an existing file
+    Created,               // File did not exist, so it was created with the content
+    SkippedAlreadyPresent, // The file already starts with the content (--idempotent)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymlinkStatus {
+    Created,
+    Replaced,         // An existing link to another target was replaced (--force)
+    SkippedUnchanged, // The link already exists and points to the target
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectoryStatus {
+    Created,
+    SkippedExists,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeleteDirectoryStatus {
+    Deleted,
+    SkippedNotFound,
+    SkippedNotEmpty, // Directory has contents and --force was not given
+    SkippedNotADirectory,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveDirectoryStatus {
+    Moved,
+    Merged, // Destination directory existed; contents were merged into it (--force)
+    SkippedSourceNotFound,
+    SkippedSourceNotADirectory,
+    SkippedDestinationExists, // Destination directory exists and --force was not given
+    SkippedDestinationIsFile,
+    SkippedDestinationInsideSource,
+    SkippedMergeConflict, // Merging would replace a file with a directory or vice versa
+}
+
+// --- Parsing Options ---
+
+/// Options controlling how markdown is parsed.
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    pub enabled_flags: BTreeSet<String>, // Flags whose `<!-- strux:if flag -->` regions are kept (--enable)
+    pub indented_code_blocks: bool, // Headers may take a following 4-space indented block (--indented-blocks)
+}
+
+// --- Processing Options and Results ---
+
+/// Options controlling how the processor applies actions.
+#[derive(Debug, Clone)]
+pub struct ProcessOptions {
+    pub overwrite: bool,  // Overwrite existing files (--force)
+    pub dry_run: bool,    // Plan only: make every decision but never touch the filesystem
+    pub atomic: bool,     // Roll every change back if any action fails (--atomic)
+    pub journal: bool,    // Save an undo journal under the output directory (see `undo_last_run`)
+    pub fuzz: usize,      // Context lines a patch hunk may ignore on each side (--fuzz)
+    pub idempotent: bool, // Skip Append/Prepend content already at the end/start of the file
+    pub auto_exec: bool,  // Mark written files starting with a `#!` shebang executable
+}
+
+impl Default for ProcessOptions {
+    fn default() -> Self {
+        ProcessOptions {
+            overwrite: false,
+            dry_run: false,
+            atomic: false,
+            journal: false,
+            fuzz: DEFAULT_PATCH_FUZZ,
+            idempotent: false,
+            auto_exec: false,
+        }
+    }
+}
+
+/// The outcome of a single action, as reported by (or planned for) its handler.
+#[derive(Debug)]
+pub enum ActionOutcome {
+    Create(CreateStatus),
+    Delete(DeleteStatus),
+    Move(MoveStatus),
+    Append(AppendStatus),
+    Prepend(PrependStatus),
+    CreateDirectory(DirectoryStatus),
+    DeleteDirectory(DeleteDirectoryStatus),
+    MoveDirectory(MoveDirectoryStatus),
+    Copy(CopyStatus),
+    Edit(EditStatus),
+    Patch(PatchStatus),
+    Insert(InsertStatus),
+    Symlink(SymlinkStatus),
+    Failed(ProcessError),           // Validation, safety or I/O failure
+    RolledBack(Box<ActionOutcome>), // Applied, then undone because a later action failed (--atomic)
+    NotRun,                         // Never attempted: an earlier action failed (--atomic)
+}
+
+impl ActionOutcome {
+    /// Stable outcome label: the name of the `Summary` counter this outcome increments
+    /// (`rolled_back` and `not_run`, which only an `--atomic` rollback produces, have none).
+    pub fn label(&self) -> &'static str {
+        match self {
+            ActionOutcome::Create(CreateStatus::Created) => "created",
+            ActionOutcome::Create(CreateStatus::Overwritten) => "overwritten",
+            ActionOutcome::Create(CreateStatus::SkippedExists) => "skipped_exists",
+            ActionOutcome::Delete(DeleteStatus::Deleted) => "deleted",
+            ActionOutcome::Delete(DeleteStatus::SkippedNotFound) => "skipped_not_found",
+            ActionOutcome::Delete(DeleteStatus::SkippedIsDir) => "skipped_isdir_delete",
+            ActionOutcome::Delete(DeleteStatus::SkippedOtherType) => "skipped_other_type",
+            ActionOutcome::Move(MoveStatus::Moved) => "moved",
+            ActionOutcome::Move(MoveStatus::MovedOverwritten) => "moved_overwritten",
+            ActionOutcome::Move(MoveStatus::SkippedSourceNotFound) => "skipped_move_src_not_found",
+            ActionOutcome::Move(MoveStatus::SkippedSourceIsDir) => "skipped_move_src_is_dir",
+            ActionOutcome::Move(MoveStatus::SkippedDestinationExists) => "skipped_move_dst_exists",
+            ActionOutcome::Move(MoveStatus::SkippedDestinationIsDir) => "skipped_move_dst_isdir",
+            ActionOutcome::Append(AppendStatus::Appended) => "appended",
+            ActionOutcome::Append(AppendStatus::Created) => "created",
+            ActionOutcome::Prepend(PrependStatus::Prepended) => "prepended",
+            ActionOutcome::Prepend(PrependStatus::Created) => "created",
+            ActionOutcome::Append(AppendStatus::SkippedAlreadyPresent) => "skipped_append_present",
+            ActionOutcome::Prepend(PrependStatus::SkippedAlreadyPresent) => {
+                "skipped_prepend_present"
+            }
+            ActionOutcome::CreateDirectory(DirectoryStatus::Created) => "dirs_created",
+            ActionOutcome::CreateDirectory(DirectoryStatus::SkippedExists) => "skipped_dir_exists",
+            ActionOutcome::DeleteDirectory(DeleteDirectoryStatus::Deleted) => "dirs_deleted",
+            ActionOutcome::DeleteDirectory(DeleteDirectoryStatus::SkippedNotFound) => {
+                "skipped_dir_not_found"
+            }
+            ActionOutcome::DeleteDirectory(DeleteDirectoryStatus::SkippedNotEmpty) => {
+                "skipped_dir_not_empty"
+            }
+            ActionOutcome::DeleteDirectory(DeleteDirectoryStatus::SkippedNotADirectory) => {
+                "skipped_dir_not_dir"
+            }
+            ActionOutcome::MoveDirectory(status) => match status {
+                MoveDirectoryStatus::Moved => "dirs_moved",
+                MoveDirectoryStatus::Merged => "dirs_merged",
+                MoveDirectoryStatus::SkippedSourceNotFound => "skipped_dir_move_src_not_found",
+                MoveDirectoryStatus::SkippedSourceNotADirectory => "skipped_dir_move_src_not_dir",
+                MoveDirectoryStatus::SkippedDestinationExists => "skipped_dir_move_dst_exists",
+                MoveDirectoryStatus::SkippedDestinationIsFile => "skipped_dir_move_dst_is_file",
+                MoveDirectoryStatus::SkippedDestinationInsideSource => "skipped_dir_move_into_self",
+                MoveDirectoryStatus::SkippedMergeConflict => "skipped_dir_merge_conflict",
+            },
+            ActionOutcome::Copy(status) => match status {
+                CopyStatus::Copied => "copied",
+                CopyStatus::CopiedOverwritten => "copied_overwritten",
+                CopyStatus::SkippedSourceNotFound => "skipped_copy_src_not_found",
+                CopyStatus::SkippedSourceIsDir => "skipped_copy_src_is_dir",
+                CopyStatus::SkippedDestinationExists => "skipped_copy_dst_exists",
+                CopyStatus::SkippedDestinationIsDir => "skipped_copy_dst_isdir",
+            },
+            ActionOutcome::Edit(EditStatus::Edited) => "edited",
+            ActionOutcome::Patch(PatchStatus::Patched) => "patched",
+            ActionOutcome::Patch(PatchStatus::Created) => "created",
+            ActionOutcome::Insert(InsertStatus::Inserted) => "inserted",
+            ActionOutcome::Insert(InsertStatus::InsertedAtEnd) => "inserted_at_end",
+            ActionOutcome::Symlink(SymlinkStatus::Created) => "symlinks_created",
+            ActionOutcome::Symlink(SymlinkStatus::Replaced) => "symlinks_replaced",
+            ActionOutcome::Symlink(SymlinkStatus::SkippedUnchanged) => "skipped_symlink_unchanged",
+            ActionOutcome::Failed(error) => match error {
+                ProcessError::Io { .. } | ProcessError::PathResolution { .. } => "failed_io",
+                ProcessError::PathNotSafe { .. }
+                | ProcessError::ReservedPath { .. }
+                | ProcessError::InvalidPathFormat { .. } => "failed_unsafe",
+                ProcessError::TargetIsDirectory { .. } => "failed_isdir_create",
+                ProcessError::ParentIsNotDirectory { .. } => "failed_parent_isdir",
+                ProcessError::MoveSourceIsDir { .. } => "skipped_move_src_is_dir",
+                ProcessError::TargetIsDirectoryForAppend { .. } => "failed_isdir_append",
+                ProcessError::TargetIsDirectoryForPrepend { .. } => "failed_isdir_prepend",
+                ProcessError::TargetIsFile { .. } => "failed_dir_target_is_file",
+                ProcessError::EditSearchMismatch { matches: 0, .. } => "failed_edit_no_match",
+                ProcessError::EditSearchMismatch { .. } => "failed_edit_ambiguous",
+                ProcessError::InvalidEditBlock { .. } => "failed_edit_invalid",
+                ProcessError::PatchFailed { .. } => "failed_patch",
+                ProcessError::InvalidPatch { .. } => "failed_patch_invalid",
+                ProcessError::AnchorNotFound { .. } => "failed_insert_no_anchor",
+                ProcessError::InvalidAnchor { .. } => "failed_insert_invalid_anchor",
+                ProcessError::SymlinkExists { .. } | ProcessError::SymlinkPathOccupied { .. } => {
+                    "failed_symlink_exists"
+                }
+                ProcessError::UnresolvedInclude { .. }
+                | ProcessError::UnknownAction
+                | ProcessError::Internal(_) => "error_other",
+            },
+            ActionOutcome::RolledBack(_) => "rolled_back",
+            ActionOutcome::NotRun => "not_run",
+        }
+    }
+
+    /// Returns the error of a failed outcome.
+    pub fn error(&self) -> Option<&ProcessError> {
+        match self {
+            ActionOutcome::Failed(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ActionOutcome {
+    /// Short human-readable form, e.g. `Created` or `Failed: <error>`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActionOutcome::Create(status) => write!(f, "{:?}", status),
+            ActionOutcome::Delete(status) => write!(f, "{:?}", status),
+            ActionOutcome::Move(status) => write!(f, "{:?}", status),
+            ActionOutcome::Append(status) => write!(f, "{:?}", status),
+            ActionOutcome::Prepend(status) => write!(f, "{:?}", status),
+            ActionOutcome::CreateDirectory(status) => write!(f, "{:?}", status),
+            ActionOutcome::DeleteDirectory(status) => write!(f, "{:?}", status),
+            ActionOutcome::MoveDirectory(status) => write!(f, "{:?}", status),
+            ActionOutcome::Copy(status) => write!(f, "{:?}", status),
+            ActionOutcome::Edit(status) => write!(f, "{:?}", status),
+            ActionOutcome::Patch(status) => write!(f, "{:?}", status),
+            ActionOutcome::Insert(status) => write!(f, "{:?}", status),
+            ActionOutcome::Symlink(status) => write!(f, "{:?}", status),
+            ActionOutcome::Failed(error) => write!(f, "Failed: {}", error),
+            ActionOutcome::RolledBack(applied) => write!(f, "RolledBack: {}", applied),
+            ActionOutcome::NotRun => write!(f, "NotRun"),
+        }
+    }
+}
+
+/// Aggregate result of a processing run: the summary counters plus one outcome per action.
+#[derive(Debug, Default)]
+pub struct ProcessReport {
+    pub summary: Summary,
+    pub outcomes: Vec<ActionOutcome>, // Same order as the processed actions
+    pub rolled_back: bool,            // An atomic run failed and its changes were undone
+    pub journal_dir: Option<PathBuf>, // Where the undo journal for this run was saved
 }
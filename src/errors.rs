@@ -16,6 +16,10 @@ pub enum AppError {
     Io(#[from] io::Error),
     #[error("Argument error: {0}")]
     Argument(String),
+    #[error("Undo error: {0}")]
+    Undo(String),
+    #[error("Pack error: {0}")]
+    Pack(String),
 }
 
 // --- Parsing Errors ---
@@ -27,6 +31,52 @@ pub enum ParseError {
     NoClosingFence { fence: String, pos: usize },
     #[error("Invalid header format found near byte {pos}: {details}")]
     InvalidHeaderFormat { pos: usize, details: String },
+    #[error("Invalid base64 block for '{path}' near byte {pos}: {details}")]
+    InvalidBase64 {
+        pos: usize,
+        path: String,
+        details: String,
+    },
+    #[error("Unknown variable '{{{{{name}}}}}' in the action at byte {pos} (define it with --var {name}=VALUE, or write \\{{{{ for literal braces)")]
+    UnknownVariable { name: String, pos: usize },
+    #[error("Invalid front matter on line {line}: {details}")]
+    InvalidFrontMatter { line: usize, details: String },
+    #[error(
+        "Action '{action}' for '{path}' at byte {pos} is not in the document's allowed_actions"
+    )]
+    ActionNotAllowed {
+        action: &'static str,
+        path: String,
+        pos: usize,
+    },
+    #[error("Cannot read '{path}', included at byte {pos} of '{including}': {details}")]
+    IncludeNotReadable {
+        path: String,
+        including: String,
+        pos: usize,
+        details: String,
+    },
+    #[error("Include cycle: {cycle}")]
+    IncludeCycle { cycle: String },
+    #[error("Includes nested more than {limit} levels deep at '{path}' (included at byte {pos} of '{including}')")]
+    IncludeTooDeep {
+        path: String,
+        including: String,
+        pos: usize,
+        limit: usize,
+    },
+    #[error("In included file '{file}': {source}")]
+    InIncludedFile {
+        file: String,
+        source: Box<ParseError>,
+    },
+    #[error("Invalid conditional marker at byte {pos}: {details}")]
+    InvalidConditional { pos: usize, details: String },
+    #[error(
+        "Conditional region(s) opened at byte(s) {} never closed with '<!-- strux:endif -->'",
+        join_positions(.positions)
+    )]
+    UnclosedConditional { positions: Vec<usize> },
     #[error("Internal logic error during parsing: {0}")]
     Internal(String),
     #[error("I/O error during parsing (unexpected): {0}")]
@@ -52,6 +102,8 @@ pub enum ProcessError {
         resolved_path: PathBuf,
         base_path: PathBuf,
     },
+    #[error("Cannot change '{path}': paths under '.strux/' are reserved for undo journals")]
+    ReservedPath { path: PathBuf },
     #[error("Invalid path format detected for '{path}': Contains empty components (e.g., '//')")]
     InvalidPathFormat { path: String },
     #[error("Cannot perform operation. Target path '{path}' exists and is a directory.")]
@@ -66,8 +118,79 @@ pub enum ProcessError {
     TargetIsDirectoryForAppend { path: PathBuf }, // New error for Append
     #[error("Cannot prepend to path '{path}' because it exists and is a directory.")]
     TargetIsDirectoryForPrepend { path: PathBuf }, // New error for Prepend
+    #[error("Cannot create directory '{path}' because it exists and is not a directory.")]
+    TargetIsFile { path: PathBuf },
+    #[error(
+        "Cannot edit '{path}': the search text of hunk {hunk} {}",
+        describe_matches(*.matches)
+    )]
+    EditSearchMismatch {
+        path: PathBuf,
+        hunk: usize,    // 1-based, in block order
+        matches: usize, // 0 = not found, more than 1 = ambiguous
+    },
+    #[error("Invalid edit block for '{path}': {details}")]
+    InvalidEditBlock { path: PathBuf, details: String },
+    #[error(
+        "Cannot patch '{path}': {} of {total_hunks} hunk(s) did not apply (#{})",
+        .failed_hunks.len(),
+        join_numbers(.failed_hunks)
+    )]
+    PatchFailed {
+        path: PathBuf,
+        failed_hunks: Vec<usize>, // 1-based, in block order
+        total_hunks: usize,
+    },
+    #[error("Invalid patch block for '{path}': {details}")]
+    InvalidPatch { path: PathBuf, details: String },
+    #[error(
+        "Cannot insert into '{path}': no line matches the anchor '{anchor}' (use --force to insert at the end of the file)"
+    )]
+    AnchorNotFound { path: PathBuf, anchor: String },
+    #[error("Invalid anchor '{anchor}' for '{path}': {details}")]
+    InvalidAnchor {
+        path: PathBuf,
+        anchor: String,
+        details: String,
+    },
+    #[error(
+        "Cannot create symlink '{path}': it already exists and points to '{}' (use --force to replace it)",
+        .target.display()
+    )]
+    SymlinkExists { path: PathBuf, target: PathBuf },
+    #[error("Cannot create symlink '{path}': a {kind} already exists at that path")]
+    SymlinkPathOccupied { path: PathBuf, kind: &'static str },
+    #[error("Cannot process the include of '{path}': includes must be expanded with resolve_includes before processing")]
+    UnresolvedInclude { path: String },
     #[error("Unknown action type encountered")]
     UnknownAction, // Should not happen if parsing is correct
     #[error("Unexpected internal error: {0}")]
     Internal(String),
 }
+
+/// Explains why a search text did not match exactly once.
+fn describe_matches(matches: usize) -> String {
+    if matches == 0 {
+        "was not found".to_string()
+    } else {
+        format!("matches {} times (it must match exactly once)", matches)
+    }
+}
+
+/// Formats byte offsets as `12, 80`.
+fn join_positions(positions: &[usize]) -> String {
+    positions
+        .iter()
+        .map(usize::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Formats hunk numbers as `1, 3`.
+fn join_numbers(numbers: &[usize]) -> String {
+    numbers
+        .iter()
+        .map(usize::to_string)
+        .collect::<Vec<_>>()
+        .join(", #")
+}
@@ -3,22 +3,39 @@
 //! Core library for parsing markdown and processing file actions.
 
 // Declare modules following the refactored structure
+pub mod checker;
 pub mod constants;
 pub mod core_types;
 pub mod errors;
+pub mod packer;
 pub mod parser;
 pub mod processor;
+pub mod reporter;
+pub mod settings;
+pub mod variables;
 
 // Re-export essential types/functions for easier use by the binary crate (main.rs)
 // or potentially other consumers of this library.
+pub use checker::{check_actions, CheckEntry, CheckReport, CheckStatus, Expectation};
 pub use constants::*;
 pub use core_types::{
-    Action, ActionType, AppendStatus, CreateStatus, DeleteStatus, MoveStatus, PrependStatus,
-    Summary,
+    Action, ActionOutcome, ActionType, AppendStatus, Content, CopyStatus, CreateStatus,
+    DeleteDirectoryStatus, DeleteStatus, DirectoryStatus, EditStatus, InsertStatus,
+    MoveDirectoryStatus, MoveStatus, ParseOptions, PatchStatus, PrependStatus, ProcessOptions,
+    ProcessReport, Summary, SymlinkStatus,
 }; // Added AppendStatus, PrependStatus
 pub use errors::{AppError, ParseError, ProcessError};
-pub use parser::parse_markdown;
-pub use processor::process_actions;
+pub use packer::{pack_directory, PackOptions};
+pub use parser::{
+    parse_document_with, parse_markdown, parse_markdown_with_reporter, resolve_includes,
+};
+pub use processor::{
+    plan_actions, preview_actions, preview_actions_with, process_actions, process_actions_with,
+    undo_last_run, ChangeKind, FileChange, Preview, UndoReport,
+};
+pub use reporter::{ConsoleReporter, Event, NullReporter, Reporter, Verbosity};
+pub use settings::{apply_line_endings, check_allowed_actions, FrontMatter, LineEndings};
+pub use variables::{parse_variable_assignment, substitute_variables, unescape_placeholders};
 
 // Note: Specific functions within submodules (like process_create) are typically
 // kept internal to the library (pub(crate) or private) unless intended for direct use.
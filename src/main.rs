@@ -1,38 +1,52 @@
 use clap::Parser;
 use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
 // Use the library's public interface
-use strux::{parse_markdown, process_actions, AppError, Summary};
+use strux::{
+    apply_line_endings, check_actions, check_allowed_actions, pack_directory, parse_document_with,
+    preview_actions_with, process_actions_with, resolve_includes, substitute_variables,
+    undo_last_run, unescape_placeholders, Action, AppError, CheckStatus, ConsoleReporter, Event,
+    FrontMatter, PackOptions, ParseError, ParseOptions, ProcessOptions, ProcessReport, Reporter,
+    Verbosity,
+};
 
 // Modules defined within the binary crate
 mod cli;
-use cli::args::Cli; // Import the argument parser struct
-use cli::output::print_summary; // Import the summary printing function
+use cli::args::{Cli, Command, OutputFormat}; // Import the argument parser struct
+use cli::config::RunConfig;
+use cli::output::{print_configuration, print_diffs, print_json_report, print_summary}; // Import the output printing functions
 
 // --- Main Execution Logic ---
 
 /// Orchestrates the entire process: parsing args, reading files, calling library, printing summary.
-fn run() -> Result<Summary, AppError> {
-    let cli = Cli::parse(); // Now the Parser trait is in scope, so parse() is found
-
-    // Resolve markdown file path for clearer error messages
-    let resolved_md_path = cli.markdown_file.canonicalize().map_err(AppError::Io)?;
-
-    println!("Reading markdown file: {}", resolved_md_path.display());
-    let markdown_content = fs::read_to_string(&resolved_md_path)?;
+fn run(cli: &Cli) -> Result<ProcessReport, AppError> {
+    // The JSON report must be the only thing this binary prints itself
+    let human = cli.format == OutputFormat::Human;
+    // Progress messages are shown from the default verbosity up; --quiet keeps errors and the summary
+    let chatty = human && cli.verbosity() >= Verbosity::Normal;
+    // Library diagnostics keep stdout free for the JSON report
+    let reporter = if human {
+        ConsoleReporter::with_verbosity(cli.verbosity())
+    } else {
+        ConsoleReporter::with_verbosity(cli.verbosity()).stderr_only()
+    };
 
-    println!("\nParsing markdown for file actions...");
-    let parsed_actions = parse_markdown(&markdown_content)?; // Use lib function
+    let (parsed_actions, has_markup, config) =
+        parse_inputs(cli, &cli.markdown_files, chatty, &reporter)?;
+    if chatty {
+        print_configuration(&config);
+    }
+    let output_dir = &config.output_dir.value;
 
     // Check if actions were found and print appropriate message
-    if parsed_actions.is_empty() {
+    if !chatty {
+        // Progress messages are skipped; the summary or report below covers the empty case
+    } else if parsed_actions.is_empty() {
         // Basic check if content might have had actionable items
-        if markdown_content.contains("```")
-            || markdown_content.contains("//")
-            || markdown_content.contains("**")
-            || markdown_content.contains("##")
-        {
+        if has_markup {
             // CHANGE: Use println! instead of eprintln! for this warning
             println!("\nWarning: No valid actions extracted. Check formatting.");
         } else {
@@ -46,28 +60,282 @@ fn run() -> Result<Summary, AppError> {
             "\nFound {} actions to process (sorted by document order).",
             parsed_actions.len()
         );
+        if cli.dry_run && !cli.diff {
+            println!("{}", "-".repeat(40));
+            println!("Planned Actions (dry run):");
+        }
     }
 
     // Process actions using the library function (will do nothing if actions is empty)
-    let summary = process_actions(&cli.output_dir, parsed_actions, cli.force)?;
+    // A dry run plans the same actions without touching the filesystem.
+    let options = ProcessOptions {
+        overwrite: config.force.value,
+        dry_run: cli.dry_run,
+        atomic: cli.atomic,
+        journal: !cli.no_journal,
+        fuzz: cli.fuzz,
+        idempotent: cli.idempotent,
+        auto_exec: cli.auto_exec,
+    };
+    let report = if cli.diff {
+        let preview =
+            preview_actions_with(output_dir, parsed_actions.clone(), &options, &reporter)?;
+        print_diffs(&preview.changes);
+        preview.report
+    } else {
+        // Each planned outcome is printed by the reporter as it is decided
+        process_actions_with(output_dir, parsed_actions.clone(), &options, &reporter)?
+    };
 
     // Print summary needs the *resolved* base path for display
     // Resolve again for printing; process_actions resolves internally for safety.
     // Use original path if canonicalize fails (e.g., dir deleted during processing).
-    let resolved_output_dir_display = cli.output_dir.canonicalize().unwrap_or(cli.output_dir);
-    // Call the imported print_summary function - THIS WILL NOW ALWAYS RUN
-    print_summary(&summary, &resolved_output_dir_display);
+    let resolved_output_dir_display = output_dir.canonicalize().unwrap_or(output_dir.clone());
+    match cli.format {
+        // Call the imported print_summary function - THIS WILL NOW ALWAYS RUN
+        OutputFormat::Human => print_summary(
+            &report.summary,
+            &resolved_output_dir_display,
+            cli.dry_run || cli.diff,
+        ),
+        OutputFormat::Json => print_json_report(
+            &parsed_actions,
+            &report,
+            &resolved_output_dir_display,
+            cli.dry_run,
+        ),
+    }
+
+    Ok(report)
+}
+
+/// Reads and parses every markdown input in order, merging their actions and front matter.
+/// Includes are expanded and each action records the file it came from; then the run configuration is resolved and
+/// applied: template variables are substituted (if any are defined), line endings converted
+/// and the allowed action types enforced. Also returns whether any input looked like it
+/// should have contained actions.
+fn parse_inputs(
+    cli: &Cli,
+    markdown_files: &[PathBuf],
+    chatty: bool,
+    reporter: &dyn Reporter,
+) -> Result<(Vec<Action>, bool, RunConfig), AppError> {
+    // Standard input can only be consumed once
+    let stdin_inputs = markdown_files.iter().filter(|path| is_stdin(path)).count();
+    if stdin_inputs > 1 {
+        return Err(AppError::Argument(
+            "Standard input ('-') can only be given once".to_string(),
+        ));
+    }
+
+    // Parse every input in order, remembering where each action came from
+    let parse_options = ParseOptions {
+        enabled_flags: cli.enable.iter().cloned().collect(),
+        indented_code_blocks: cli.indented_blocks,
+    };
+    let mut parsed_actions = Vec::new();
+    let mut front_matter = FrontMatter::default();
+    let mut has_markup = false; // Whether any input looked like it should contain actions
+    for md_path in markdown_files {
+        let markdown_content = read_markdown(md_path, chatty)?;
+        has_markup |= markdown_content.contains("```")
+            || markdown_content.contains("//")
+            || markdown_content.contains("**")
+            || markdown_content.contains("##");
+
+        if chatty {
+            println!("\nParsing markdown for file actions...");
+        }
+        let source_file = if is_stdin(md_path) {
+            "<stdin>".to_string()
+        } else {
+            md_path.display().to_string()
+        };
+        let (mut actions, document_settings) =
+            parse_document_with(&markdown_content, &parse_options, reporter).inspect_err(|_| {
+                reporter.report(&Event::Error(format!("Could not parse '{}'", source_file)))
+            })?; // Use lib function
+        for action in &mut actions {
+            action.source_file = Some(source_file.clone());
+        }
+        // Included actions take the directive's place and record their own file
+        let document = (!is_stdin(md_path)).then_some(md_path.as_path());
+        let mut actions = resolve_includes(actions, document, &parse_options, reporter)
+            .inspect_err(|_| {
+                reporter.report(&Event::Error(format!("Could not parse '{}'", source_file)))
+            })?;
+        front_matter.merge(document_settings); // Later inputs win
+        parsed_actions.append(&mut actions);
+    }
+
+    // Settings apply once every input is known; errors name the file an action came from
+    let config = RunConfig::resolve(cli, front_matter)?;
+    for actions in parsed_actions.chunk_by_mut(|a, b| a.source_file == b.source_file) {
+        let source_file = actions[0].source_file.clone().unwrap_or_default();
+        apply_config(actions, &config).inspect_err(|_| {
+            reporter.report(&Event::Error(format!("Could not parse '{}'", source_file)))
+        })?;
+    }
+
+    Ok((parsed_actions, has_markup, config))
+}
+
+/// Applies the document-level settings of the run configuration to the actions of one file.
+fn apply_config(actions: &mut [Action], config: &RunConfig) -> Result<(), ParseError> {
+    // Without variables, '{{' is ordinary text (e.g. GitHub Actions expressions) and only
+    // '\{{' escapes are processed; --no-vars leaves both alone
+    if config.substitute {
+        if config.variables.is_empty() {
+            unescape_placeholders(actions);
+        } else {
+            substitute_variables(actions, &config.variables)?;
+        }
+    }
+    apply_line_endings(actions, config.line_endings.value);
+    match &config.allowed_actions.value {
+        Some(allowed) => check_allowed_actions(actions, allowed),
+        None => Ok(()),
+    }
+}
+
+/// Whether a markdown input argument refers to standard input.
+fn is_stdin(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Reads one markdown input, from standard input for '-'.
+fn read_markdown(md_path: &Path, chatty: bool) -> Result<String, AppError> {
+    if is_stdin(md_path) {
+        if chatty {
+            println!("Reading markdown from standard input");
+        }
+        let mut markdown_content = String::new();
+        io::stdin().read_to_string(&mut markdown_content)?;
+        return Ok(markdown_content);
+    }
+    // Resolve markdown file path for clearer error messages
+    let resolved_md_path = md_path.canonicalize().map_err(AppError::Io)?;
+    if chatty {
+        println!("Reading markdown file: {}", resolved_md_path.display());
+    }
+    Ok(fs::read_to_string(&resolved_md_path)?)
+}
 
-    Ok(summary)
+/// Runs `strux undo`: reverts the most recent run recorded in the output directory.
+fn run_undo(cli: &Cli) -> ExitCode {
+    let reporter = ConsoleReporter::with_verbosity(cli.verbosity());
+    match undo_last_run(&cli.output_dir(), &reporter) {
+        Ok(report) if report.failed > 0 => {
+            eprintln!(
+                "\nError: Undo incomplete: {} path(s) could not be restored. The journal was kept: {}",
+                report.failed,
+                report.journal_dir.display()
+            );
+            ExitCode::FAILURE
+        }
+        Ok(report) => {
+            println!(
+                "Undo complete: {} path(s) restored from {}",
+                report.restored,
+                report.journal_dir.display()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("\nError: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Runs `strux check`: compares the output directory with the documents, read-only.
+fn run_check(cli: &Cli, markdown_files: &[PathBuf]) -> ExitCode {
+    let verbosity = cli.verbosity();
+    let reporter = ConsoleReporter::with_verbosity(verbosity);
+    let result =
+        parse_inputs(cli, markdown_files, false, &reporter).and_then(|(actions, _, config)| {
+            check_actions(&config.output_dir.value, &actions, cli.fuzz, &reporter)
+        });
+    let report = match result {
+        Ok(report) => report,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for entry in &report.entries {
+        if entry.status != CheckStatus::Match {
+            println!("  FAIL  {} ({})", entry.path, entry.status);
+        } else if verbosity >= Verbosity::Normal {
+            println!("  ok    {}", entry.path);
+        }
+    }
+    let mismatches = report.mismatches();
+    if mismatches == 0 {
+        println!(
+            "Check passed: all {} path(s) match the document.",
+            report.entries.len()
+        );
+        ExitCode::SUCCESS
+    } else {
+        println!(
+            "Check failed: {} of {} path(s) do not match the document.",
+            mismatches,
+            report.entries.len()
+        );
+        ExitCode::FAILURE
+    }
+}
+
+/// Runs `strux pack`: prints the markdown for a directory tree to stdout.
+fn run_pack(cli: &Cli, dir: &Path, options: &PackOptions) -> ExitCode {
+    // Stdout holds only the document, so it can be redirected to a file
+    let reporter = ConsoleReporter::with_verbosity(cli.verbosity()).stderr_only();
+    match pack_directory(dir, options, &reporter) {
+        Ok(document) => {
+            print!("{}", document);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
 }
 
 // --- Entry Point ---
 
 /// Main application entry point. Calls `run` and handles errors.
 fn main() -> ExitCode {
-    match run() {
+    let cli = Cli::parse(); // Now the Parser trait is in scope, so parse() is found
+    match &cli.command {
+        Some(Command::Undo) => return run_undo(&cli),
+        Some(Command::Check { markdown_files }) => return run_check(&cli, markdown_files),
+        Some(Command::Pack {
+            dir,
+            include,
+            exclude,
+        }) => {
+            let options = PackOptions {
+                include: include.clone(),
+                exclude: exclude.clone(),
+            };
+            return run_pack(&cli, dir, &options);
+        }
+        None => {}
+    }
+    match run(&cli) {
+        Ok(report) if report.rolled_back => {
+            if cli.format == OutputFormat::Human {
+                eprintln!("\nError: An action failed; all changes were rolled back (--atomic).");
+            }
+            ExitCode::FAILURE
+        }
         Ok(_) => {
-            println!("\nProject file processing completed successfully.");
+            if cli.format == OutputFormat::Human && cli.verbosity() >= Verbosity::Normal {
+                println!("\nProject file processing completed successfully.");
+            }
             ExitCode::SUCCESS
         }
         Err(err) => {
@@ -82,6 +350,8 @@ fn main() -> ExitCode {
                 AppError::Argument(arg_err) => {
                     eprintln!("  Stage: Arguments\n  Details: {}", arg_err)
                 }
+                AppError::Undo(undo_err) => eprintln!("  Stage: Undo\n  Details: {}", undo_err),
+                AppError::Pack(pack_err) => eprintln!("  Stage: Pack\n  Details: {}", pack_err),
             }
             ExitCode::FAILURE
         }
@@ -0,0 +1,293 @@
+//! Generates a Strux markdown document from an existing directory tree (`strux pack`).
+//!
+//! Every file becomes a `## File: path` header followed by a fenced block. The output is
+//! parsed back before it is returned, so a packed document always recreates the tree exactly.
+//! Executable files carry their permissions as a `mode=NNN` fence attribute, and files that
+//! are not valid UTF-8 are emitted as `base64` blocks. Every `{{` is written as `\{{`, so
+//! template placeholders in packed files survive a run with or without variables, and lines
+//! that look like `<!-- strux:... -->` markers are escaped the same way.
+
+use crate::constants::{FENCE_ATTRIBUTE_MODE, FENCE_ATTRIBUTE_NO_EOL, FENCE_LANG_BASE64};
+use crate::core_types::{ActionType, Content, ParseOptions};
+use crate::errors::AppError;
+use crate::parser::conditionals::escape_marker_lines;
+use crate::parser::parse_document_with;
+use crate::reporter::{Event, NullReporter, Reporter};
+use crate::settings::FrontMatter;
+use crate::variables::unescape_placeholders;
+use base64::prelude::{Engine, BASE64_STANDARD};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::fs;
+use std::path::Path;
+
+mod language;
+#[cfg(test)]
+mod packer_tests;
+
+/// Directory names that are never packed (version control data and undo journals).
+const ALWAYS_SKIPPED_DIRS: [&str; 2] = [".git", ".strux"];
+
+/// Characters per line of an encoded `base64` block (the MIME line length).
+const BASE64_LINE_WIDTH: usize = 76;
+
+/// Options controlling which files `pack_directory` includes.
+#[derive(Debug, Clone, Default)]
+pub struct PackOptions {
+    /// Only pack files whose relative path matches one of these globs (all files if empty).
+    pub include: Vec<String>,
+    /// Skip files and directories whose relative path matches one of these globs.
+    pub exclude: Vec<String>,
+}
+
+/// A file selected for packing.
+struct PackedFile {
+    relative: String,  // Always '/'-separated
+    content: Content,  // Binary for files that are not valid UTF-8
+    mode: Option<u32>, // Only recorded for executable files
+}
+
+/// Walks `root` and returns a markdown document that recreates its files.
+/// Files are emitted in sorted path order. Symlinks are skipped with a warning; empty
+/// directories cannot be represented and are left out.
+pub fn pack_directory(
+    root: &Path,
+    options: &PackOptions,
+    reporter: &dyn Reporter,
+) -> Result<String, AppError> {
+    if !root.is_dir() {
+        return Err(AppError::Argument(format!(
+            "Cannot pack '{}': not a directory",
+            root.display()
+        )));
+    }
+    let include = build_glob_set(&options.include)?;
+    let exclude = build_glob_set(&options.exclude)?;
+
+    let mut files = Vec::new();
+    collect_files(root, "", &include, &exclude, &mut files, reporter)?;
+    reporter.report(&Event::Info(format!(
+        "Packing {} files from '{}'",
+        files.len(),
+        root.display()
+    )));
+
+    let document = render_document(&files);
+    verify_round_trip(&document, &files)?;
+    Ok(document)
+}
+
+/// Compiles glob patterns; `None` when there are none.
+fn build_glob_set(patterns: &[String]) -> Result<Option<GlobSet>, AppError> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern).map_err(|e| {
+            AppError::Argument(format!("Invalid glob pattern '{}': {}", pattern, e))
+        })?;
+        builder.add(glob);
+    }
+    builder
+        .build()
+        .map(Some)
+        .map_err(|e| AppError::Argument(format!("Invalid glob patterns: {}", e)))
+}
+
+/// Recursively collects packable files below `dir` in sorted order.
+fn collect_files(
+    dir: &Path,
+    relative_dir: &str,
+    include: &Option<GlobSet>,
+    exclude: &Option<GlobSet>,
+    files: &mut Vec<PackedFile>,
+    reporter: &dyn Reporter,
+) -> Result<(), AppError> {
+    let mut entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            reporter.report(&Event::Warning(format!(
+                "Skipping '{}': file name is not valid UTF-8.",
+                entry.path().display()
+            )));
+            continue;
+        };
+        let relative = if relative_dir.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", relative_dir, name)
+        };
+        if exclude.as_ref().is_some_and(|set| set.is_match(&relative)) {
+            reporter.report(&Event::Decision(format!("Excluded: {}", relative)));
+            continue;
+        }
+
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if ALWAYS_SKIPPED_DIRS.contains(&name) {
+                continue;
+            }
+            collect_files(&entry.path(), &relative, include, exclude, files, reporter)?;
+        } else if file_type.is_file() {
+            if include.as_ref().is_some_and(|set| !set.is_match(&relative)) {
+                continue;
+            }
+            let content = match String::from_utf8(fs::read(entry.path())?) {
+                Ok(text) => Content::Text(text),
+                Err(e) => {
+                    reporter.report(&Event::Decision(format!(
+                        "Packing '{}' as base64: not valid UTF-8 text.",
+                        relative
+                    )));
+                    Content::Binary(e.into_bytes())
+                }
+            };
+            files.push(PackedFile {
+                relative,
+                content,
+                mode: executable_mode(&entry.metadata()?),
+            });
+        } else {
+            reporter.report(&Event::Warning(format!(
+                "Skipping '{}': not a regular file.",
+                relative
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Renders the packed files as a Strux markdown document.
+fn render_document(files: &[PackedFile]) -> String {
+    // One fence for the whole document, longer than any backtick run in any file, so no
+    // content line can close a block early or be mistaken for a closer of another block.
+    let fence = "`"
+        .repeat(longest_backtick_run(files) + 1)
+        .max("```".to_string());
+
+    let mut document = String::new();
+    for file in files {
+        let body = match &file.content {
+            Content::Text(text) => escape_placeholders(&escape_marker_lines(text)),
+            Content::Binary(bytes) => encode_base64(bytes),
+        };
+        let mut info = match file.content {
+            Content::Text(_) => language::language_for(Path::new(&file.relative)).to_string(),
+            Content::Binary(_) => FENCE_LANG_BASE64.to_string(),
+        };
+        if let Some(mode) = file.mode {
+            if !info.is_empty() {
+                info.push(' ');
+            }
+            info.push_str(&format!("{}{:03o}", FENCE_ATTRIBUTE_MODE, mode));
+        }
+        let needs_eol = !body.is_empty() && !body.ends_with('\n');
+        if needs_eol {
+            if !info.is_empty() {
+                info.push(' ');
+            }
+            info.push_str(FENCE_ATTRIBUTE_NO_EOL);
+        }
+
+        if !document.is_empty() {
+            document.push('\n');
+        }
+        document.push_str(&format!("## File: {}\n", header_path(&file.relative)));
+        document.push_str(&format!("{}{}\n", fence, info));
+        document.push_str(&body);
+        if needs_eol {
+            document.push('\n');
+        }
+        document.push_str(&format!("{}\n", fence));
+    }
+    document
+}
+
+/// Encodes binary content as base64 lines of `BASE64_LINE_WIDTH` characters.
+fn encode_base64(bytes: &[u8]) -> String {
+    let encoded = BASE64_STANDARD.encode(bytes);
+    let mut lines = String::with_capacity(encoded.len() + encoded.len() / BASE64_LINE_WIDTH + 1);
+    for line in encoded.as_bytes().chunks(BASE64_LINE_WIDTH) {
+        lines.push_str(std::str::from_utf8(line).expect("base64 output is ASCII"));
+        lines.push('\n');
+    }
+    lines
+}
+
+/// The permissions of a file with any executable bit set.
+#[cfg(unix)]
+fn executable_mode(metadata: &fs::Metadata) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = metadata.permissions().mode() & 0o7777;
+    (mode & 0o111 != 0).then_some(mode)
+}
+
+#[cfg(not(unix))]
+fn executable_mode(_metadata: &fs::Metadata) -> Option<u32> {
+    None
+}
+
+/// Length of the longest run of consecutive backticks in any text file (base64 has none).
+fn longest_backtick_run(files: &[PackedFile]) -> usize {
+    files
+        .iter()
+        .filter_map(|file| file.content.as_text())
+        .flat_map(|text| text.split(|c| c != '`'))
+        .map(str::len)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Paths with spaces are wrapped in backticks so the header keeps them intact.
+fn header_path(relative: &str) -> String {
+    let relative = escape_placeholders(relative);
+    if relative.contains(char::is_whitespace) {
+        format!("`{}`", relative)
+    } else {
+        relative
+    }
+}
+
+/// Writes every `{{` as `\{{`, which a run turns back into `{{` instead of a placeholder.
+fn escape_placeholders(text: &str) -> String {
+    text.replace("{{", "\\{{")
+}
+
+/// Parses the document back, with the same steps a run without flags applies after parsing,
+/// and checks that it recreates exactly the packed files.
+fn verify_round_trip(document: &str, files: &[PackedFile]) -> Result<(), AppError> {
+    let (mut actions, front_matter) =
+        parse_document_with(document, &ParseOptions::default(), &NullReporter)?;
+    if front_matter != FrontMatter::default() {
+        return Err(AppError::Pack(
+            "the packed document would be read with front matter".to_string(),
+        ));
+    }
+    // No variables are defined and line endings are kept as written
+    unescape_placeholders(&mut actions);
+    let mismatch = files
+        .iter()
+        .zip(&actions)
+        .find(|(file, action)| {
+            action.action_type != ActionType::Create
+                || action.path != file.relative
+                || action.content.as_ref() != Some(&file.content)
+                || action.mode != file.mode
+        })
+        .map(|(file, _)| file.relative.as_str());
+    match mismatch {
+        None if actions.len() == files.len() => Ok(()),
+        None => Err(AppError::Pack(format!(
+            "the packed document yields {} actions for {} files",
+            actions.len(),
+            files.len()
+        ))),
+        Some(relative) => Err(AppError::Pack(format!(
+            "'{}' would not be recreated exactly; exclude it and try again",
+            relative
+        ))),
+    }
+}
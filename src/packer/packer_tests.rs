@@ -0,0 +1,101 @@
+//! Unit tests for the packer's document rendering.
+
+use super::language::language_for;
+use super::{longest_backtick_run, render_document, PackedFile};
+use crate::core_types::Content;
+use std::path::Path;
+
+fn file(relative: &str, content: &str) -> PackedFile {
+    PackedFile {
+        relative: relative.to_string(),
+        content: Content::Text(content.to_string()),
+        mode: None,
+    }
+}
+
+#[test]
+fn test_render_uses_three_backticks_by_default() {
+    let document = render_document(&[file("src/main.rs", "fn main() {}\n")]);
+    assert_eq!(
+        document,
+        "## File: src/main.rs\n```rust\nfn main() {}\n```\n"
+    );
+}
+
+#[test]
+fn test_render_fence_outgrows_nested_backticks() {
+    let files = [file("README.md", "# Demo\n````bash\nls\n````\n")];
+    assert_eq!(longest_backtick_run(&files), 4);
+    let document = render_document(&files);
+    assert!(document.starts_with("## File: README.md\n`````markdown\n"));
+    assert!(document.ends_with("\n`````\n"));
+}
+
+#[test]
+fn test_render_marks_missing_final_newline() {
+    let document = render_document(&[file("a.txt", "no newline")]);
+    assert_eq!(document, "## File: a.txt\n```no-eol\nno newline\n```\n");
+}
+
+#[test]
+fn test_render_marks_executable_mode() {
+    let mut script = file("run.sh", "#!/bin/sh\necho hi");
+    script.mode = Some(0o755);
+    let document = render_document(&[script]);
+    assert_eq!(
+        document,
+        "## File: run.sh\n```bash mode=755 no-eol\n#!/bin/sh\necho hi\n```\n"
+    );
+}
+
+#[test]
+fn test_render_encodes_binary_files_as_base64_lines() {
+    let mut icon = file("icon.bin", "");
+    icon.content = Content::Binary(vec![0xFF; 60]);
+    let document = render_document(&[icon]);
+    let encoded = "/".repeat(80);
+    assert_eq!(
+        document,
+        format!(
+            "## File: icon.bin\n```base64\n{}\n{}\n```\n",
+            &encoded[..76],
+            &encoded[76..]
+        )
+    );
+}
+
+#[test]
+fn test_render_quotes_paths_with_spaces() {
+    let document = render_document(&[file("my notes.txt", "x\n")]);
+    assert!(document.starts_with("## File: `my notes.txt`\n"));
+}
+
+#[test]
+fn test_render_escapes_placeholder_braces() {
+    let document = render_document(&[file("{{name}}.txt", "X: ${{ github.ref }}\n")]);
+    assert_eq!(
+        document,
+        "## File: \\{{name}}.txt\n```\nX: $\\{{ github.ref }}\n```\n"
+    );
+}
+
+#[test]
+fn test_render_escapes_marker_lines() {
+    let document = render_document(&[file(
+        "notes.txt",
+        "<!-- strux:if docker -->\n  \\<!-- strux:endif -->\nsee <!-- strux:if x -->\n",
+    )]);
+    assert_eq!(
+        document,
+        "## File: notes.txt\n```\n\\<!-- strux:if docker -->\n  \\\\<!-- strux:endif -->\nsee <!-- strux:if x -->\n```\n"
+    );
+}
+
+#[test]
+fn test_language_for_common_files() {
+    assert_eq!(language_for(Path::new("src/lib.rs")), "rust");
+    assert_eq!(language_for(Path::new("app/main.PY")), "python");
+    assert_eq!(language_for(Path::new("Dockerfile")), "dockerfile");
+    assert_eq!(language_for(Path::new("config.yml")), "yaml");
+    assert_eq!(language_for(Path::new("LICENSE")), "");
+}
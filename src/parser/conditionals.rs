@@ -0,0 +1,216 @@
+//! Conditional regions: `<!-- strux:if flag -->` ... `<!-- strux:else -->` ...
+//! `<!-- strux:endif -->` lines around parts of a document.
+//!
+//! Regions are resolved before the passes run: marker lines and the lines of disabled regions
+//! are removed, so nothing inside a disabled region (headers, fences, content lines) is seen
+//! by the passes. Markers are whole lines and also work inside code blocks, making single
+//! lines of a file optional; a file that needs a literal marker line writes it as
+//! `\<!-- strux:... -->`, which is kept with the backslash removed. The kept text is mapped
+//! back to the original document so positions still point into it.
+
+use crate::errors::ParseError;
+use crate::reporter::{Event, Reporter};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::BTreeSet;
+
+/// Any line that looks like a marker; checked against `MARKER_REGEX` to report typos.
+const MARKER_PREFIX: &str = "<!-- strux:";
+/// Escapes a marker line, which then stands for itself (one escape is removed per run).
+const MARKER_ESCAPE: char = '\\';
+
+/// A complete marker line (without surrounding whitespace).
+static MARKER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^<!--\s*strux:(?P<keyword>if|else|endif)(?:\s+(?P<flag>[A-Za-z0-9_.-]+))?\s*-->$")
+        .expect("Failed to compile MARKER_REGEX")
+});
+
+/// An open `if` region.
+struct Region {
+    pos: usize,    // Byte offset of its `if` marker in the original document
+    flag: String,  // The flag it tests
+    taken: bool,   // Whether the current branch is kept (the flag is enabled, or after `else`)
+    in_else: bool, // Whether the `else` marker was seen
+}
+
+/// The text left after removing markers and disabled regions, with the offsets needed to map
+/// positions in it back to the original document.
+pub(crate) struct FilteredDocument {
+    pub text: String,
+    /// `(offset in text, offset in the original)` at the start of each run of kept lines.
+    segments: Vec<(usize, usize)>,
+}
+
+impl FilteredDocument {
+    /// Maps a byte offset in the filtered text to the original document.
+    pub(crate) fn original_pos(&self, pos: usize) -> usize {
+        let index = self
+            .segments
+            .partition_point(|&(filtered_start, _)| filtered_start <= pos);
+        match index.checked_sub(1).map(|i| self.segments[i]) {
+            Some((filtered_start, original_start)) => original_start + (pos - filtered_start),
+            None => pos,
+        }
+    }
+
+    /// Maps the position carried by a parse error back to the original document.
+    pub(crate) fn original_error(&self, error: ParseError) -> ParseError {
+        match error {
+            ParseError::NoClosingFence { fence, pos } => ParseError::NoClosingFence {
+                fence,
+                pos: self.original_pos(pos),
+            },
+            ParseError::InvalidHeaderFormat { pos, details } => ParseError::InvalidHeaderFormat {
+                pos: self.original_pos(pos),
+                details,
+            },
+            ParseError::InvalidBase64 { pos, path, details } => ParseError::InvalidBase64 {
+                pos: self.original_pos(pos),
+                path,
+                details,
+            },
+            other => other,
+        }
+    }
+}
+
+/// Removes the marker lines and disabled regions of `markdown`, whose first byte is at
+/// `offset` in the original document. Returns `None` when it contains no markers.
+pub(crate) fn filter_conditionals(
+    markdown: &str,
+    offset: usize,
+    enabled_flags: &BTreeSet<String>,
+    reporter: &dyn Reporter,
+) -> Result<Option<FilteredDocument>, ParseError> {
+    if !markdown.contains(MARKER_PREFIX) {
+        return Ok(None);
+    }
+
+    let mut filtered = FilteredDocument {
+        text: String::with_capacity(markdown.len()),
+        segments: Vec::new(),
+    };
+    let mut regions: Vec<Region> = Vec::new();
+    let mut line_start = offset;
+    let mut previous_kept = false; // Whether the previous line was kept (no new segment needed)
+    for line in markdown.split_inclusive('\n') {
+        let pos = line_start;
+        line_start += line.len();
+        let trimmed = line.trim();
+        if !trimmed.starts_with(MARKER_PREFIX) {
+            if regions.iter().all(|region| region.taken) {
+                if !previous_kept {
+                    filtered.segments.push((filtered.text.len(), pos));
+                }
+                if is_escaped_marker(trimmed) {
+                    // Drop the escape after the indentation; the rest starts a new segment
+                    let escape = line.len() - line.trim_start().len();
+                    filtered.text.push_str(&line[..escape]);
+                    filtered
+                        .segments
+                        .push((filtered.text.len(), pos + escape + 1));
+                    filtered.text.push_str(&line[escape + 1..]);
+                } else {
+                    filtered.text.push_str(line);
+                }
+                previous_kept = true;
+            } else {
+                previous_kept = false;
+            }
+            continue;
+        }
+
+        previous_kept = false;
+        let caps = MARKER_REGEX
+            .captures(trimmed)
+            .ok_or_else(|| invalid(pos, format!("unrecognized marker '{}'", trimmed)))?;
+        let flag = caps.name("flag").map(|flag| flag.as_str().to_string());
+        match (&caps["keyword"], flag) {
+            ("if", Some(flag)) => {
+                let taken = enabled_flags.contains(&flag);
+                if regions.iter().all(|region| region.taken) {
+                    reporter.report(&Event::Decision(format!(
+                        "Conditional region '{}' at byte {}: {}",
+                        flag,
+                        pos,
+                        if taken {
+                            "enabled"
+                        } else {
+                            "disabled, dropping it"
+                        }
+                    )));
+                }
+                regions.push(Region {
+                    pos,
+                    flag,
+                    taken,
+                    in_else: false,
+                });
+            }
+            ("if", None) => return Err(invalid(pos, "'strux:if' needs a flag name".to_string())),
+            ("else", None) => {
+                let region = regions
+                    .last_mut()
+                    .ok_or_else(|| invalid(pos, "'strux:else' without 'strux:if'".to_string()))?;
+                if region.in_else {
+                    return Err(invalid(
+                        pos,
+                        format!(
+                            "second 'strux:else' for the region '{}' opened at byte {}",
+                            region.flag, region.pos
+                        ),
+                    ));
+                }
+                region.in_else = true;
+                region.taken = !region.taken;
+            }
+            ("endif", None) => {
+                regions
+                    .pop()
+                    .ok_or_else(|| invalid(pos, "'strux:endif' without 'strux:if'".to_string()))?;
+            }
+            (keyword, Some(_)) => {
+                return Err(invalid(
+                    pos,
+                    format!("'strux:{}' does not take a flag", keyword),
+                ))
+            }
+            _ => unreachable!("MARKER_REGEX only matches if, else and endif"),
+        }
+    }
+
+    if !regions.is_empty() {
+        return Err(ParseError::UnclosedConditional {
+            positions: regions.iter().map(|region| region.pos).collect(),
+        });
+    }
+    Ok(Some(filtered))
+}
+
+/// Whether a trimmed line is a marker behind one or more escapes.
+fn is_escaped_marker(trimmed: &str) -> bool {
+    trimmed.starts_with(MARKER_ESCAPE)
+        && trimmed
+            .trim_start_matches(MARKER_ESCAPE)
+            .starts_with(MARKER_PREFIX)
+}
+
+/// Escapes every line of `text` that a run would read as a marker (or as an escaped one), so
+/// the text comes back unchanged. Used by `pack` for file contents.
+pub(crate) fn escape_marker_lines(text: &str) -> String {
+    text.split_inclusive('\n')
+        .map(|line| {
+            let trimmed = line.trim();
+            if trimmed.starts_with(MARKER_PREFIX) || is_escaped_marker(trimmed) {
+                let indent = line.len() - line.trim_start().len();
+                format!("{}{}{}", &line[..indent], MARKER_ESCAPE, &line[indent..])
+            } else {
+                line.to_string()
+            }
+        })
+        .collect()
+}
+
+fn invalid(pos: usize, details: String) -> ParseError {
+    ParseError::InvalidConditional { pos, details }
+}
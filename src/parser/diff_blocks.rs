@@ -0,0 +1,203 @@
+//! Header-less diff blocks: a ```` ```diff ```` or ```` ```patch ```` block that no header
+//! claimed in Pass 1 or Pass 2 is split at its `---`/`+++` file headers into one `Patch`
+//! action per file (or a `Delete` action for `+++ /dev/null`).
+
+use crate::constants::DIFF_FENCE_LANGS;
+use crate::core_types::{Action, ActionType, Content};
+use crate::errors::ParseError;
+use crate::parser::helpers::ensure_trailing_newline;
+use crate::parser::pass1::{fence_finder, utils as pass1_utils};
+use crate::parser::path_utils::validate_path_format;
+use crate::parser::regex::OPENING_FENCE_REGEX;
+use crate::reporter::{Event, Reporter};
+use std::collections::HashSet;
+
+/// One file's part of a diff block.
+struct FileSection<'a> {
+    offset: usize,            // Start of the `---` line, relative to the block content
+    old_path: Option<String>, // None for /dev/null
+    new_path: Option<String>, // None for /dev/null
+    text: &'a str,            // From the `---` line up to the next file's `---` line
+}
+
+/// Turns every unclaimed diff block into actions.
+pub(crate) fn run_diff_blocks(
+    content_to_parse: &str,
+    parse_offset: usize,
+    actions_with_pos: &mut Vec<(usize, Action)>,
+    processed_code_block_ranges: &mut HashSet<(usize, usize)>,
+    reporter: &dyn Reporter,
+) -> Result<(), ParseError> {
+    let mut current_search_pos = 0;
+    while let Some(caps) = OPENING_FENCE_REGEX.captures_at(content_to_parse, current_search_pos) {
+        let fence_match = caps.get(0).unwrap();
+        let fence_start = fence_match.start();
+        if pass1_utils::is_already_processed(fence_start, processed_code_block_ranges) {
+            current_search_pos = fence_start + 1;
+            continue;
+        }
+        let fence_chars = caps.name("fence").unwrap().as_str();
+        let lang = caps.name("lang").map(|m| m.as_str().trim()).unwrap_or("");
+        let Some(closing_match) = fence_finder::find_closing_fence(
+            content_to_parse,
+            fence_chars,
+            fence_match.end(),
+            reporter,
+        ) else {
+            current_search_pos = fence_match.end(); // Already reported in Pass 1
+            continue;
+        };
+        let block_outer_end = closing_match.end();
+        current_search_pos = block_outer_end;
+
+        let is_diff = lang
+            .split_whitespace()
+            .next()
+            .is_some_and(|word| DIFF_FENCE_LANGS.contains(&word.to_ascii_lowercase().as_str()));
+        if !is_diff {
+            continue;
+        }
+
+        let block_content = &content_to_parse[fence_match.end()..closing_match.start()];
+        let sections = split_file_sections(block_content);
+        let original_block_pos = fence_start + parse_offset;
+        if sections.is_empty() {
+            reporter.report(&Event::Warning(format!(
+                "Diff block at original pos {} names no files ('---'/'+++' lines) and has no header. Skipping.",
+                original_block_pos
+            )));
+            continue;
+        }
+
+        reporter.report(&Event::Decision(format!(
+            "  - Found header-less diff block at original pos {} touching {} file(s)",
+            original_block_pos,
+            sections.len()
+        )));
+        for section in sections {
+            // Keep the actions of one block in order, after the block's opening fence
+            let original_pos = fence_match.end() + section.offset + parse_offset;
+            if let Some(action) = section_action(&section, original_pos, reporter) {
+                actions_with_pos.push((original_pos, action));
+            }
+        }
+        processed_code_block_ranges.insert((fence_start, block_outer_end));
+    }
+    Ok(())
+}
+
+/// Builds the action for one file of a diff block.
+fn section_action(
+    section: &FileSection,
+    original_pos: usize,
+    reporter: &dyn Reporter,
+) -> Option<Action> {
+    let (action_type, path) = match (&section.old_path, &section.new_path) {
+        (_, Some(new_path)) => (ActionType::Patch, new_path.clone()),
+        (Some(old_path), None) => (ActionType::Delete, old_path.clone()),
+        (None, None) => {
+            reporter.report(&Event::Warning(format!(
+                "Diff at original pos {} goes from /dev/null to /dev/null. Skipping.",
+                original_pos
+            )));
+            return None;
+        }
+    };
+    if path.is_empty() || validate_path_format(&path).is_err() {
+        reporter.report(&Event::Warning(format!(
+            "Invalid path '{}' in diff header at original pos {}. Skipping.",
+            path, original_pos
+        )));
+        return None;
+    }
+    reporter.report(&Event::Decision(format!(
+        "     -> Added {} action for '{}' from diff header",
+        format!("{:?}", action_type).to_uppercase(),
+        path
+    )));
+
+    let content = (action_type == ActionType::Patch).then(|| {
+        let mut text = section.text.to_string();
+        ensure_trailing_newline(&mut text);
+        Content::Text(text)
+    });
+    Some(Action {
+        action_type,
+        path,
+        dest_path: None,
+        content,
+        anchor: None,
+        mode: None,
+        original_pos,
+        source_file: None,
+    })
+}
+
+/// Splits a diff at every `---` line directly followed by a `+++` line.
+fn split_file_sections(block_content: &str) -> Vec<FileSection<'_>> {
+    let mut lines = Vec::new(); // (offset, line without its line ending)
+    let mut offset = 0;
+    for line in block_content.split_inclusive('\n') {
+        lines.push((offset, line.trim_end_matches(['\n', '\r'])));
+        offset += line.len();
+    }
+
+    let headers: Vec<usize> = (0..lines.len())
+        .filter(|&index| {
+            lines[index].1.starts_with("--- ")
+                && lines
+                    .get(index + 1)
+                    .is_some_and(|(_, next)| next.starts_with("+++ "))
+        })
+        .collect();
+
+    headers
+        .iter()
+        .enumerate()
+        .map(|(header_index, &line_index)| {
+            let start = lines[line_index].0;
+            let end = headers
+                .get(header_index + 1)
+                .map_or(block_content.len(), |&next| lines[next].0);
+            let old_path = diff_header_path(&lines[line_index].1[4..]);
+            let new_path = diff_header_path(&lines[line_index + 1].1[4..]);
+            let (old_path, new_path) = strip_git_prefixes(old_path, new_path);
+            FileSection {
+                offset: start,
+                old_path,
+                new_path,
+                text: &block_content[start..end],
+            }
+        })
+        .collect()
+}
+
+/// Reads the path of a `---`/`+++` line (without the marker), dropping a trailing
+/// timestamp and surrounding quotes. `None` for `/dev/null`.
+fn diff_header_path(rest: &str) -> Option<String> {
+    let path = rest.split('\t').next().unwrap_or("").trim();
+    let path = path
+        .strip_prefix('"')
+        .and_then(|quoted| quoted.strip_suffix('"'))
+        .unwrap_or(path);
+    (path != "/dev/null").then(|| path.to_string())
+}
+
+/// Drops git's `a/` and `b/` prefixes when the diff uses them on both sides.
+fn strip_git_prefixes(
+    old_path: Option<String>,
+    new_path: Option<String>,
+) -> (Option<String>, Option<String>) {
+    let old_prefixed = old_path
+        .as_deref()
+        .is_none_or(|path| path.starts_with("a/"));
+    let new_prefixed = new_path
+        .as_deref()
+        .is_none_or(|path| path.starts_with("b/"));
+    if old_prefixed && new_prefixed {
+        let strip = |path: Option<String>| path.map(|path| path[2..].to_string());
+        (strip(old_path), strip(new_path))
+    } else {
+        (old_path, new_path)
+    }
+}
@@ -0,0 +1,313 @@
+//! Parses a leading front-matter block: `---` (YAML) or `+++` (TOML) lines around
+//! per-document settings.
+//!
+//! Only the subset of both formats the settings need is understood: `key: value` /
+//! `key = value` pairs, string lists (`[a, b]`, or YAML `- item` lines) and the `variables`
+//! map (an indented YAML block, or a TOML `[variables]` table). Quotes around values are
+//! removed; escapes are not supported. `#` starts a comment at the beginning of a line or
+//! after whitespace, outside quotes.
+
+use crate::core_types::ActionType;
+use crate::errors::ParseError;
+use crate::reporter::{Event, Reporter};
+use crate::settings::{FrontMatter, LineEndings};
+use crate::variables::is_valid_variable_name;
+
+const YAML_DELIMITER: &str = "---";
+const TOML_DELIMITER: &str = "+++";
+/// The TOML table holding template variables.
+const VARIABLES_KEY: &str = "variables";
+
+/// A value as written in the front matter, before it is interpreted.
+enum Value {
+    Empty, // A YAML key with nothing after the colon; the lines below give it a list or map
+    Scalar(String),
+    List(Vec<String>),
+    Map(Vec<(String, String)>),
+}
+
+/// A `key`, its value and the (1-based) line of the document it is on.
+struct Entry {
+    key: String,
+    value: Value,
+    line: usize,
+}
+
+/// Splits a leading front-matter block off `markdown`. Returns the settings and the byte
+/// offset where the rest of the document starts, or `None` when the document does not begin
+/// with a delimiter line that is closed later on (or with a `---` block that is not YAML).
+pub(crate) fn split_front_matter(
+    markdown: &str,
+    reporter: &dyn Reporter,
+) -> Result<Option<(FrontMatter, usize)>, ParseError> {
+    let mut lines = markdown.split_inclusive('\n');
+    let Some(first_line) = lines.next() else {
+        return Ok(None);
+    };
+    let delimiter = first_line.trim_end();
+    if delimiter != YAML_DELIMITER && delimiter != TOML_DELIMITER {
+        return Ok(None);
+    }
+
+    let mut offset = first_line.len();
+    let mut block = Vec::new(); // (line number, text)
+    for (index, line) in lines.enumerate() {
+        offset += line.len();
+        if line.trim_end() == delimiter {
+            reporter.report(&Event::Decision(format!(
+                "Found front matter ('{}') ending at offset {}",
+                delimiter, offset
+            )));
+            let entries = if delimiter == YAML_DELIMITER {
+                // `---` is also a Markdown rule: a block that is not all `key: value`, list and
+                // map lines is two rules around ordinary text, not broken front matter
+                match parse_yaml(&block) {
+                    Ok(entries) => entries,
+                    Err(e) => {
+                        reporter.report(&Event::Warning(format!(
+                            "Not reading the leading '---' block as front matter ({}); parsing it as Markdown.",
+                            e
+                        )));
+                        return Ok(None);
+                    }
+                }
+            } else {
+                parse_toml(&block)?
+            };
+            return Ok(Some((build_front_matter(entries, reporter)?, offset)));
+        }
+        block.push((index + 2, line.trim_end()));
+    }
+    Ok(None) // Never closed: an ordinary line (e.g. a Markdown rule), not front matter
+}
+
+fn parse_yaml(block: &[(usize, &str)]) -> Result<Vec<Entry>, ParseError> {
+    let mut entries: Vec<Entry> = Vec::new();
+    for &(line, text) in block {
+        let trimmed = strip_comment(text.trim());
+        if trimmed.is_empty() {
+            continue;
+        }
+        let indented = text.starts_with([' ', '\t']);
+        let parent = entries.last_mut().filter(|entry| {
+            matches!(entry.value, Value::Empty | Value::List(_) | Value::Map(_))
+                && !matches!(entry.value, Value::List(_) if indented && !trimmed.starts_with('-'))
+        });
+
+        if let Some(item) = trimmed.strip_prefix('-') {
+            // `- item` lines belong to the key above, indented or not
+            let Some(entry) = parent else {
+                return Err(invalid(line, "list item without a key above it"));
+            };
+            match &mut entry.value {
+                Value::Empty => entry.value = Value::List(vec![unquote(item.trim())]),
+                Value::List(items) => items.push(unquote(item.trim())),
+                _ => return Err(invalid(line, "list item inside a map")),
+            }
+        } else if indented {
+            let Some(entry) = parent else {
+                return Err(invalid(line, "unexpected indentation"));
+            };
+            let (name, value) = split_pair(trimmed, ':', line)?;
+            match &mut entry.value {
+                Value::Empty => entry.value = Value::Map(vec![(name, value)]),
+                Value::Map(pairs) => pairs.push((name, value)),
+                _ => return Err(invalid(line, "map entry inside a list")),
+            }
+        } else {
+            let (key, raw) = trimmed
+                .split_once(':')
+                .ok_or_else(|| invalid(line, "expected 'key: value'"))?;
+            let raw = raw.trim();
+            let value = if raw.is_empty() {
+                Value::Empty
+            } else if raw.starts_with('[') {
+                Value::List(parse_inline_list(raw, line)?)
+            } else {
+                Value::Scalar(unquote(raw))
+            };
+            entries.push(Entry {
+                key: key.trim().to_string(),
+                value,
+                line,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+fn parse_toml(block: &[(usize, &str)]) -> Result<Vec<Entry>, ParseError> {
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut in_variables = false;
+    for &(line, text) in block {
+        let trimmed = strip_comment(text.trim());
+        if trimmed.is_empty() {
+            continue;
+        }
+        if let Some(table) = trimmed
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            if table.trim() != VARIABLES_KEY {
+                return Err(invalid(
+                    line,
+                    &format!("unsupported table '[{}]'", table.trim()),
+                ));
+            }
+            in_variables = true;
+            entries.push(Entry {
+                key: VARIABLES_KEY.to_string(),
+                value: Value::Map(Vec::new()),
+                line,
+            });
+            continue;
+        }
+        let (key, raw) = trimmed
+            .split_once('=')
+            .ok_or_else(|| invalid(line, "expected 'key = value'"))?;
+        let raw = raw.trim();
+        if in_variables {
+            if let Some(Value::Map(pairs)) = entries.last_mut().map(|entry| &mut entry.value) {
+                pairs.push((key.trim().to_string(), unquote(raw)));
+            }
+            continue;
+        }
+        let value = if raw.starts_with('[') {
+            Value::List(parse_inline_list(raw, line)?)
+        } else {
+            Value::Scalar(unquote(raw))
+        };
+        entries.push(Entry {
+            key: key.trim().to_string(),
+            value,
+            line,
+        });
+    }
+    Ok(entries)
+}
+
+/// Interprets the parsed entries as settings; unknown keys are ignored with a warning.
+fn build_front_matter(
+    entries: Vec<Entry>,
+    reporter: &dyn Reporter,
+) -> Result<FrontMatter, ParseError> {
+    let mut front_matter = FrontMatter::default();
+    for Entry { key, value, line } in entries {
+        match (key.as_str(), value) {
+            ("output_dir", Value::Scalar(dir)) if !dir.is_empty() => {
+                front_matter.output_dir = Some(dir)
+            }
+            ("force", Value::Scalar(flag)) => {
+                front_matter.force = Some(match flag.as_str() {
+                    "true" => true,
+                    "false" => false,
+                    _ => return Err(invalid(line, "'force' must be true or false")),
+                })
+            }
+            ("variables", Value::Map(pairs)) => {
+                for (name, value) in pairs {
+                    if !is_valid_variable_name(&name) {
+                        return Err(invalid(line, &format!("invalid variable name '{}'", name)));
+                    }
+                    front_matter.variables.insert(name, value);
+                }
+            }
+            ("variables", Value::Empty) => {}
+            ("line_endings", Value::Scalar(name)) => {
+                front_matter.line_endings = Some(
+                    LineEndings::from_name(&name)
+                        .ok_or_else(|| invalid(line, "'line_endings' must be keep, lf or crlf"))?,
+                )
+            }
+            ("allowed_actions", Value::List(labels)) => {
+                front_matter.allowed_actions = Some(parse_action_labels(&labels, line)?)
+            }
+            ("allowed_actions", Value::Scalar(label)) => {
+                front_matter.allowed_actions = Some(parse_action_labels(&[label], line)?)
+            }
+            ("allowed_actions", Value::Empty) => front_matter.allowed_actions = Some(Vec::new()),
+            ("output_dir" | "force" | "variables" | "line_endings" | "allowed_actions", _) => {
+                return Err(invalid(line, &format!("invalid value for '{}'", key)))
+            }
+            _ => reporter.report(&Event::Warning(format!(
+                "Ignoring unknown front matter key '{}' (line {}).",
+                key, line
+            ))),
+        }
+    }
+    Ok(front_matter)
+}
+
+/// Resolves action type labels (as in the JSON report, e.g. `insert_after`).
+fn parse_action_labels(labels: &[String], line: usize) -> Result<Vec<ActionType>, ParseError> {
+    labels
+        .iter()
+        .map(|label| {
+            ActionType::from_label(label).ok_or_else(|| {
+                let known: Vec<_> = ActionType::ALL.iter().map(ActionType::label).collect();
+                invalid(
+                    line,
+                    &format!(
+                        "unknown action type '{}' (expected one of: {})",
+                        label,
+                        known.join(", ")
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Splits `name<separator>value`, removing quotes around the value.
+fn split_pair(text: &str, separator: char, line: usize) -> Result<(String, String), ParseError> {
+    let (name, value) = text
+        .split_once(separator)
+        .ok_or_else(|| invalid(line, &format!("expected 'name{} value'", separator)))?;
+    Ok((name.trim().to_string(), unquote(value.trim())))
+}
+
+/// Parses `[a, "b", 'c']`.
+fn parse_inline_list(raw: &str, line: usize) -> Result<Vec<String>, ParseError> {
+    let inner = raw
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .ok_or_else(|| invalid(line, "unterminated list (lists must fit on one line)"))?;
+    Ok(inner
+        .split(',')
+        .map(|item| unquote(item.trim()))
+        .filter(|item| !item.is_empty())
+        .collect())
+}
+
+/// Cuts off a `#` comment that starts the text or follows whitespace, outside quotes.
+fn strip_comment(text: &str) -> &str {
+    let mut quote = None;
+    let mut after_space = true;
+    for (index, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '#') if after_space => return text[..index].trim_end(),
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            _ => {}
+        }
+        after_space = c.is_whitespace();
+    }
+    text
+}
+
+/// Removes one pair of matching single or double quotes.
+fn unquote(value: &str) -> String {
+    for quote in ['"', '\''] {
+        if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+            return value[1..value.len() - 1].to_string();
+        }
+    }
+    value.to_string()
+}
+
+fn invalid(line: usize, details: &str) -> ParseError {
+    ParseError::InvalidFrontMatter {
+        line,
+        details: details.to_string(),
+    }
+}
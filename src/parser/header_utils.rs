@@ -1,8 +1,10 @@
 //! Utilities for parsing markdown header lines (e.g., **Action: path**).
 
 use crate::constants::{
-    ACTION_APPEND_FILE, ACTION_CREATE, ACTION_DELETED_FILE, ACTION_FILE, ACTION_MOVED_FILE,
-    ACTION_PREPEND_FILE, ACTION_UPDATE,
+    ACTION_APPEND_FILE, ACTION_COPIED_FILE, ACTION_CREATE, ACTION_DELETED_DIRECTORY,
+    ACTION_DELETED_FILE, ACTION_DIRECTORY, ACTION_EDIT_FILE, ACTION_FILE, ACTION_INCLUDE,
+    ACTION_INSERT_AFTER, ACTION_INSERT_BEFORE, ACTION_MOVED_DIRECTORY, ACTION_MOVED_FILE,
+    ACTION_PATCH_FILE, ACTION_PREPEND_FILE, ACTION_SYMLINK, ACTION_UPDATE, INSERT_ANCHOR_SEPARATOR,
 }; // Added new actions
 use crate::core_types::ActionType;
 use regex::Captures;
@@ -12,6 +14,7 @@ pub(crate) struct ParsedHeaderAction {
     pub action_word: String,
     pub path: String, // Source path for Move, target path for File/Delete/Append/Prepend
     pub dest_path: Option<String>, // Destination path for Move
+    pub anchor: Option<String>, // Anchor line for Insert After/Insert Before
 }
 
 /// Extracts action word, primary path, and optional destination path from HEADER_REGEX captures.
@@ -43,6 +46,22 @@ pub(crate) fn extract_header_action_details(caps: &Captures) -> Option<ParsedHea
         action_word_opt = Some(aw.as_str().to_string());
         raw_content_opt = Some(c.as_str().to_string()); // content_bold is the raw string after "Action: "
     } else if let (Some(aw), Some(c)) = (caps.name("action_word_hash"), caps.name("content_hash")) {
+        // "Directory" is a common word in headings ("## Project Directory Layout"), so the
+        // directory, symlink and include actions only count when followed by a colon
+        let whole = caps.get(0)?;
+        let separator = &whole.as_str()[aw.end() - whole.start()..c.start() - whole.start()];
+        if [
+            ACTION_DIRECTORY,
+            ACTION_DELETED_DIRECTORY,
+            ACTION_MOVED_DIRECTORY,
+            ACTION_SYMLINK,
+            ACTION_INCLUDE,
+        ]
+        .contains(&aw.as_str())
+            && !separator.contains(':')
+        {
+            return None;
+        }
         action_word_opt = Some(aw.as_str().to_string());
         raw_content_opt = Some(c.as_str().to_string()); // content_hash is the raw string after "Action: "
     }
@@ -50,14 +69,22 @@ pub(crate) fn extract_header_action_details(caps: &Captures) -> Option<ParsedHea
     // --- Parse Raw Content based on Action Word ---
     match (action_word_opt, raw_content_opt) {
         (Some(action_word), Some(raw_content)) => {
-            if action_word == ACTION_MOVED_FILE {
-                // Parse "source to destination" from raw_content
+            if [
+                ACTION_MOVED_FILE,
+                ACTION_MOVED_DIRECTORY,
+                ACTION_COPIED_FILE,
+                ACTION_SYMLINK,
+            ]
+            .contains(&action_word.as_str())
+            {
+                // Parse "source to destination" (or "link to target") from raw_content
                 if let Some((source, dest)) = parse_move_paths_from_content(&raw_content) {
                     if is_path_valid_for_action(&source) && is_path_valid_for_action(&dest) {
                         Some(ParsedHeaderAction {
                             action_word,
                             path: source,
                             dest_path: Some(dest),
+                            anchor: None,
                         })
                     } else {
                         None
@@ -65,6 +92,19 @@ pub(crate) fn extract_header_action_details(caps: &Captures) -> Option<ParsedHea
                 } else {
                     None // Failed to parse "source to dest"
                 }
+            } else if [ACTION_INSERT_AFTER, ACTION_INSERT_BEFORE].contains(&action_word.as_str()) {
+                // Parse "path @ anchor" from raw_content
+                let (path, anchor) = parse_anchor_from_content(&raw_content)?;
+                if is_path_valid_for_action(&path) {
+                    Some(ParsedHeaderAction {
+                        action_word,
+                        path,
+                        dest_path: None,
+                        anchor: Some(anchor),
+                    })
+                } else {
+                    None
+                }
             } else {
                 // For ACTION_FILE (including backtick-only cases), ACTION_DELETED_FILE,
                 // ACTION_APPEND_FILE, ACTION_PREPEND_FILE, ACTION_CREATE, ACTION_UPDATE
@@ -74,6 +114,7 @@ pub(crate) fn extract_header_action_details(caps: &Captures) -> Option<ParsedHea
                             action_word,
                             path,
                             dest_path: None,
+                            anchor: None,
                         })
                     } else {
                         None
@@ -101,11 +142,8 @@ pub(crate) fn is_path_valid_for_action(path_str: &str) -> bool {
         return false;
     }
     // Heuristic: If a path has too many spaces, it's likely a sentence, not a path.
+    // Callers report the rejected header themselves.
     if path_str.chars().filter(|&c| c == ' ').count() > 5 {
-        println!(
-            "    Info: Skipping potential path due to high space count (> 5): '{}'",
-            path_str
-        );
         return false;
     }
     true
@@ -145,8 +183,8 @@ fn parse_single_path_from_content(raw_content: &str) -> Option<String> {
     }
 }
 
-/// Parses "source to destination" from a content string for "Moved File:" actions.
-/// The raw_content is the string part after "Moved File: ".
+/// Parses "source to destination" from a content string for "Moved File:", "Moved Directory:"
+/// and "Copied File:" actions. The raw_content is the string part after "Moved File: ".
 fn parse_move_paths_from_content(raw_content: &str) -> Option<(String, String)> {
     // First, strip potential trailing comments from the whole "source to dest" string
     let content_trimmed = raw_content.trim();
@@ -203,6 +241,35 @@ fn parse_move_paths_from_content(raw_content: &str) -> Option<(String, String)>
     }
 }
 
+/// Parses "path @ anchor" from a content string for "Insert After:" and "Insert Before:"
+/// actions. Unlike paths, the anchor keeps trailing text such as " (" or " #"; it is only
+/// unwrapped from backticks when it is fully enclosed in them.
+fn parse_anchor_from_content(raw_content: &str) -> Option<(String, String)> {
+    let content_trimmed = raw_content.trim();
+
+    // Split at the first separator outside backticks, so a backticked path may contain " @ "
+    let mut in_backticks = false;
+    let split_idx = content_trimmed.char_indices().find_map(|(i, c)| {
+        if c == '`' {
+            in_backticks = !in_backticks;
+        }
+        (!in_backticks && content_trimmed[i..].starts_with(INSERT_ANCHOR_SEPARATOR)).then_some(i)
+    })?;
+
+    let path = parse_single_path_from_content(&content_trimmed[..split_idx])?;
+    let anchor_str = content_trimmed[split_idx + INSERT_ANCHOR_SEPARATOR.len()..].trim();
+    let anchor = anchor_str
+        .strip_prefix('`')
+        .and_then(|inner| inner.strip_suffix('`'))
+        .filter(|inner| !inner.contains('`'))
+        .unwrap_or(anchor_str);
+    if anchor.is_empty() {
+        None
+    } else {
+        Some((path, anchor.to_string()))
+    }
+}
+
 /// Maps action word string to ActionType enum.
 pub(crate) fn get_action_type(action_word: &str) -> Option<ActionType> {
     match action_word {
@@ -211,6 +278,16 @@ pub(crate) fn get_action_type(action_word: &str) -> Option<ActionType> {
         ACTION_MOVED_FILE => Some(ActionType::Move),
         ACTION_APPEND_FILE => Some(ActionType::Append),
         ACTION_PREPEND_FILE => Some(ActionType::Prepend),
+        ACTION_COPIED_FILE => Some(ActionType::Copy),
+        ACTION_EDIT_FILE => Some(ActionType::Edit),
+        ACTION_PATCH_FILE => Some(ActionType::Patch),
+        ACTION_INSERT_AFTER => Some(ActionType::InsertAfter),
+        ACTION_INSERT_BEFORE => Some(ActionType::InsertBefore),
+        ACTION_DIRECTORY => Some(ActionType::CreateDirectory),
+        ACTION_DELETED_DIRECTORY => Some(ActionType::DeleteDirectory),
+        ACTION_MOVED_DIRECTORY => Some(ActionType::MoveDirectory),
+        ACTION_SYMLINK => Some(ActionType::Symlink),
+        ACTION_INCLUDE => Some(ActionType::Include),
         _ => None,
     }
 }
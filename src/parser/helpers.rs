@@ -2,8 +2,14 @@
 
 // Removed unused import: once_cell::sync::Lazy;
 // Removed unused import: regex::Regex;
-// Removed unused ParseError
 // Removed unused HashMap
+use crate::constants::{FENCE_ATTRIBUTE_MODE, FENCE_ATTRIBUTE_NO_EOL, FENCE_LANG_BASE64};
+use crate::core_types::{Action, ActionType, Content};
+use crate::errors::ParseError;
+use crate::parser::front_matter;
+use crate::reporter::{Event, Reporter};
+use crate::settings::FrontMatter;
+use base64::prelude::{Engine, BASE64_STANDARD};
 
 // --- Moved to header_utils.rs ---
 // extract_action_path_from_captures
@@ -21,9 +27,25 @@
 //     Regex::new(r"(?m)^\s*```\s*$").expect("Failed to compile LAST_CLOSING_FENCE_REGEX")
 // });
 
+/// Strips a leading front-matter block, then a ```markdown wrapper line.
+/// Returns the content slice to parse, its starting offset and the front matter's settings.
+pub(crate) fn preprocess_markdown<'a>(
+    markdown_content: &'a str,
+    reporter: &dyn Reporter,
+) -> Result<(&'a str, usize, FrontMatter), ParseError> {
+    let (front_matter, body_offset) =
+        front_matter::split_front_matter(markdown_content, reporter)?.unwrap_or_default();
+    let (content_to_parse, wrapper_offset) =
+        strip_markdown_wrapper(&markdown_content[body_offset..], reporter);
+    Ok((content_to_parse, body_offset + wrapper_offset, front_matter))
+}
+
 /// Handles the initial check for and potential stripping of ```markdown blocks.
 /// Returns the content slice to parse and the starting offset.
-pub(crate) fn preprocess_markdown(markdown_content: &str) -> (&str, usize) {
+fn strip_markdown_wrapper<'a>(
+    markdown_content: &'a str,
+    reporter: &dyn Reporter,
+) -> (&'a str, usize) {
     // Simplified logic: Only check if the *very first non-whitespace line* is exactly ```markdown
     // This avoids incorrectly consuming the whole file if it happens to end with ``` later.
     // The regular parser logic (Pass 1) should handle ```markdown blocks correctly anyway,
@@ -35,16 +57,22 @@ pub(crate) fn preprocess_markdown(markdown_content: &str) -> (&str, usize) {
     if let Some(first_newline_idx) = markdown_content.find('\n') {
         let first_line = &markdown_content[..first_newline_idx];
         if first_line.trim() == "```markdown" {
-            println!("Info: Ignoring first line '```markdown'.");
+            reporter.report(&Event::Decision(
+                "Info: Ignoring first line '```markdown'.".to_string(),
+            ));
             let parse_offset = first_newline_idx + 1;
             let content_to_parse = &markdown_content[parse_offset..];
-            println!("  (Parsing content starting from offset {parse_offset})");
+            reporter.report(&Event::Decision(format!(
+                "  (Parsing content starting from offset {parse_offset})"
+            )));
             return (content_to_parse, parse_offset);
         }
     }
     // Also handle the case where the *entire file* is just "```markdown"
     if markdown_content.trim() == "```markdown" {
-        println!("Info: Input file only contained '```markdown'.");
+        reporter.report(&Event::Decision(
+            "Info: Input file only contained '```markdown'.".to_string(),
+        ));
         return ("", markdown_content.len());
     }
 
@@ -62,6 +90,78 @@ pub(crate) fn ensure_trailing_newline(content: &mut String) {
     }
 }
 
+/// Applies the attributes of the fence info string of the block an action takes its content
+/// from: `no-eol` drops the final newline, `mode=NNN` sets the file's permissions and a
+/// `base64` language decodes the block to raw bytes (`block_pos`, the block's byte offset
+/// in the original markdown, locates decoding errors).
+pub(crate) fn apply_fence_attributes(
+    action: &mut Action,
+    info: &str,
+    block_pos: usize,
+    reporter: &dyn Reporter,
+) -> Result<(), ParseError> {
+    let writes_file = matches!(
+        action.action_type,
+        ActionType::Create | ActionType::Append | ActionType::Prepend
+    );
+    for word in info.split_whitespace() {
+        if word == FENCE_ATTRIBUTE_NO_EOL {
+            if let Some(Content::Text(content)) = action.content.as_mut() {
+                if content.ends_with('\n') {
+                    content.pop();
+                }
+            }
+        } else if word == FENCE_LANG_BASE64 {
+            if !writes_file {
+                reporter.report(&Event::Warning(format!(
+                    "Treating the base64 block for '{}' as text: binary content only applies to File, Append File and Prepend File.",
+                    action.path
+                )));
+                continue;
+            }
+            if let Some(Content::Text(encoded)) = &action.content {
+                action.content = Some(Content::Binary(decode_base64(encoded).map_err(
+                    |details| ParseError::InvalidBase64 {
+                        pos: block_pos,
+                        path: action.path.clone(),
+                        details,
+                    },
+                )?));
+            }
+        } else if let Some(value) = word.strip_prefix(FENCE_ATTRIBUTE_MODE) {
+            if !writes_file {
+                reporter.report(&Event::Warning(format!(
+                    "Ignoring '{}' on the block for '{}': modes only apply to File, Append File and Prepend File.",
+                    word, action.path
+                )));
+                continue;
+            }
+            match parse_mode(value) {
+                Some(mode) => action.mode = Some(mode),
+                None => reporter.report(&Event::Warning(format!(
+                    "Ignoring invalid mode '{}' on the block for '{}' (expected octal, e.g. mode=755).",
+                    value, action.path
+                ))),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Decodes the text of a `base64` block, ignoring line breaks and other whitespace.
+fn decode_base64(encoded: &str) -> Result<Vec<u8>, String> {
+    let compact: String = encoded.split_whitespace().collect();
+    BASE64_STANDARD.decode(compact).map_err(|e| e.to_string())
+}
+
+/// Parses an octal permission string such as `755` or `0644`.
+fn parse_mode(value: &str) -> Option<u32> {
+    if value.is_empty() || value.len() > 4 {
+        return None;
+    }
+    u32::from_str_radix(value, 8).ok()
+}
+
 /// Heuristic check if a line likely starts with a common single-line comment marker.
 pub(crate) fn is_likely_comment(line: &str) -> bool {
     let trimmed = line.trim_start();
@@ -0,0 +1,139 @@
+//! Expands `## Include: other.md` actions into the actions of the documents they name.
+
+use crate::constants::MAX_INCLUDE_DEPTH;
+use crate::core_types::{Action, ActionType, ParseOptions};
+use crate::errors::ParseError;
+use crate::parser::parse_document_with;
+use crate::reporter::{Event, Reporter};
+use crate::settings::FrontMatter;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the including document when the markdown came from standard input.
+const STDIN_NAME: &str = "<stdin>";
+
+/// A document on the current include chain: its canonical path (to detect cycles) and the
+/// name it was included under (for messages).
+struct ChainEntry {
+    canonical: PathBuf,
+    name: String,
+}
+
+/// Replaces every Include action with the actions of the document it names, recursively, so
+/// they take the directive's place in document order.
+///
+/// `document` is the file `actions` were parsed from (`None` for standard input); include
+/// paths are relative to its directory (or the working directory). Included actions record
+/// their file in `source_file`, and their `original_pos` is a byte offset in that file.
+/// Included documents are parsed with the same `options`; their front matter is ignored with
+/// a warning.
+pub fn resolve_includes(
+    actions: Vec<Action>,
+    document: Option<&Path>,
+    options: &ParseOptions,
+    reporter: &dyn Reporter,
+) -> Result<Vec<Action>, ParseError> {
+    if !actions
+        .iter()
+        .any(|action| action.action_type == ActionType::Include)
+    {
+        return Ok(actions);
+    }
+    let name = document.map_or(STDIN_NAME.to_string(), |path| path.display().to_string());
+    let mut chain: Vec<ChainEntry> = document
+        .and_then(|path| path.canonicalize().ok())
+        .map(|canonical| ChainEntry {
+            canonical,
+            name: name.clone(),
+        })
+        .into_iter()
+        .collect();
+    let base_dir = document.and_then(Path::parent).unwrap_or(Path::new(""));
+    expand(actions, base_dir, &name, &mut chain, 1, options, reporter)
+}
+
+/// Expands the includes of one document (`including`, whose directory is `base_dir`).
+/// `depth` is the nesting level of the documents it includes.
+fn expand(
+    actions: Vec<Action>,
+    base_dir: &Path,
+    including: &str,
+    chain: &mut Vec<ChainEntry>,
+    depth: usize,
+    options: &ParseOptions,
+    reporter: &dyn Reporter,
+) -> Result<Vec<Action>, ParseError> {
+    let mut expanded = Vec::with_capacity(actions.len());
+    for action in actions {
+        if action.action_type != ActionType::Include {
+            expanded.push(action);
+            continue;
+        }
+        let included = base_dir.join(&action.path);
+        let name = included.display().to_string();
+        let not_readable = |e: std::io::Error| ParseError::IncludeNotReadable {
+            path: name.clone(),
+            including: including.to_string(),
+            pos: action.original_pos,
+            details: e.to_string(),
+        };
+        let canonical = included.canonicalize().map_err(not_readable)?;
+
+        if let Some(start) = chain.iter().position(|entry| entry.canonical == canonical) {
+            let mut names: Vec<&str> = chain[start..]
+                .iter()
+                .map(|entry| entry.name.as_str())
+                .collect();
+            names.push(&name);
+            return Err(ParseError::IncludeCycle {
+                cycle: names.join(" -> "),
+            });
+        }
+        if depth > MAX_INCLUDE_DEPTH {
+            return Err(ParseError::IncludeTooDeep {
+                path: name,
+                including: including.to_string(),
+                pos: action.original_pos,
+                limit: MAX_INCLUDE_DEPTH,
+            });
+        }
+
+        let markdown = fs::read_to_string(&included).map_err(not_readable)?;
+        reporter.report(&Event::Decision(format!(
+            "Including '{}' at byte {} of '{}'",
+            name, action.original_pos, including
+        )));
+        let (mut actions, front_matter) = parse_document_with(&markdown, options, reporter)
+            .map_err(|e| ParseError::InIncludedFile {
+                file: name.clone(),
+                source: Box::new(e),
+            })?;
+        if front_matter != FrontMatter::default() {
+            reporter.report(&Event::Warning(format!(
+                "Ignoring the front matter of included file '{}': only the documents given on the command line configure a run.",
+                name
+            )));
+        }
+        for included_action in &mut actions {
+            included_action.source_file = Some(name.clone());
+        }
+
+        chain.push(ChainEntry {
+            canonical,
+            name: name.clone(),
+        });
+        let nested_base = included.parent().unwrap_or(Path::new(""));
+        let nested = expand(
+            actions,
+            nested_base,
+            &name,
+            chain,
+            depth + 1,
+            options,
+            reporter,
+        );
+        chain.pop();
+        expanded.extend(nested?);
+    }
+    Ok(expanded)
+}
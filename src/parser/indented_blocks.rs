@@ -0,0 +1,141 @@
+//! Indented code blocks (opt-in with `ParseOptions::indented_code_blocks`): a content header
+//! that Pass 1 did not associate, followed (after optional blank lines) by lines indented
+//! with 4 spaces or a tab, takes those lines as its content, without the indentation.
+
+use crate::core_types::{Action, ActionType, Content};
+use crate::errors::ParseError;
+use crate::parser::header_utils::{extract_header_action_details, get_action_type};
+use crate::parser::helpers::ensure_trailing_newline;
+use crate::parser::pass1::utils as pass1_utils;
+use crate::parser::regex::HEADER_REGEX;
+use crate::reporter::{Event, Reporter};
+use std::collections::HashSet;
+
+/// An indented block: its range in the content and its de-indented text.
+struct IndentedBlock {
+    start: usize, // Start of its first line, relative to the content
+    end: usize,   // End of its last non-blank line (including the line break)
+    text: String,
+}
+
+/// Associates unprocessed content headers with the indented code block that follows them.
+/// Runs after Pass 1 (so headers inside fenced blocks are known) and before Pass 2 (which
+/// would otherwise link such headers to the next fenced block).
+pub(crate) fn run_indented_blocks(
+    content_to_parse: &str,
+    parse_offset: usize,
+    actions_with_pos: &mut Vec<(usize, Action)>,
+    processed_header_starts: &mut HashSet<usize>,
+    all_code_block_ranges: &mut HashSet<(usize, usize)>,
+    processed_code_block_ranges: &mut HashSet<(usize, usize)>,
+    reporter: &dyn Reporter,
+) -> Result<(), ParseError> {
+    for caps in HEADER_REGEX.captures_iter(content_to_parse) {
+        let header_match = caps.get(0).unwrap();
+        let header_start_rel = header_match.start();
+        let original_header_pos = header_start_rel + parse_offset;
+        if processed_header_starts.contains(&original_header_pos)
+            || pass1_utils::is_already_processed(header_start_rel, all_code_block_ranges)
+        {
+            continue;
+        }
+
+        let Some(details) = extract_header_action_details(&caps) else {
+            continue;
+        };
+        let Some(action_type) = get_action_type(&details.action_word) else {
+            continue;
+        };
+        if !matches!(
+            action_type,
+            ActionType::Create
+                | ActionType::Append
+                | ActionType::Prepend
+                | ActionType::Edit
+                | ActionType::Patch
+                | ActionType::InsertAfter
+                | ActionType::InsertBefore
+        ) {
+            continue;
+        }
+
+        let Some(block) = find_indented_block(content_to_parse, header_match.end()) else {
+            continue;
+        };
+        // Lines of a fenced block are not an indented block (Pass 1 already claimed them)
+        if all_code_block_ranges
+            .iter()
+            .any(|&(start, end)| start < block.end && block.start < end)
+        {
+            reporter.report(&Event::Decision(format!(
+                "  - Indented lines after header for '{}' overlap a fenced code block. Ignoring them.",
+                details.path
+            )));
+            continue;
+        }
+
+        reporter.report(&Event::Decision(format!(
+            "  - Associated header for '{}' with the indented code block at original pos {}",
+            details.path,
+            block.start + parse_offset
+        )));
+        let mut text = block.text;
+        ensure_trailing_newline(&mut text);
+        actions_with_pos.push((
+            original_header_pos,
+            Action {
+                action_type,
+                path: details.path,
+                dest_path: None,
+                content: Some(Content::Text(text)),
+                anchor: details.anchor,
+                mode: None,
+                original_pos: original_header_pos,
+                source_file: None,
+            },
+        ));
+        processed_header_starts.insert(original_header_pos);
+        all_code_block_ranges.insert((block.start, block.end));
+        processed_code_block_ranges.insert((block.start, block.end));
+    }
+    Ok(())
+}
+
+/// The indented block starting on the first non-blank line after `header_end`, if that line
+/// is indented. The block ends before the next non-blank line that is not indented; blank
+/// lines at its end are not part of it.
+fn find_indented_block(content: &str, header_end: usize) -> Option<IndentedBlock> {
+    let rest_start = content[header_end..]
+        .find('\n')
+        .map_or(content.len(), |n| header_end + n + 1);
+
+    let mut block: Option<IndentedBlock> = None;
+    let mut pending_blank_lines = String::new(); // Kept only if more indented lines follow
+    let mut line_start = rest_start;
+    for line in content[rest_start..].split_inclusive('\n') {
+        let this_line_start = line_start;
+        line_start += line.len();
+        if line.trim().is_empty() {
+            if block.is_some() {
+                pending_blank_lines.push_str(line.trim_start_matches([' ', '\t']));
+            }
+            continue;
+        }
+        let Some(unindented) = line
+            .strip_prefix("    ")
+            .or_else(|| line.strip_prefix('\t'))
+        else {
+            break;
+        };
+        let block = block.get_or_insert_with(|| IndentedBlock {
+            start: this_line_start,
+            end: this_line_start,
+            text: String::new(),
+        });
+        block.text.push_str(&pending_blank_lines);
+        pending_blank_lines.clear();
+        block.text.push_str(unindented);
+        block.end = line_start;
+    }
+    block
+}
@@ -1,13 +1,20 @@
 //! Main markdown parsing logic orchestrator.
 
-use crate::core_types::Action;
+use crate::core_types::{Action, ParseOptions};
 use crate::errors::ParseError;
+use crate::reporter::{ConsoleReporter, Event, Reporter};
+use crate::settings::FrontMatter;
 use std::collections::HashSet; // Removed unused HashMap
 
 // Declare submodules within the parser module
 mod action_checker; // ADDED
+pub(crate) mod conditionals; // `<!-- strux:if flag -->` regions, resolved before the passes
+mod diff_blocks; // Header-less ```diff blocks
+mod front_matter; // Leading ---/+++ settings blocks
 mod header_utils;
 mod helpers;
+mod include; // `## Include:` expansion (reads the included files)
+mod indented_blocks; // Opt-in 4-space indented blocks after content headers
 mod internal_comment;
 mod pass1;
 mod pass2; // Find unassociated content headers and link forward
@@ -23,10 +30,69 @@ mod internal_comment_tests;
 
 // Re-export the main parsing function
 // Removed pub use of check_action_conflicts as it's crate-internal
+pub use self::include::resolve_includes;
 pub use self::regex::{HEADER_REGEX, OPENING_FENCE_REGEX}; // Export regexes if needed by passes
 
-/// Parses markdown content to extract file actions.
+/// Parses markdown content to extract file actions, reporting progress to the console.
 pub fn parse_markdown(markdown_content: &str) -> Result<Vec<Action>, ParseError> {
+    parse_markdown_with_reporter(markdown_content, &ConsoleReporter::new())
+}
+
+/// Parses markdown content to extract file actions, sending diagnostics to `reporter`.
+/// Front matter is validated but its settings are discarded, and no feature flags are
+/// enabled; use `parse_document_with` for either.
+pub fn parse_markdown_with_reporter(
+    markdown_content: &str,
+    reporter: &dyn Reporter,
+) -> Result<Vec<Action>, ParseError> {
+    parse_document_with(markdown_content, &ParseOptions::default(), reporter)
+        .map(|(actions, _)| actions)
+}
+
+/// Parses markdown content to extract file actions and the settings of its front matter
+/// (empty when the document has none), sending diagnostics to `reporter`.
+/// Conditional regions are resolved with the feature flags in `options` first.
+pub fn parse_document_with(
+    markdown_content: &str,
+    options: &ParseOptions,
+    reporter: &dyn Reporter,
+) -> Result<(Vec<Action>, FrontMatter), ParseError> {
+    let (content_to_parse, parse_offset, front_matter) =
+        helpers::preprocess_markdown(markdown_content, reporter)?;
+
+    if content_to_parse.is_empty() && parse_offset > 0 {
+        // Only contained front matter and/or the ignored ```markdown block
+        return Ok((Vec::new(), front_matter));
+    }
+
+    // --- Conditional regions: the passes only see the enabled parts ---
+    let actions = match conditionals::filter_conditionals(
+        content_to_parse,
+        parse_offset,
+        &options.enabled_flags,
+        reporter,
+    )? {
+        Some(filtered) => {
+            let mut actions = run_passes(&filtered.text, 0, options, reporter)
+                .map_err(|e| filtered.original_error(e))?;
+            for action in &mut actions {
+                action.original_pos = filtered.original_pos(action.original_pos);
+            }
+            actions
+        }
+        None => run_passes(content_to_parse, parse_offset, options, reporter)?,
+    };
+    Ok((actions, front_matter))
+}
+
+/// Runs the parsing passes over `content_to_parse`, which starts at `parse_offset` in the
+/// document, and returns the actions in document order.
+fn run_passes(
+    content_to_parse: &str,
+    parse_offset: usize,
+    options: &ParseOptions,
+    reporter: &dyn Reporter,
+) -> Result<Vec<Action>, ParseError> {
     // --- TEMPORARY DEBUG ---
     // Call the debug function to test isolated regex patterns
     // Make sure to run tests with --nocapture to see this output
@@ -42,17 +108,10 @@ pub fn parse_markdown(markdown_content: &str) -> Result<Vec<Action>, ParseError>
     let mut all_code_block_ranges: HashSet<(usize, usize)> = HashSet::new();
     let mut processed_code_block_ranges: HashSet<(usize, usize)> = HashSet::new();
 
-    let (content_to_parse, parse_offset) = helpers::preprocess_markdown(markdown_content);
-
-    if content_to_parse.is_empty() && parse_offset > 0 {
-        // Only contained the ignored ```markdown block
-        return Ok(Vec::new());
-    }
-
     // --- Pass 1: Find Code Blocks and associate actions ---
-    println!(
-        "Step 1: Locating code blocks and associating with adjacent/internal/wrapped headers..."
-    );
+    reporter.report(&Event::Stage(
+        "Step 1: Locating code blocks and associating with adjacent/internal/wrapped headers...",
+    ));
     pass1::run_pass1(
         // Now calls the function in the pass1 module
         content_to_parse,
@@ -61,32 +120,65 @@ pub fn parse_markdown(markdown_content: &str) -> Result<Vec<Action>, ParseError>
         &mut processed_header_starts,
         &mut all_code_block_ranges,
         &mut processed_code_block_ranges,
+        reporter,
     )?;
 
+    // --- Indented code blocks (opt-in): claim them before Pass 2 links headers further on ---
+    if options.indented_code_blocks {
+        reporter.report(&Event::Stage(
+            "Step 1b: Associating remaining content headers with indented code blocks...",
+        ));
+        indented_blocks::run_indented_blocks(
+            content_to_parse,
+            parse_offset,
+            &mut actions_with_pos,
+            &mut processed_header_starts,
+            &mut all_code_block_ranges,
+            &mut processed_code_block_ranges,
+            reporter,
+        )?;
+    }
+
     // --- Pass 2: Find unassociated content headers and link to next block ---
-    println!(
-        "\nStep 2: Locating unassociated content headers and linking to subsequent code blocks..."
-    );
+    reporter.report(&Event::Stage(
+        "Step 2: Locating unassociated content headers and linking to subsequent code blocks...",
+    ));
     pass2::run_pass2(
         content_to_parse,
         parse_offset,
         &mut actions_with_pos,
         &mut processed_header_starts,
         &mut processed_code_block_ranges,
+        reporter,
+    )?;
+
+    // --- Header-less diff blocks: one Patch action per file named by `---`/`+++` lines ---
+    reporter.report(&Event::Stage(
+        "Step 2b: Expanding header-less diff blocks into per-file patches...",
+    ));
+    diff_blocks::run_diff_blocks(
+        content_to_parse,
+        parse_offset,
+        &mut actions_with_pos,
+        &mut processed_code_block_ranges,
+        reporter,
     )?;
 
     // --- Pass 3: Find standalone Delete/Move headers ---
-    println!("\nStep 3: Locating standalone Delete/Move headers...");
+    reporter.report(&Event::Stage(
+        "Step 3: Locating standalone Delete/Move headers...",
+    ));
     pass3::run_pass3(
         content_to_parse,
         parse_offset,
         &mut actions_with_pos,
         &processed_header_starts, // Pass as immutable ref
         &all_code_block_ranges,   // Pass as immutable ref
+        reporter,
     )?;
 
     // --- Sort actions by original position ---
-    println!("\nSorting actions by document order...");
+    reporter.report(&Event::Stage("Sorting actions by document order..."));
     actions_with_pos.sort_by_key(|&(pos, _)| pos);
 
     // Extract sorted action dictionaries
@@ -96,8 +188,11 @@ pub fn parse_markdown(markdown_content: &str) -> Result<Vec<Action>, ParseError>
         .collect();
 
     // --- Final check for conflicting actions on the same path ---
-    action_checker::check_action_conflicts(&final_actions); // Use new module
+    action_checker::check_action_conflicts(&final_actions, reporter); // Use new module
 
-    println!("\nParsing complete. Found {} actions.", final_actions.len());
+    reporter.report(&Event::Info(format!(
+        "\nParsing complete. Found {} actions.",
+        final_actions.len()
+    )));
     Ok(final_actions)
 }
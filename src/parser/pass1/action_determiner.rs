@@ -1,6 +1,7 @@
 //! Determines the action type (external, wrapped, internal) for a given block in Pass 1.
 
 use crate::parser::pass1::{external_header, internal_header, wrapped_header};
+use crate::reporter::Reporter;
 // Import type aliases
 use super::types::DeterminationResult;
 use std::collections::HashSet;
@@ -19,6 +20,7 @@ pub(crate) fn determine_block_action(
     lang: &str,
     processed_header_starts: &mut HashSet<usize>,
     processed_code_block_ranges: &mut HashSet<(usize, usize)>,
+    reporter: &dyn Reporter,
 ) -> DeterminationResult {
     // APPLY THE DeterminationResult TYPE ALIAS HERE
     // Check External Header FIRST
@@ -29,6 +31,7 @@ pub(crate) fn determine_block_action(
         block_content_end,
         parse_offset,
         processed_header_starts,
+        reporter,
     )? {
         return Ok(Some((action, header_pos, "external")));
     }
@@ -43,6 +46,7 @@ pub(crate) fn determine_block_action(
             block_content_end,
             block_outer_end,
             processed_code_block_ranges,
+            reporter,
         )? {
             Some((action, header_pos, next_block_range)) => {
                 // Mark *both* blocks as processed for wrapped actions
@@ -70,6 +74,7 @@ pub(crate) fn determine_block_action(
             block_content_end,
             parse_offset,
             processed_header_starts,
+            reporter,
         )? {
             return Ok(Some((action, header_pos, "internal")));
         }
@@ -2,6 +2,8 @@
 
 use crate::core_types::Action;
 use crate::errors::ParseError;
+use crate::parser::helpers::apply_fence_attributes;
+use crate::reporter::{Event, Reporter};
 // Import the new action_determiner module
 use crate::parser::pass1::{action_adder, action_determiner};
 // Import type aliases - adjust the return type alias usage
@@ -23,6 +25,7 @@ pub(crate) fn process_single_block(
     processed_header_starts: &mut HashSet<usize>,
     all_code_block_ranges: &mut HashSet<(usize, usize)>,
     processed_code_block_ranges: &mut HashSet<(usize, usize)>,
+    reporter: &dyn Reporter,
 ) -> Result<(), ParseError> {
     // Determine the action type and source associated with this block
     // Call determine_block_action from the new module
@@ -36,10 +39,15 @@ pub(crate) fn process_single_block(
         lang,
         processed_header_starts,
         processed_code_block_ranges,
+        reporter,
     )?; // Use ? here
     let action_found = determination_result.is_some();
 
-    if let Some((action, header_pos_rel, action_source)) = determination_result {
+    if let Some((mut action, header_pos_rel, action_source)) = determination_result {
+        // Wrapped actions take their content from another block and apply its attributes there
+        if action_source != "wrapped" {
+            apply_fence_attributes(&mut action, lang, fence_start_pos + parse_offset, reporter)?;
+        }
         action_adder::add_action(
             action,
             header_pos_rel,
@@ -47,13 +55,14 @@ pub(crate) fn process_single_block(
             parse_offset,
             actions_with_pos,
             processed_header_starts,
+            reporter,
         );
     } else {
         // The block was unassociated in this pass. Log it.
-        println!(
+        reporter.report(&Event::Decision(format!(
             "    Code block at original pos {} has no associated action header (checked external, wrapped, internal). Leaving for Pass 2.",
             original_block_start
-        );
+        )));
     }
 
     // Always record that this range is a code block.
@@ -4,6 +4,7 @@ use crate::constants::ACTION_DELETED_FILE;
 use crate::core_types::{Action, ActionType};
 use crate::errors::ParseError;
 use crate::parser::path_utils::validate_path_format;
+use crate::reporter::{Event, Reporter};
 
 /// Specific handler for the "## Deleted File:" header + path in code block format.
 pub(crate) fn handle_external_delete_special_case(
@@ -12,11 +13,12 @@ pub(crate) fn handle_external_delete_special_case(
     block_content_end: usize,
     header_start_rel: usize,
     parse_offset: usize,
+    reporter: &dyn Reporter,
 ) -> Result<Option<Action>, ParseError> {
-    println!(
+    reporter.report(&Event::Decision(format!(
         "    Found external '{}:' header. Checking code block for path...",
         ACTION_DELETED_FILE
-    );
+    )));
     let block_raw_content = &content[block_content_start..block_content_end];
     let block_lines: Vec<&str> = block_raw_content
         .lines()
@@ -25,28 +27,42 @@ pub(crate) fn handle_external_delete_special_case(
         .collect();
 
     if block_lines.is_empty() {
-        eprintln!(
-            "Warning: '{}:' header at original pos {} followed by empty block. Skipping.",
+        reporter.report(&Event::Warning(format!(
+            "'{}:' header at original pos {} followed by empty block. Skipping.",
             ACTION_DELETED_FILE,
             header_start_rel + parse_offset
-        );
+        )));
         Ok(None) // Return Ok(None) so no action is added
     } else {
         let path_from_block = block_lines[0].to_string();
         if block_lines.len() > 1 {
-            eprintln!("Warning: Code block for '{}:' at original pos {} has multiple lines. Using first: '{}'.", ACTION_DELETED_FILE, header_start_rel + parse_offset, path_from_block);
+            reporter.report(&Event::Warning(format!(
+                "Code block for '{}:' at original pos {} has multiple lines. Using first: '{}'.",
+                ACTION_DELETED_FILE,
+                header_start_rel + parse_offset,
+                path_from_block
+            )));
         }
         if validate_path_format(&path_from_block).is_err() {
-            eprintln!("Warning: Invalid path format '{}' in code block for external '{}:' header. Skipping.", path_from_block, ACTION_DELETED_FILE);
+            reporter.report(&Event::Warning(format!(
+                "Invalid path format '{}' in code block for external '{}:' header. Skipping.",
+                path_from_block, ACTION_DELETED_FILE
+            )));
             Ok(None) // Return Ok(None) so no action is added
         } else {
-            println!("      -> Path from code block: '{}'", path_from_block);
+            reporter.report(&Event::Decision(format!(
+                "      -> Path from code block: '{}'",
+                path_from_block
+            )));
             Ok(Some(Action {
                 action_type: ActionType::Delete,
                 path: path_from_block,
                 dest_path: None, // Delete actions don't have a dest_path
                 content: None,
+                anchor: None,
+                mode: None,
                 original_pos: 0, // original_pos set later
+                source_file: None,
             }))
         }
     }
@@ -1,13 +1,14 @@
 //! Handles external headers preceding code blocks in Pass 1.
 
 use crate::constants::ACTION_DELETED_FILE;
-use crate::core_types::{Action, ActionType};
+use crate::core_types::{Action, ActionType, Content};
 use crate::errors::ParseError;
 use crate::parser::header_utils::{extract_header_action_details, get_action_type};
 use crate::parser::helpers::ensure_trailing_newline;
 use crate::parser::pass1::external_delete_special;
 use crate::parser::path_utils::validate_path_format;
 use crate::parser::regex::HEADER_REGEX; // Import the new module
+use crate::reporter::{Event, Reporter};
 use std::collections::HashSet;
 
 /// Checks for and handles an external header preceding a code block.
@@ -20,6 +21,7 @@ pub(crate) fn handle_external_header(
     block_content_end: usize,
     parse_offset: usize,
     processed_header_starts: &mut HashSet<usize>,
+    reporter: &dyn Reporter,
 ) -> Result<Option<(Action, usize)>, ParseError> {
     // Returns (Action, header_start_pos_rel)
     let fence_line_start_rel = content[..fence_start_pos].rfind('\n').map_or(0, |n| n + 1);
@@ -46,16 +48,17 @@ pub(crate) fn handle_external_header(
 
         if action_word_from_capture == Some(ACTION_DELETED_FILE) {
             // If the keyword matches, handle the special case where the path is in the block.
-            println!(
+            reporter.report(&Event::Decision(format!(
                 "    Detected external '{}:' header, invoking special handler.",
                 ACTION_DELETED_FILE
-            );
+            )));
             return external_delete_special::handle_external_delete_special_case(
                 content,
                 block_content_start,
                 block_content_end,
                 prev_line_start_rel,
                 parse_offset,
+                reporter,
             )
             .map(|opt_action| opt_action.map(|a| (a, prev_line_start_rel)));
         }
@@ -63,30 +66,40 @@ pub(crate) fn handle_external_header(
         // If it wasn't the special delete keyword, proceed with normal extraction.
         // This handles ## File:, **File:**, `path`, **`path`**, ## `path`, etc.
         // Also handles Append File, Prepend File.
-        // "Moved File" headers are standalone and should not be associated with code blocks here.
+        // "Moved File" (and other "source to dest") headers are standalone and should not be
+        // associated with code blocks here.
         if let Some(details) = extract_header_action_details(&caps) {
             if details.dest_path.is_some() {
-                // This indicates a "Moved File" header
-                println!(
-                    "    Info: External header '{}' is a 'Moved File' action, which is standalone. Ignoring for this code block.",
-                    stripped_prev_line
-                );
+                // This indicates a "Moved File", "Moved Directory" or "Copied File" header
+                reporter.report(&Event::Decision(format!(
+                    "    Info: External header '{}' is a '{}' action, which is standalone. Ignoring for this code block.",
+                    stripped_prev_line, details.action_word
+                )));
                 return Ok(None);
             }
 
             if validate_path_format(&details.path).is_err() {
-                eprintln!(
-                    "Warning: Invalid path format in external header '{}'. Skipping.",
+                reporter.report(&Event::Warning(format!(
+                    "Invalid path format in external header '{}'. Skipping.",
                     stripped_prev_line
-                );
+                )));
                 processed_header_starts.insert(prev_line_start_rel + parse_offset);
                 return Ok(None);
             }
 
             if let Some(action_type_enum) = get_action_type(&details.action_word) {
                 match action_type_enum {
-                    ActionType::Create | ActionType::Append | ActionType::Prepend => {
-                        println!("    Found external header: '{}'", stripped_prev_line);
+                    ActionType::Create
+                    | ActionType::Append
+                    | ActionType::Prepend
+                    | ActionType::Edit
+                    | ActionType::Patch
+                    | ActionType::InsertAfter
+                    | ActionType::InsertBefore => {
+                        reporter.report(&Event::Decision(format!(
+                            "    Found external header: '{}'",
+                            stripped_prev_line
+                        )));
                         let mut block_data =
                             content[block_content_start..block_content_end].to_string();
                         ensure_trailing_newline(&mut block_data);
@@ -94,14 +107,17 @@ pub(crate) fn handle_external_header(
                             action_type: action_type_enum,
                             path: details.path,
                             dest_path: None,
-                            content: Some(block_data),
+                            content: Some(Content::Text(block_data)),
+                            anchor: details.anchor,
+                            mode: None,
                             original_pos: 0, // Set later in pass1 mod
+                            source_file: None,
                         };
-                        println!(
+                        reporter.report(&Event::Decision(format!(
                             "     -> Added {} action for '{}'",
                             format!("{:?}", action.action_type).to_uppercase(),
                             action.path
-                        );
+                        )));
                         return Ok(Some((action, prev_line_start_rel)));
                     }
                     ActionType::Delete => {
@@ -109,31 +125,43 @@ pub(crate) fn handle_external_header(
                         // The special "Deleted File:" + block case is handled above.
                         // If we reach here with ActionType::Delete, it means it's a standalone
                         // header that shouldn't be associated with this block.
-                        println!(
+                        reporter.report(&Event::Decision(format!(
                             "    Info: External header '{}' is a standalone 'Delete' action. Ignoring for this code block.",
                             stripped_prev_line
-                        );
+                        )));
+                    }
+                    ActionType::CreateDirectory
+                    | ActionType::DeleteDirectory
+                    | ActionType::Include => {
+                        // Directory and include actions carry no content; Pass 3 picks up their headers.
+                        reporter.report(&Event::Decision(format!(
+                            "    Info: External header '{}' is a standalone directory or include action. Ignoring for this code block.",
+                            stripped_prev_line
+                        )));
                     }
-                    ActionType::Move => {
+                    ActionType::Move
+                    | ActionType::MoveDirectory
+                    | ActionType::Copy
+                    | ActionType::Symlink => {
                         // This should have been caught by `details.dest_path.is_some()` check.
                         // If not, it's an error or unexpected state.
-                        eprintln!(
-                            "    Error: Unexpected 'Move' action type for external header associated with a code block: '{}'. Ignoring.",
+                        reporter.report(&Event::Error(format!(
+                            "Unexpected 'Move' action type for external header associated with a code block: '{}'. Ignoring.",
                             stripped_prev_line
-                        );
+                        )));
                     }
                 }
             } else {
-                println!(
+                reporter.report(&Event::Decision(format!(
                     "    Info: External header '{}' matched regex but action type was not recognized. Ignoring.",
                     stripped_prev_line
-                );
+                )));
             }
         } else {
-            println!(
+            reporter.report(&Event::Decision(format!(
                 "    Info: External header '{}' matched regex but failed path extraction. Ignoring.",
                 stripped_prev_line
-            );
+            )));
         }
         // If path extraction failed or action wasn't Create/Append/Prepend, fall through to Ok(None)
     }
@@ -1,11 +1,13 @@
 //! Utility functions for finding matching fences.
 
-use regex::{Match, RegexBuilder};
+use crate::reporter::{Event as ReportEvent, Reporter};
+use regex::{Match, Regex, RegexBuilder};
 
 // Enum to represent the type of fence event for sorting.
 // Lower discriminant = higher priority if at same position.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 enum EventKind {
+    EnclosingClose,
     TargetClose,
     AnyOpen,
 }
@@ -21,27 +23,64 @@ struct Event<'a> {
 }
 
 /// Finds the matching closing fence for a given opening fence, handling nesting.
+/// Fences are runs of 3+ backticks or tildes, and a closer is a line of the same character
+/// that is at least as long as the opener (a bare ```` line closes a ``` block).
 /// This function now also correctly skips over nested blocks that use a *different*
-/// type of fence (e.g., skips a ````...```` block when searching for ```` ``` ```` closers).
+/// type of fence (e.g., skips a ````...```` or ~~~...~~~ block when searching for ``` closers).
 pub(crate) fn find_closing_fence<'a>(
     content: &'a str,
     target_fence_chars: &str, // The type of fence we are trying to close (e.g., "```")
     search_start_pos: usize,
+    reporter: &dyn Reporter,
 ) -> Option<Match<'a>> {
-    println!(
-        "[find_closing_fence] START: target_fence_chars='{}', search_start_pos={}",
-        target_fence_chars, search_start_pos
-    );
+    find_closing_fence_within(
+        content,
+        target_fence_chars,
+        None,
+        search_start_pos,
+        reporter,
+    )
+}
 
-    let escaped_target_fence = regex::escape(target_fence_chars);
-    let target_closing_pattern = format!(r"(?m)^[ \t]*{}[ \t]*$", escaped_target_fence);
-    // Regex for ANY opening fence of 3+ backticks. Capture the fence itself.
-    let any_opening_pattern = r"(?m)^\s*(`{3,})[^`\n\r]*(\r?\n)";
+/// A line closing a block opened by `fence_chars`: the same character, at least as many times.
+fn closing_fence_regex(fence_chars: &str) -> Regex {
+    let fence_char = fence_chars.chars().next().unwrap_or('`');
+    let pattern = format!(
+        r"(?m)^[ \t]*{}{{{},}}[ \t]*$",
+        regex::escape(&fence_char.to_string()),
+        fence_chars.len()
+    );
+    RegexBuilder::new(&pattern).crlf(true).build().unwrap()
+}
 
-    let target_closing_re = RegexBuilder::new(&target_closing_pattern)
-        .crlf(true)
-        .build()
-        .unwrap();
+/// `find_closing_fence` for a block nested in one opened by `enclosing_fence_chars`. A line
+/// closing the enclosing block ends the search (the nested block is unclosed) unless it is
+/// exactly the nested block's own fence, so a stray fence line in a block's content cannot
+/// reach past the block's end. A nested fence of the same character that is longer than the
+/// enclosing one is exempt: that is how a block holds examples of the enclosing fence.
+fn find_closing_fence_within<'a>(
+    content: &'a str,
+    target_fence_chars: &str,
+    enclosing_fence_chars: Option<&str>,
+    search_start_pos: usize,
+    reporter: &dyn Reporter,
+) -> Option<Match<'a>> {
+    reporter.report(&ReportEvent::Trace(format!(
+        "[find_closing_fence] START: target_fence_chars='{}', search_start_pos={}",
+        target_fence_chars, search_start_pos
+    )));
+
+    let target_closing_re = closing_fence_regex(target_fence_chars);
+    let enclosing_closing_re = enclosing_fence_chars
+        .filter(|enclosing| {
+            target_fence_chars.len() <= enclosing.len()
+                || !target_fence_chars.starts_with(&enclosing[..1])
+        })
+        .map(closing_fence_regex);
+    // Regex for ANY opening fence of 3+ backticks or tildes. Capture the fence itself.
+    // `[ \t]*` rather than `\s*`: blank lines before a closing fence must not turn it into an opener
+    // Only the info string of a backtick fence cannot contain backticks (as in CommonMark).
+    let any_opening_pattern = r"(?m)^[ \t]*(?:(`{3,})[^`\n\r]*|(~{3,})[^\n\r]*)(\r?\n)";
     let any_opening_re = RegexBuilder::new(any_opening_pattern)
         .crlf(true)
         .build()
@@ -51,13 +90,25 @@ pub(crate) fn find_closing_fence<'a>(
     let mut current_pos = search_start_pos;
 
     loop {
-        println!(
+        reporter.report(&ReportEvent::Trace(format!(
             "[find_closing_fence] LOOP: current_pos={}, level={}",
             current_pos, level
-        );
+        )));
 
         let mut candidates: Vec<Event> = Vec::new();
 
+        if let Some(m) = enclosing_closing_re
+            .as_ref()
+            .and_then(|re| re.find_at(content, current_pos))
+            .filter(|m| m.as_str().trim() != target_fence_chars)
+        {
+            candidates.push(Event {
+                pos: m.start(),
+                kind: EventKind::EnclosingClose,
+                match_obj: m,
+                fence_chars: None,
+            });
+        }
         if let Some(m) = target_closing_re.find_at(content, current_pos) {
             candidates.push(Event {
                 pos: m.start(),
@@ -69,7 +120,7 @@ pub(crate) fn find_closing_fence<'a>(
         // Find next potential opening fence of *any* type
         if let Some(caps) = any_opening_re.captures_at(content, current_pos) {
             let full_match = caps.get(0).unwrap();
-            let fence_match = caps.get(1).unwrap();
+            let fence_match = caps.get(1).or_else(|| caps.get(2)).unwrap();
             candidates.push(Event {
                 pos: full_match.start(),
                 kind: EventKind::AnyOpen,
@@ -79,10 +130,10 @@ pub(crate) fn find_closing_fence<'a>(
         }
 
         if candidates.is_empty() {
-            println!(
+            reporter.report(&ReportEvent::Trace(format!(
                 "[find_closing_fence] NO MORE EVENTS: No candidates found. level={}",
                 level
-            );
+            )));
             return None;
         }
 
@@ -92,7 +143,7 @@ pub(crate) fn find_closing_fence<'a>(
         let earliest_event = &candidates[0];
         let current_event_match = earliest_event.match_obj;
 
-        println!(
+        reporter.report(&ReportEvent::Trace(format!(
             "[find_closing_fence]   Selected Event: {:?}, match: {:?}",
             earliest_event.kind,
             (
@@ -100,38 +151,49 @@ pub(crate) fn find_closing_fence<'a>(
                 current_event_match.end(),
                 current_event_match.as_str()
             )
-        );
+        )));
 
         match earliest_event.kind {
+            EventKind::EnclosingClose => {
+                reporter.report(&ReportEvent::Trace(format!(
+                    "[find_closing_fence] ENCLOSING BLOCK CLOSED at {}: '{}' is unclosed.",
+                    current_event_match.start(),
+                    target_fence_chars
+                )));
+                return None;
+            }
             EventKind::TargetClose => {
-                println!(
+                reporter.report(&ReportEvent::Trace(format!(
                     "[find_closing_fence]   Event Action: Target Close at {}-{}",
                     current_event_match.start(),
                     current_event_match.end()
-                );
+                )));
                 level -= 1;
                 if level == 0 {
-                    println!(
+                    reporter.report(&ReportEvent::Trace(format!(
                         "[find_closing_fence] MATCH FOUND: level=0, match_pos={}, match_str='{:?}'",
                         current_event_match.start(),
                         current_event_match.as_str()
-                    );
+                    )));
                     return Some(current_event_match);
                 }
                 current_pos = current_event_match.end();
                 if level < 0 {
-                    println!("[find_closing_fence] ERROR: Level < 0 (too many target closers). Returning None.");
+                    reporter.report(&ReportEvent::Trace(
+                        "[find_closing_fence] ERROR: Level < 0 (too many target closers). Returning None."
+                            .to_string(),
+                    ));
                     return None;
                 }
             }
             EventKind::AnyOpen => {
                 let opening_fence_chars = earliest_event.fence_chars.unwrap();
-                println!(
+                reporter.report(&ReportEvent::Trace(format!(
                     "[find_closing_fence]   Event Action: Any Open ('{}') at {}-{}",
                     opening_fence_chars,
                     current_event_match.start(),
                     current_event_match.end()
-                );
+                )));
 
                 if opening_fence_chars == target_fence_chars {
                     // Nested block of the same type
@@ -139,21 +201,29 @@ pub(crate) fn find_closing_fence<'a>(
                     current_pos = current_event_match.end();
                 } else {
                     // Nested block of a different type, we need to find its end and skip over it
-                    if let Some(other_close_match) =
-                        find_closing_fence(content, opening_fence_chars, current_event_match.end())
-                    {
-                        println!(
+                    if let Some(other_close_match) = find_closing_fence_within(
+                        content,
+                        opening_fence_chars,
+                        Some(target_fence_chars),
+                        current_event_match.end(),
+                        reporter,
+                    ) {
+                        reporter.report(&ReportEvent::Trace(format!(
                             "[find_closing_fence]     Skipped nested block from {} to {}",
                             current_event_match.start(),
                             other_close_match.end()
-                        );
+                        )));
                         current_pos = other_close_match.end();
                     } else {
                         // Malformed/unclosed inner block. Instead of failing the whole search,
                         // treat this unclosed opening fence as simple text and continue the
                         // search for the original target from after this line. This handles
                         // cases where content inside a block looks like a fence but isn't.
-                        println!("[find_closing_fence]     Unclosed nested block starting at {}. Treating as text and continuing search for target '{}'.", current_event_match.start(), target_fence_chars);
+                        reporter.report(&ReportEvent::Trace(format!(
+                            "[find_closing_fence]     Unclosed nested block starting at {}. Treating as text and continuing search for target '{}'.",
+                            current_event_match.start(),
+                            target_fence_chars
+                        )));
                         current_pos = current_event_match.end();
                     }
                 }
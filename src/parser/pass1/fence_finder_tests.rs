@@ -3,13 +3,15 @@
 #[cfg(test)]
 mod tests {
     use crate::parser::pass1::fence_finder::find_closing_fence;
+    use crate::reporter::NullReporter;
 
     #[test]
     fn find_simple_closing_fence() {
         let content = "```rust\nfn main() {}\n```";
         let fence_chars = "```";
         let search_start_pos = content.find('\n').unwrap() + 1; // After "```rust\n"
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(closing_match.is_some());
         assert_eq!(closing_match.unwrap().as_str(), "```");
         assert_eq!(
@@ -18,13 +20,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn find_closing_fence_after_blank_lines() {
+        // Blank lines directly before the closer must not make it look like a nested opener
+        let content = "```\n  indented\n\n\n```\n";
+        let search_start_pos = content.find('\n').unwrap() + 1;
+        let closing_match = find_closing_fence(content, "```", search_start_pos, &NullReporter)
+            .expect("closing fence should be found");
+        assert_eq!(closing_match.start(), content.rfind("```").unwrap());
+    }
+
     #[test]
     fn find_closing_fence_no_newline_after_open() {
         // This case should not occur with current OPENING_FENCE_REGEX, but tests robustness
         let content = "```fn main() {}\n```";
         let fence_chars = "```";
         let search_start_pos = 3; // After "```"
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(closing_match.is_some());
         assert_eq!(closing_match.unwrap().as_str(), "```");
     }
@@ -34,7 +47,9 @@ mod tests {
         let content = "```rust\nfn main() {";
         let fence_chars = "```";
         let search_start_pos = content.find('\n').unwrap() + 1;
-        assert!(find_closing_fence(content, fence_chars, search_start_pos).is_none());
+        assert!(
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter).is_none()
+        );
     }
 
     #[test]
@@ -42,7 +57,8 @@ mod tests {
         let content = "```markdown\nOuter\n```rust\nInner\n```\nOuter again\n```";
         let fence_chars = "```";
         let search_start_pos = content.find('\n').unwrap() + 1; // After "```markdown\n"
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(closing_match.is_some());
         assert_eq!(closing_match.unwrap().as_str(), "```");
         assert_eq!(
@@ -56,7 +72,8 @@ mod tests {
         let content = "```L0\n```L1\n```L2\nText\n```\n```\n```";
         let fence_chars = "```";
         let search_start_pos = content.find('\n').unwrap() + 1; // After "```L0\n"
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(closing_match.is_some());
         assert_eq!(
             closing_match.unwrap().start(),
@@ -69,7 +86,8 @@ mod tests {
         let content = "```markdown\nOuter\n````rust\nInner four-tick\n````\nOuter again\n```";
         let fence_chars = "```"; // Looking for three-ticks
         let search_start_pos = content.find('\n').unwrap() + 1;
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(
             closing_match.is_some(),
             "Failed on: find_closing_fence_different_fence_types_ignored. Content:\n{}",
@@ -82,30 +100,45 @@ mod tests {
     }
 
     #[test]
-    fn minimal_different_fence_ignored() {
-        let content = "```\n````\n```"; // Open ```, then ```` (should be ignored), then close ```
+    fn minimal_longer_bare_fence_closes() {
+        let content = "```\n````\n```"; // Open ```, then a bare ```` closes it (longer closer)
         let fence_chars = "```";
         let search_start_pos = content.find('\n').unwrap() + 1; // After "```\n"
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(
             closing_match.is_some(),
             "Minimal case: closing_match should be Some. Content:\n{}",
             content
         );
-        assert_eq!(closing_match.unwrap().as_str(), "```");
+        assert_eq!(closing_match.unwrap().as_str(), "````");
         assert_eq!(
             closing_match.unwrap().start(),
-            content.rfind("```").unwrap(),
+            content.find("````").unwrap(),
             "Minimal case: Mismatched closing fence position"
         );
     }
 
+    #[test]
+    fn minimal_different_fence_with_info_string_ignored() {
+        let content = "```\n````rust\n````\n```"; // Nested ````rust block is skipped as a whole
+        let fence_chars = "```";
+        let search_start_pos = content.find('\n').unwrap() + 1;
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
+        assert_eq!(
+            closing_match.unwrap().start(),
+            content.rfind("```").unwrap()
+        );
+    }
+
     #[test]
     fn find_closing_fence_four_ticks_nested() {
         let content = "````yaml\nOuter\n```bash\nInner three-tick\n```\nOuter again\n````";
         let fence_chars = "````"; // Looking for four-ticks
         let search_start_pos = content.find('\n').unwrap() + 1;
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(closing_match.is_some());
         assert_eq!(
             closing_match.unwrap().start(),
@@ -118,7 +151,8 @@ mod tests {
         let content = "```\n```";
         let fence_chars = "```";
         let search_start_pos = content.find('\n').unwrap() + 1;
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(closing_match.is_some());
         assert_eq!(
             closing_match.unwrap().start(),
@@ -131,7 +165,8 @@ mod tests {
         let content = "```\nBlock\n```\nSome trailing text.";
         let fence_chars = "```";
         let search_start_pos = content.find('\n').unwrap() + 1;
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(closing_match.is_some());
         assert_eq!(
             closing_match.unwrap().start(),
@@ -145,7 +180,8 @@ mod tests {
         let content = "```first\nContent1\n```\n```second\nContent2\n```";
         let fence_chars = "```";
         let search_start_pos = content.find("first\n").unwrap() + "first\n".len();
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(
             closing_match.is_some(),
             "Should find closing fence for the first block"
@@ -175,7 +211,12 @@ mod tests {
         let search_start_pos =
             normalized_content[first_newline + 1..].find('\n').unwrap() + first_newline + 1 + 1;
 
-        let closing_match = find_closing_fence(&normalized_content, fence_chars, search_start_pos);
+        let closing_match = find_closing_fence(
+            &normalized_content,
+            fence_chars,
+            search_start_pos,
+            &NullReporter,
+        );
         assert!(
             closing_match.is_some(),
             "Should find the final closing fence"
@@ -196,7 +237,8 @@ mod tests {
         let fence_chars = "```";
         let search_start_pos = "```md\n".len();
 
-        let closing_match = find_closing_fence(&full_input, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(&full_input, fence_chars, search_start_pos, &NullReporter);
         assert!(
             closing_match.is_some(),
             "find_closing_fence failed for README-like structure. Content:\n{}",
@@ -215,7 +257,8 @@ mod tests {
         let fence_chars = "```";
         let search_start_pos = content.find('\n').unwrap() + 1; // After "```\n"
 
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(
             closing_match.is_some(),
             "Should find closing fence. Content:\n{}",
@@ -237,7 +280,8 @@ mod tests {
         let content = "```\ntext\n```"; // No newline after the final fence
         let fence_chars = "```";
         let search_start_pos = content.find('\n').unwrap() + 1;
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(
             closing_match.is_some(),
             "Fence at EOF not found. Content:\n{}",
@@ -254,7 +298,8 @@ mod tests {
         let content = "```\ntext\n  ```"; // Closing fence has leading spaces
         let fence_chars = "```";
         let search_start_pos = content.find('\n').unwrap() + 1;
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(
             closing_match.is_some(),
             "Fence with leading whitespace not found. Content:\n{}",
@@ -274,7 +319,8 @@ mod tests {
         let content = "```rust\r\nfn main() {}\r\n```\r\n";
         let fence_chars = "```";
         let search_start_pos = content.find("\r\n").unwrap() + 2; // After "```rust\r\n"
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(
             closing_match.is_some(),
             "CRLF: closing_match should be Some. Content:\n{}",
@@ -297,7 +343,8 @@ mod tests {
         let content = "```\nThis line contains ``` as text.\nAnd another ```` as text.\n```";
         let fence_chars = "```";
         let search_start_pos = content.find('\n').unwrap() + 1;
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(
             closing_match.is_some(),
             "Fence chars in text: closing_match should be Some. Content:\n{}",
@@ -315,7 +362,8 @@ mod tests {
         let content = "````yaml\nkey:\n  ```json\n  {\n    \"inner_key\": \"value with ``` backticks\"\n  }\n  ```\nvalue: outer\n````";
         let fence_chars = "````"; // Looking for four-ticks
         let search_start_pos = content.find('\n').unwrap() + 1; // After "````yaml\n"
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(
             closing_match.is_some(),
             "Complex nesting (outer four): closing_match should be Some. Content:\n{}",
@@ -333,7 +381,8 @@ mod tests {
         let content = "```yaml\nkey:\n  ````json\n  {\n    \"inner_key\": \"value with ```` backticks\"\n  }\n  ````\nvalue: outer\n```";
         let fence_chars = "```"; // Looking for three-ticks
         let search_start_pos = content.find('\n').unwrap() + 1; // After "```yaml\n"
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(
             closing_match.is_some(),
             "Complex nesting (outer three): closing_match should be Some. Content:\n{}",
@@ -351,7 +400,8 @@ mod tests {
         let content = "```outer\n  ```inner_unclosed\n  some text in unclosed inner block\n"; // Outer should be unclosed
         let fence_chars = "```";
         let search_start_pos = content.find('\n').unwrap() + 1; // After "```outer\n"
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(
             closing_match.is_none(),
             "Unclosed inner: outer closing_match should be None. Content:\n{}",
@@ -364,7 +414,8 @@ mod tests {
         let content = "```\ntext ``` still on same line\nanother line\n```"; // The middle ``` should be ignored as closer
         let fence_chars = "```";
         let search_start_pos = content.find('\n').unwrap() + 1;
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(
             closing_match.is_some(),
             "Closing not on own line: closing_match should be Some. Content:\n{}",
@@ -378,11 +429,22 @@ mod tests {
     }
 
     #[test]
-    fn unclosed_due_to_mismatched_fence_type_at_end() {
-        let content = "```\n````"; // Opened with ```, only ```` follows
+    fn longer_fence_at_end_closes() {
+        let content = "```\n````"; // Opened with ```, only ```` follows: it is a valid closer
         let fence_chars = "```";
         let search_start_pos = content.find('\n').unwrap() + 1; // After "```\n"
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
+        assert_eq!(closing_match.unwrap().as_str(), "````");
+    }
+
+    #[test]
+    fn unclosed_due_to_mismatched_fence_char_at_end() {
+        let content = "```\n~~~"; // Opened with ```, only ~~~ follows
+        let fence_chars = "```";
+        let search_start_pos = content.find('\n').unwrap() + 1;
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(
             closing_match.is_none(),
             "Unclosed mismatched end: closing_match should be None. Content:\n{}",
@@ -395,7 +457,8 @@ mod tests {
         let content = "````\n```"; // Opened with ````, only ``` follows
         let fence_chars = "````";
         let search_start_pos = content.find('\n').unwrap() + 1; // After "````\n"
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(
             closing_match.is_none(),
             "Unclosed mismatched end (four-tick): closing_match should be None. Content:\n{}",
@@ -408,7 +471,8 @@ mod tests {
         let content = "```rust   \nfn main() {}\n```";
         let fence_chars = "```";
         let search_start_pos = content.find('\n').unwrap() + 1;
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(
             closing_match.is_some(),
             "Lang tag spaces: closing_match should be Some. Content:\n{}",
@@ -426,7 +490,8 @@ mod tests {
         let fence_chars = "```";
         // Test closing for the first block
         let search_start_pos1 = content.find('\n').unwrap() + 1; // After first "```\n"
-        let closing_match1 = find_closing_fence(content, fence_chars, search_start_pos1);
+        let closing_match1 =
+            find_closing_fence(content, fence_chars, search_start_pos1, &NullReporter);
         assert!(
             closing_match1.is_some(),
             "Adjacent fences (block 1): closing_match1 should be Some. Content:\n{}",
@@ -444,7 +509,8 @@ mod tests {
         let content = "```\ntext\n\t```\t"; // Closing fence line has tabs around it
         let fence_chars = "```";
         let search_start_pos = content.find('\n').unwrap() + 1;
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(
             closing_match.is_some(),
             "Closing with tabs: closing_match should be Some. Content:\n{}",
@@ -457,12 +523,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tilde_fence_with_longer_closer() {
+        let content = "~~~python\nprint('~~')\n~~~~~\nafter";
+        let fence_chars = "~~~";
+        let search_start_pos = content.find('\n').unwrap() + 1;
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
+        assert_eq!(closing_match.unwrap().as_str(), "~~~~~");
+    }
+
+    #[test]
+    fn mixed_tilde_and_backtick_nesting() {
+        // A tilde block holding backtick fences, and a backtick block holding a tilde block
+        let content = "~~~markdown\n```rust\nfn f() {}\n```\n```\n~~~";
+        let search_start_pos = content.find('\n').unwrap() + 1;
+        let closing_match = find_closing_fence(content, "~~~", search_start_pos, &NullReporter);
+        assert_eq!(
+            closing_match.unwrap().start(),
+            content.rfind("~~~").unwrap()
+        );
+
+        let content = "```markdown\n~~~~bash\nls\n~~~\n~~~~\n```";
+        let search_start_pos = content.find('\n').unwrap() + 1;
+        let closing_match = find_closing_fence(content, "```", search_start_pos, &NullReporter);
+        assert_eq!(
+            closing_match.unwrap().start(),
+            content.rfind("```").unwrap()
+        );
+    }
+
+    #[test]
+    fn stray_nested_fence_does_not_reach_past_enclosing_closer() {
+        // The lone ``` and ~~~ lines are content of the ```` block, not openers of blocks that
+        // end in the next block (whose fences they would otherwise pair with).
+        let content = "````\n```\n~~~\n````\n\n````\n```\n~~~\n````";
+        let search_start_pos = content.find('\n').unwrap() + 1;
+        let closing_match = find_closing_fence(content, "````", search_start_pos, &NullReporter);
+        assert_eq!(
+            closing_match.unwrap().start(),
+            content.find("````\n\n").unwrap()
+        );
+    }
+
     #[test]
     fn five_tick_block_with_nested_three_and_four() {
         let content = "`````\nOuter five\n```\nInner three\n```\n````\nInner four\n````\nOuter five continues\n`````";
         let fence_chars = "`````";
         let search_start_pos = content.find('\n').unwrap() + 1;
-        let closing_match = find_closing_fence(content, fence_chars, search_start_pos);
+        let closing_match =
+            find_closing_fence(content, fence_chars, search_start_pos, &NullReporter);
         assert!(
             closing_match.is_some(),
             "Failed on five_tick_block. Content:\n{}",
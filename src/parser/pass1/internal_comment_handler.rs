@@ -1,10 +1,11 @@
 //! Handles internal comment headers like `// File: path` or `//path`.
 
-use crate::core_types::{Action, ActionType};
+use crate::core_types::{Action, ActionType, Content};
 use crate::errors::ParseError;
 use crate::parser::header_utils::is_path_valid_for_action; // Import the validation function
 use crate::parser::helpers::ensure_trailing_newline;
 use crate::parser::path_utils::validate_path_format;
+use crate::reporter::{Event, Reporter};
 use std::collections::HashSet;
 
 /// Context for processing an internal comment header.
@@ -24,23 +25,24 @@ pub(crate) fn handle_internal_comment_header(
     include_header: bool,
     context: &InternalCommentContext, // Use context struct
     processed_header_starts: &mut HashSet<usize>,
+    reporter: &dyn Reporter,
 ) -> Result<Option<(Action, usize)>, ParseError> {
     if validate_path_format(&path).is_err() {
-        eprintln!(
-            "Warning: Invalid path format in internal comment header '{}'. Skipping.",
+        reporter.report(&Event::Warning(format!(
+            "Invalid path format in internal comment header '{}'. Skipping.",
             context.stripped_first_line // Use context field
-        );
+        )));
         return Ok(None);
     }
     // Apply general path validity checks, including the space count heuristic
     if !is_path_valid_for_action(&path) {
-        eprintln!(
-            "Warning: Path from internal comment header '{}' failed validity check. Skipping.",
+        reporter.report(&Event::Warning(format!(
+            "Path from internal comment header '{}' failed validity check. Skipping.",
             context.stripped_first_line
-        );
+        )));
         return Ok(None);
     }
-    println!(
+    reporter.report(&Event::Decision(format!(
         "    Found internal comment header: '{}' ({} output)",
         context.stripped_first_line, // Use context field
         if include_header {
@@ -48,7 +50,7 @@ pub(crate) fn handle_internal_comment_header(
         } else {
             "Excluded from"
         }
-    );
+    )));
     processed_header_starts.insert(context.header_original_pos); // Use context field
     let mut final_content = if include_header {
         context.block_content.to_string() // Use context field
@@ -60,10 +62,16 @@ pub(crate) fn handle_internal_comment_header(
         action_type: ActionType::Create,
         path,
         dest_path: None, // Create actions don't have a dest_path
-        content: Some(final_content),
+        content: Some(Content::Text(final_content)),
+        anchor: None,
+        mode: None,
         original_pos: 0, // Set later in pass1 mod
+        source_file: None,
     };
-    println!("     -> Added CREATE action for '{}'", action.path);
+    reporter.report(&Event::Decision(format!(
+        "     -> Added CREATE action for '{}'",
+        action.path
+    )));
     // Return the block content start position from the context
     Ok(Some((action, context.block_content_start))) // Use context field
 }
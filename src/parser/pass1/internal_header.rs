@@ -4,6 +4,7 @@ use crate::core_types::Action; // Import Action
 use crate::errors::ParseError;
 use crate::parser::helpers::{is_likely_comment, is_likely_string};
 use crate::parser::internal_comment::extract_path_from_internal_comment;
+use crate::reporter::{Event, Reporter};
 // Import the specific handler function and the context struct
 use crate::parser::pass1::internal_comment_handler;
 use crate::parser::pass1::internal_standard_handler;
@@ -20,6 +21,7 @@ pub(crate) fn handle_internal_header(
     block_content_end: usize,
     parse_offset: usize,
     processed_header_starts: &mut HashSet<usize>, // Add this argument
+    reporter: &dyn Reporter,
 ) -> Result<Option<(Action, usize)>, ParseError> {
     // Returns (Action, header_start_pos_rel)
     let block_content = &content[block_content_start..block_content_end];
@@ -49,6 +51,7 @@ pub(crate) fn handle_internal_header(
             include_header,
             &context, // Pass context by reference
             processed_header_starts,
+            reporter,
         );
     }
 
@@ -57,10 +60,10 @@ pub(crate) fn handle_internal_header(
     if let Some(caps) = HEADER_REGEX.captures(first_line) {
         // Apply heuristics *before* trying to extract path/action
         if is_likely_string(stripped_first_line) {
-            println!(
+            reporter.report(&Event::Decision(format!(
                 "    Info: Ignoring potential internal header (matched string heuristic): '{}'",
                 stripped_first_line
-            );
+            )));
             return Ok(None); // Ignore
         }
 
@@ -71,10 +74,10 @@ pub(crate) fn handle_internal_header(
             // we MUST allow lines starting with '#' to proceed to extraction.
             // We filter out other comment types (//, --, etc.) unless they were handled by extract_path_from_internal_comment above.
             if !stripped_first_line.starts_with('#') {
-                println!(
+                reporter.report(&Event::Decision(format!(
                     "    Info: Ignoring potential internal header (matched comment heuristic): '{}'",
                     stripped_first_line
-                );
+                )));
                 return Ok(None);
             }
             // If it starts with '#', we proceed. The HEADER_REGEX match implies it has the structure of a header.
@@ -89,6 +92,7 @@ pub(crate) fn handle_internal_header(
             header_original_pos,
             block_content_start,
             processed_header_starts,
+            reporter,
         );
     }
 
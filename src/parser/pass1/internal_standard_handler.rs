@@ -1,11 +1,12 @@
 //! Handles internal standard headers like `**File:**` or `## File:`.
 
 use crate::constants::ACTION_DELETED_FILE;
-use crate::core_types::{Action, ActionType};
+use crate::core_types::{Action, ActionType, Content};
 use crate::errors::ParseError;
 use crate::parser::header_utils::{extract_header_action_details, get_action_type};
 use crate::parser::helpers::ensure_trailing_newline;
 use crate::parser::path_utils::validate_path_format;
+use crate::reporter::{Event, Reporter};
 use std::collections::HashSet;
 
 /// Handles internal standard headers like `**File:**` or `## File:`.
@@ -16,33 +17,40 @@ pub(crate) fn handle_internal_standard_header(
     header_original_pos: usize,
     block_content_start: usize,
     processed_header_starts: &mut HashSet<usize>,
+    reporter: &dyn Reporter,
 ) -> Result<Option<(Action, usize)>, ParseError> {
     if let Some(details) = extract_header_action_details(&caps) {
-        // "Moved File" headers are not valid inside code blocks.
+        // "Moved File" (and other "source to dest") headers are not valid inside code blocks.
         if details.dest_path.is_some() {
-            println!(
-                "Info: Ignoring 'Moved File:' header inside code block at original pos {}.",
-                header_original_pos
-            );
+            reporter.report(&Event::Decision(format!(
+                "Info: Ignoring '{}:' header inside code block at original pos {}.",
+                details.action_word, header_original_pos
+            )));
             processed_header_starts.insert(header_original_pos);
             return Ok(None);
         }
 
         if validate_path_format(&details.path).is_err() {
-            eprintln!(
-                "Warning: Invalid path format in internal standard header '{}'. Skipping.",
+            reporter.report(&Event::Warning(format!(
+                "Invalid path format in internal standard header '{}'. Skipping.",
                 stripped_first_line
-            );
+            )));
             return Ok(None);
         }
 
         if let Some(action_type_enum) = get_action_type(&details.action_word) {
             match action_type_enum {
-                ActionType::Create | ActionType::Append | ActionType::Prepend => {
-                    println!(
+                ActionType::Create
+                | ActionType::Append
+                | ActionType::Prepend
+                | ActionType::Edit
+                | ActionType::Patch
+                | ActionType::InsertAfter
+                | ActionType::InsertBefore => {
+                    reporter.report(&Event::Decision(format!(
                         "    Found internal standard header: '{}' (Excluded from output)",
                         stripped_first_line
-                    );
+                    )));
                     processed_header_starts.insert(header_original_pos);
                     let mut block_data = rest_content.to_string();
                     ensure_trailing_newline(&mut block_data);
@@ -50,30 +58,44 @@ pub(crate) fn handle_internal_standard_header(
                         action_type: action_type_enum,
                         path: details.path,
                         dest_path: None,
-                        content: Some(block_data),
+                        content: Some(Content::Text(block_data)),
+                        anchor: details.anchor,
+                        mode: None,
                         original_pos: 0,
+                        source_file: None,
                     };
-                    println!(
+                    reporter.report(&Event::Decision(format!(
                         "     -> Added {} action for '{}'",
                         format!("{:?}", action.action_type).to_uppercase(),
                         action.path
-                    );
+                    )));
                     return Ok(Some((action, block_content_start)));
                 }
                 ActionType::Delete => {
-                    println!(
+                    reporter.report(&Event::Decision(format!(
                         "Info: Ignoring '{}:' header inside code block at original pos {}.",
                         ACTION_DELETED_FILE, header_original_pos
-                    );
+                    )));
                     processed_header_starts.insert(header_original_pos);
                     return Ok(None);
                 }
-                ActionType::Move => {
+                ActionType::CreateDirectory | ActionType::DeleteDirectory | ActionType::Include => {
+                    reporter.report(&Event::Decision(format!(
+                        "Info: Ignoring '{}:' header inside code block at original pos {}.",
+                        details.action_word, header_original_pos
+                    )));
+                    processed_header_starts.insert(header_original_pos);
+                    return Ok(None);
+                }
+                ActionType::Move
+                | ActionType::MoveDirectory
+                | ActionType::Copy
+                | ActionType::Symlink => {
                     // This should have been caught by `details.dest_path.is_some()` check.
-                    println!(
+                    reporter.report(&Event::Decision(format!(
                         "Info: Ignoring 'Moved File:' header inside code block at original pos {}.",
                         header_original_pos
-                    );
+                    )));
                     processed_header_starts.insert(header_original_pos);
                     return Ok(None);
                 }
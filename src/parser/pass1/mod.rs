@@ -3,6 +3,7 @@
 use crate::core_types::Action;
 use crate::errors::ParseError;
 use crate::parser::regex::OPENING_FENCE_REGEX;
+use crate::reporter::{Event, Reporter};
 use std::collections::HashSet;
 
 // Declare submodules for Pass 1
@@ -31,6 +32,7 @@ pub(crate) fn run_pass1(
     processed_header_starts: &mut HashSet<usize>,
     all_code_block_ranges: &mut HashSet<(usize, usize)>,
     processed_code_block_ranges: &mut HashSet<(usize, usize)>,
+    reporter: &dyn Reporter,
 ) -> Result<(), ParseError> {
     let mut current_search_pos = 0;
     while let Some(caps) = OPENING_FENCE_REGEX.captures_at(content_to_parse, current_search_pos) {
@@ -53,15 +55,19 @@ pub(crate) fn run_pass1(
         let fence_chars = caps.name("fence").unwrap().as_str();
         let lang = caps.name("lang").map(|m| m.as_str().trim()).unwrap_or("");
 
-        let closing_match_opt =
-            fence_finder::find_closing_fence(content_to_parse, fence_chars, fence_end_pos);
+        let closing_match_opt = fence_finder::find_closing_fence(
+            content_to_parse,
+            fence_chars,
+            fence_end_pos,
+            reporter,
+        );
 
         if closing_match_opt.is_none() {
             let original_pos = fence_start_pos + parse_offset;
-            eprintln!(
-                "Warning: Opening fence '{}' at original pos {} has no closing fence. Skipping.",
+            reporter.report(&Event::Warning(format!(
+                "Opening fence '{}' at original pos {} has no closing fence. Skipping.",
                 fence_chars, original_pos
-            );
+            )));
             current_search_pos = fence_end_pos; // Advance past this unclosed opening fence line
             continue;
         }
@@ -72,13 +78,12 @@ pub(crate) fn run_pass1(
         let block_outer_end = closing_match.end(); // End of the closing fence line
         let original_block_start = fence_start_pos + parse_offset;
 
-        println!(
-            "  - Found code block: '{}' (lang: '{}') from original pos {} to {}",
-            fence_chars,
-            if lang.is_empty() { "none" } else { lang },
-            original_block_start,
-            block_outer_end + parse_offset
-        );
+        reporter.report(&Event::BlockFound {
+            fence: fence_chars,
+            lang,
+            start: original_block_start,
+            end: block_outer_end + parse_offset,
+        });
 
         block_processor::process_single_block(
             content_to_parse,
@@ -93,6 +98,7 @@ pub(crate) fn run_pass1(
             processed_header_starts,
             all_code_block_ranges,
             processed_code_block_ranges,
+            reporter,
         )?;
 
         current_search_pos = block_outer_end; // Advance search to after the current block
@@ -1,9 +1,10 @@
 //! Handles the specific logic for a wrapped 'Create', 'Append', or 'Prepend' header in Pass 1.
 
-use crate::core_types::{Action, ActionType};
+use crate::core_types::{Action, ActionType, Content};
+use crate::reporter::{Event, Reporter};
 // Removed unused ParseError import
 // use crate::errors::ParseError;
-use crate::parser::helpers::ensure_trailing_newline;
+use crate::parser::helpers::{apply_fence_attributes, ensure_trailing_newline};
 use crate::parser::pass1::{fence_finder, utils};
 // Import type aliases
 use super::types::WrappedActionResult;
@@ -20,8 +21,10 @@ pub(crate) fn handle_wrapped_content_action(
     block_outer_end: usize,  // End of the ```markdown block
     action_type: ActionType, // Pass the determined ActionType
     path: &str,
+    anchor: Option<&str>, // For Insert After/Insert Before
     potential_header_line: &str,
     processed_code_block_ranges: &mut HashSet<(usize, usize)>,
+    reporter: &dyn Reporter,
 ) -> WrappedActionResult {
     // Use type alias here
     // Look for the *next* code block immediately after this one
@@ -34,19 +37,22 @@ pub(crate) fn handle_wrapped_content_action(
         // Check if it's immediately adjacent (allow whitespace lines between)
         let gap = &content_to_parse[block_outer_end..next_fence_match.start()];
         if gap.trim().is_empty() {
-            println!(
+            reporter.report(&Event::Decision(format!(
                 "    Found wrapped header '{}' ({:?}) associated with the following code block.",
                 potential_header_line, action_type
-            );
+            )));
 
             // Now process the *next* block using the header info we just extracted
             let next_fence_start = next_fence_match.start();
             let next_fence_end = next_fence_match.end();
             let next_fence_chars = next_fence_caps.name("fence").unwrap().as_str();
 
-            if let Some(next_closing_match) =
-                fence_finder::find_closing_fence(content_to_parse, next_fence_chars, next_fence_end)
-            {
+            if let Some(next_closing_match) = fence_finder::find_closing_fence(
+                content_to_parse,
+                next_fence_chars,
+                next_fence_end,
+                reporter,
+            ) {
                 let next_content_start = next_fence_end;
                 let next_content_end = next_closing_match.start();
                 let next_outer_end = next_closing_match.end();
@@ -56,30 +62,49 @@ pub(crate) fn handle_wrapped_content_action(
                 ensure_trailing_newline(&mut block_data);
 
                 // Create the action using the wrapped header info
-                let action = Action {
+                let mut action = Action {
                     action_type, // Use passed ActionType
                     path: path.to_string(),
                     dest_path: None,
-                    content: Some(block_data),
+                    content: Some(Content::Text(block_data)),
+                    anchor: anchor.map(str::to_string),
+                    mode: None,
                     original_pos: fence_start_pos + parse_offset,
+                    source_file: None,
                 };
+                let next_lang = next_fence_caps.name("lang").map_or("", |m| m.as_str());
+                apply_fence_attributes(
+                    &mut action,
+                    next_lang,
+                    next_fence_start + parse_offset,
+                    reporter,
+                )?;
                 let next_block_range = (next_fence_start, next_outer_end);
-                println!(
+                reporter.report(&Event::Decision(format!(
                     "     -> Added {:?} action for '{}' from wrapped header.",
                     action.action_type, path
-                );
+                )));
                 return Ok(Some((action, fence_start_pos, next_block_range)));
             } else {
-                eprintln!("Warning: Found wrapped {:?} header '{}' but the following code block is unclosed. Skipping.", action_type, potential_header_line);
+                reporter.report(&Event::Warning(format!(
+                    "Found wrapped {:?} header '{}' but the following code block is unclosed. Skipping.",
+                    action_type,
+                    potential_header_line
+                )));
             }
         } else {
-            eprintln!("Warning: Found wrapped {:?} header '{}' but it's not immediately followed by a code block (gap='{}'). Skipping.", action_type, potential_header_line, gap.escape_debug());
+            reporter.report(&Event::Warning(format!(
+                "Found wrapped {:?} header '{}' but it's not immediately followed by a code block (gap='{}'). Skipping.",
+                action_type,
+                potential_header_line,
+                gap.escape_debug()
+            )));
         }
     } else {
-        eprintln!(
-            "Warning: Found wrapped {:?} header '{}' but no subsequent code block found. Skipping.",
+        reporter.report(&Event::Warning(format!(
+            "Found wrapped {:?} header '{}' but no subsequent code block found. Skipping.",
             action_type, potential_header_line
-        );
+        )));
     }
     Ok(None)
 }
@@ -1,6 +1,7 @@
 //! Handles wrapped headers (header inside ```markdown block) in Pass 1.
 
 use crate::core_types::{Action, ActionType};
+use crate::reporter::{Event, Reporter};
 // Removed unused ParseError import
 // use crate::errors::ParseError;
 use crate::parser::header_utils::{extract_header_action_details, get_action_type};
@@ -21,6 +22,7 @@ pub(crate) fn handle_wrapped_header(
     block_content_end: usize,
     block_outer_end: usize,
     processed_code_block_ranges: &mut HashSet<(usize, usize)>,
+    reporter: &dyn Reporter,
 ) -> WrappedActionResult {
     // Use type alias here
     // Returns (Action, header_start_pos_rel, next_block_range)
@@ -33,32 +35,39 @@ pub(crate) fn handle_wrapped_header(
 
     if trimmed_lines.len() == 1 {
         let potential_header_line = trimmed_lines[0];
-        println!(
+        reporter.report(&Event::Decision(format!(
             "    Checking single line inside markdown block: '{}'",
             potential_header_line
-        );
+        )));
         if let Some(header_caps) = HEADER_REGEX.captures(potential_header_line) {
             if let Some(details) = extract_header_action_details(&header_caps) {
                 if validate_path_format(&details.path).is_err() {
-                    eprintln!(
-                        "Warning: Invalid path format in wrapped header '{}' (path: '{}'). Skipping.",
+                    reporter.report(&Event::Warning(format!(
+                        "Invalid path format in wrapped header '{}' (path: '{}'). Skipping.",
                         potential_header_line, details.path
-                    );
+                    )));
                     return Ok(None);
                 }
                 if let Some(ref dest_path_val) = details.dest_path {
                     if validate_path_format(dest_path_val).is_err() {
-                        eprintln!(
-                            "Warning: Invalid destination path format in wrapped header '{}' (dest_path: '{}'). Skipping.",
-                            potential_header_line, dest_path_val
-                        );
+                        reporter.report(&Event::Warning(format!(
+                            "Invalid destination path format in wrapped header '{}' (dest_path: '{}'). Skipping.",
+                            potential_header_line,
+                            dest_path_val
+                        )));
                         return Ok(None);
                     }
                 }
 
                 if let Some(action_type) = get_action_type(&details.action_word) {
                     match action_type {
-                        ActionType::Create | ActionType::Append | ActionType::Prepend => {
+                        ActionType::Create
+                        | ActionType::Append
+                        | ActionType::Prepend
+                        | ActionType::Edit
+                        | ActionType::Patch
+                        | ActionType::InsertAfter
+                        | ActionType::InsertBefore => {
                             // Delegate to specific handler for content-based actions
                             return wrapped_create_handler::handle_wrapped_content_action(
                                 content_to_parse,
@@ -67,60 +76,95 @@ pub(crate) fn handle_wrapped_header(
                                 block_outer_end,
                                 action_type, // Pass the determined action_type
                                 &details.path,
+                                details.anchor.as_deref(),
                                 potential_header_line,
                                 processed_code_block_ranges,
+                                reporter,
                             );
                         }
                         ActionType::Delete => {
-                            println!(
+                            reporter.report(&Event::Decision(format!(
                                 "    Found wrapped standalone DELETE action for: '{}'",
                                 details.path
-                            );
+                            )));
                             let action = Action {
                                 action_type: ActionType::Delete,
                                 path: details.path,
                                 dest_path: None,
                                 content: None,
+                                anchor: None,
+                                mode: None,
                                 original_pos: fence_start_pos + parse_offset,
+                                source_file: None,
                             };
                             return Ok(Some((action, fence_start_pos, (0, 0))));
                         }
-                        ActionType::Move => {
-                            println!(
-                                "    Found wrapped standalone MOVE action for: '{}' to '{}'",
+                        ActionType::CreateDirectory
+                        | ActionType::DeleteDirectory
+                        | ActionType::Include => {
+                            reporter.report(&Event::Decision(format!(
+                                "    Found wrapped standalone {} action for: '{}'",
+                                format!("{:?}", action_type).to_uppercase(),
+                                details.path
+                            )));
+                            let action = Action {
+                                action_type,
+                                path: details.path,
+                                dest_path: None,
+                                content: None,
+                                anchor: None,
+                                mode: None,
+                                original_pos: fence_start_pos + parse_offset,
+                                source_file: None,
+                            };
+                            return Ok(Some((action, fence_start_pos, (0, 0))));
+                        }
+                        ActionType::Move
+                        | ActionType::MoveDirectory
+                        | ActionType::Copy
+                        | ActionType::Symlink => {
+                            reporter.report(&Event::Decision(format!(
+                                "    Found wrapped standalone {} action for: '{}' to '{}'",
+                                format!("{:?}", action_type).to_uppercase(),
                                 details.path,
                                 details.dest_path.as_ref().unwrap_or(&String::new())
-                            );
+                            )));
                             let action = Action {
-                                action_type: ActionType::Move,
+                                action_type,
                                 path: details.path,
                                 dest_path: details.dest_path,
                                 content: None,
+                                anchor: None,
+                                mode: None,
                                 original_pos: fence_start_pos + parse_offset,
+                                source_file: None,
                             };
                             return Ok(Some((action, fence_start_pos, (0, 0))));
                         }
                     }
                 } else {
-                    println!("    Single line in markdown block ('{}') did not contain a valid action word.", potential_header_line);
+                    reporter.report(&Event::Decision(format!(
+                        "    Single line in markdown block ('{}') did not contain a valid action word.",
+                        potential_header_line
+                    )));
                 }
             } else {
-                println!(
+                reporter.report(&Event::Decision(format!(
                     "    Single line in markdown block ('{}') did not yield a valid path or action details.",
                     potential_header_line
-                );
+                )));
             }
         } else {
-            println!(
+            reporter.report(&Event::Decision(format!(
                 "    Single line in markdown block ('{}') did not match HEADER_REGEX.",
                 potential_header_line
-            );
+            )));
         }
     } else {
-        println!(
+        reporter.report(&Event::Decision(format!(
             "    Markdown block does not contain exactly one non-empty line (found {}). Treating as regular content.",
             trimmed_lines.len()
-        );
+        )));
     }
     Ok(None)
 }
@@ -1,11 +1,12 @@
 //! Logic for Pass 2 of markdown parsing: Finding unassociated content headers and linking them forward.
 
-use crate::core_types::Action;
+use crate::core_types::{Action, Content};
 use crate::errors::ParseError;
 use crate::parser::header_utils::{extract_header_action_details, get_action_type};
-use crate::parser::helpers::ensure_trailing_newline;
+use crate::parser::helpers::{apply_fence_attributes, ensure_trailing_newline};
 use crate::parser::pass1::{fence_finder, utils as pass1_utils}; // Reuse utils from pass1
 use crate::parser::regex::HEADER_REGEX;
+use crate::reporter::{Event, Reporter};
 use std::collections::HashSet;
 
 /// Executes Pass 2: Find unassociated headers for content actions (`File`, `Append`, `Prepend`)
@@ -17,6 +18,7 @@ pub(crate) fn run_pass2(
     actions_with_pos: &mut Vec<(usize, Action)>,
     processed_header_starts: &mut HashSet<usize>,
     processed_code_block_ranges: &mut HashSet<(usize, usize)>,
+    reporter: &dyn Reporter,
 ) -> Result<(), ParseError> {
     for caps in HEADER_REGEX.captures_iter(content_to_parse) {
         let header_match = caps.get(0).unwrap(); // The whole match
@@ -38,12 +40,16 @@ pub(crate) fn run_pass2(
                 match action_type {
                     crate::core_types::ActionType::Create
                     | crate::core_types::ActionType::Append
-                    | crate::core_types::ActionType::Prepend => {
+                    | crate::core_types::ActionType::Prepend
+                    | crate::core_types::ActionType::Edit
+                    | crate::core_types::ActionType::Patch
+                    | crate::core_types::ActionType::InsertAfter
+                    | crate::core_types::ActionType::InsertBefore => {
                         // This is an orphaned content header. Let's find its block.
-                        println!(
+                        reporter.report(&Event::Decision(format!(
                             "  - Found unassociated content header for '{}'. Searching for next code block...",
                             details.path
-                        );
+                        )));
 
                         // Find the next available code block after this header
                         if let Some(next_fence_caps) = pass1_utils::find_next_opening_fence(
@@ -61,12 +67,13 @@ pub(crate) fn run_pass2(
                                 content_to_parse,
                                 next_fence_chars,
                                 next_fence_end,
+                                reporter,
                             ) {
                                 // Successfully found a complete, unprocessed block. Associate it.
-                                println!(
+                                reporter.report(&Event::Decision(format!(
                                     "    -> Associated with code block at relative pos {}",
                                     next_fence_start
-                                );
+                                )));
 
                                 let next_content_start = next_fence_end;
                                 let next_content_end = next_closing_match.start();
@@ -77,13 +84,24 @@ pub(crate) fn run_pass2(
                                     .to_string();
                                 ensure_trailing_newline(&mut block_data);
 
-                                let action = Action {
+                                let mut action = Action {
                                     action_type,
                                     path: details.path,
                                     dest_path: None,
-                                    content: Some(block_data),
+                                    content: Some(Content::Text(block_data)),
+                                    anchor: details.anchor,
+                                    mode: None,
                                     original_pos: original_header_pos,
+                                    source_file: None,
                                 };
+                                let next_lang =
+                                    next_fence_caps.name("lang").map_or("", |m| m.as_str());
+                                apply_fence_attributes(
+                                    &mut action,
+                                    next_lang,
+                                    next_fence_start + parse_offset,
+                                    reporter,
+                                )?;
                                 actions_with_pos.push((original_header_pos, action));
 
                                 // Mark both as processed so they aren't picked up again
@@ -92,22 +110,31 @@ pub(crate) fn run_pass2(
                                     .insert((next_fence_start, next_outer_end));
                             } else {
                                 // Found an opening fence but it was unclosed.
-                                eprintln!(
-                                    "Warning: Found header '{}' for path '{}' but the next code block was unclosed. Skipping.",
-                                    header_match.as_str().trim(), details.path
-                                );
+                                reporter.report(&Event::Warning(format!(
+                                    "Found header '{}' for path '{}' but the next code block was unclosed. Skipping.",
+                                    header_match.as_str().trim(),
+                                    details.path
+                                )));
                             }
                         } else {
                             // No subsequent code block found for this header.
-                            eprintln!(
-                                "Warning: Found header '{}' for path '{}' at original pos {} without an associated code block. Skipping.",
-                                header_match.as_str().trim(), details.path, original_header_pos
-                            );
+                            reporter.report(&Event::Warning(format!(
+                                "Found header '{}' for path '{}' at original pos {} without an associated code block. Skipping.",
+                                header_match.as_str().trim(),
+                                details.path,
+                                original_header_pos
+                            )));
                         }
                     }
-                    // Delete and Move actions will be handled in Pass 3
-                    crate::core_types::ActionType::Delete | crate::core_types::ActionType::Move => {
-                    }
+                    // Delete, Move, Copy, Symlink, Include and directory actions will be handled in Pass 3
+                    crate::core_types::ActionType::Delete
+                    | crate::core_types::ActionType::Move
+                    | crate::core_types::ActionType::MoveDirectory
+                    | crate::core_types::ActionType::Copy
+                    | crate::core_types::ActionType::Symlink
+                    | crate::core_types::ActionType::CreateDirectory
+                    | crate::core_types::ActionType::DeleteDirectory
+                    | crate::core_types::ActionType::Include => {}
                 }
             }
         }
@@ -32,7 +32,10 @@ pub(crate) fn handle_standalone_delete(
                 path: path.to_string(),
                 dest_path: None, // Delete actions don't have a dest_path
                 content: None,
+                anchor: None,
+                mode: None,
                 original_pos: original_header_pos,
+                source_file: None,
             },
         ));
         // Mark header as processed? No, Pass 2 iterates once.
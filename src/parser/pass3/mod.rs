@@ -5,12 +5,13 @@ use crate::errors::ParseError;
 use crate::parser::header_utils::{extract_header_action_details, get_action_type};
 use crate::parser::path_utils::validate_path_format;
 use crate::parser::regex::HEADER_REGEX;
+use crate::reporter::{Event, Reporter};
 use std::collections::HashSet;
 
 // Declare submodules for Pass 3
 mod standalone_delete;
 
-/// Executes Pass 3: Find standalone Delete/Move headers.
+/// Executes Pass 3: Find standalone Delete/Move/Copy/Symlink/Directory/Include headers.
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn run_pass3(
     content_to_parse: &str,
@@ -18,6 +19,7 @@ pub(crate) fn run_pass3(
     actions_with_pos: &mut Vec<(usize, Action)>,
     processed_header_starts: &HashSet<usize>, // Read-only access needed
     all_code_block_ranges: &HashSet<(usize, usize)>, // Use this to skip headers inside blocks
+    reporter: &dyn Reporter,
 ) -> Result<(), ParseError> {
     for caps in HEADER_REGEX.captures_iter(content_to_parse) {
         let header_match = caps.get(0).unwrap(); // The whole match
@@ -40,21 +42,21 @@ pub(crate) fn run_pass3(
         if let Some(details) = extract_header_action_details(&caps) {
             // Validate primary path
             if validate_path_format(&details.path).is_err() {
-                eprintln!(
-                    "Warning: Invalid path format in standalone header '{}' (path: '{}'). Skipping.",
+                reporter.report(&Event::Warning(format!(
+                    "Invalid path format in standalone header '{}' (path: '{}'). Skipping.",
                     header_match.as_str().trim(),
                     details.path
-                );
+                )));
                 continue;
             }
             // Validate destination path if it's a Move action
             if let Some(ref dest_path_val) = details.dest_path {
                 if validate_path_format(dest_path_val).is_err() {
-                    eprintln!(
-                        "Warning: Invalid destination path format in standalone header '{}' (dest_path: '{}'). Skipping.",
+                    reporter.report(&Event::Warning(format!(
+                        "Invalid destination path format in standalone header '{}' (dest_path: '{}'). Skipping.",
                         header_match.as_str().trim(),
                         dest_path_val
-                    );
+                    )));
                     continue;
                 }
             }
@@ -66,28 +68,61 @@ pub(crate) fn run_pass3(
                             original_header_pos,
                             &details.path,
                             actions_with_pos,
+                            reporter,
                         );
                     }
                     crate::core_types::ActionType::Create
                     | crate::core_types::ActionType::Append
-                    | crate::core_types::ActionType::Prepend => {
+                    | crate::core_types::ActionType::Prepend
+                    | crate::core_types::ActionType::Edit
+                    | crate::core_types::ActionType::Patch
+                    | crate::core_types::ActionType::InsertAfter
+                    | crate::core_types::ActionType::InsertBefore => {
                         // The warning for this is now handled by pass2 if no block is found.
                         // So, we do nothing here.
                     }
-                    crate::core_types::ActionType::Move => {
+                    crate::core_types::ActionType::Move
+                    | crate::core_types::ActionType::MoveDirectory
+                    | crate::core_types::ActionType::Copy
+                    | crate::core_types::ActionType::Symlink => {
                         // Add Move action
-                        println!(
-                            "  - Found standalone MOVE action for: '{}' to '{}' at original pos {}",
+                        reporter.report(&Event::Decision(format!(
+                            "  - Found standalone {} action for: '{}' to '{}' at original pos {}",
+                            format!("{:?}", action_type).to_uppercase(),
                             details.path,
                             details.dest_path.as_ref().unwrap_or(&String::new()), // Should always be Some for Move
                             original_header_pos
-                        );
+                        )));
                         let action = Action {
-                            action_type: crate::core_types::ActionType::Move,
+                            action_type,
                             path: details.path,
                             dest_path: details.dest_path, // This will be Some if action_type is Move
                             content: None,
+                            anchor: None,
+                            mode: None,
+                            original_pos: original_header_pos,
+                            source_file: None,
+                        };
+                        actions_with_pos.push((original_header_pos, action));
+                    }
+                    crate::core_types::ActionType::CreateDirectory
+                    | crate::core_types::ActionType::DeleteDirectory
+                    | crate::core_types::ActionType::Include => {
+                        reporter.report(&Event::Decision(format!(
+                            "  - Found standalone {} action for: '{}' at original pos {}",
+                            format!("{:?}", action_type).to_uppercase(),
+                            details.path,
+                            original_header_pos
+                        )));
+                        let action = Action {
+                            action_type,
+                            path: details.path,
+                            dest_path: None,
+                            content: None,
+                            anchor: None,
+                            mode: None,
                             original_pos: original_header_pos,
+                            source_file: None,
                         };
                         actions_with_pos.push((original_header_pos, action));
                     }
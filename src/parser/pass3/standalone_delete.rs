@@ -2,6 +2,7 @@
 
 // Removed unused import: crate::constants::ACTION_DELETED_FILE;
 use crate::core_types::{Action, ActionType};
+use crate::reporter::{Event, Reporter};
 // Removed unused imports: OPENING_FENCE_REGEX, HashSet
 
 /// Handles logic for adding a standalone delete action found in Pass 2.
@@ -10,6 +11,7 @@ pub(crate) fn handle_standalone_delete(
     original_header_pos: usize,
     path: &str,
     actions_with_pos: &mut Vec<(usize, Action)>,
+    reporter: &dyn Reporter,
 ) {
     // DEBUG: Log function entry (Remove this line)
     // println!("    [Standalone Delete] Handling pos={}, path='{}'", original_header_pos, path);
@@ -21,10 +23,10 @@ pub(crate) fn handle_standalone_delete(
         .iter()
         .any(|(_, a)| a.action_type == ActionType::Delete && a.path == path)
     {
-        println!(
+        reporter.report(&Event::Decision(format!(
             "  - Found standalone DELETE action for: '{}' at original pos {}",
             path, original_header_pos
-        );
+        )));
         actions_with_pos.push((
             original_header_pos,
             Action {
@@ -32,11 +34,18 @@ pub(crate) fn handle_standalone_delete(
                 path: path.to_string(),
                 dest_path: None, // Delete actions don't have a dest_path
                 content: None,
+                anchor: None,
+                mode: None,
                 original_pos: original_header_pos,
+                source_file: None,
             },
         ));
         // Mark header as processed? No, Pass 2 iterates once.
     } else {
-        println!("  - Info: Duplicate standalone DELETE action found for '{}' at original pos {}. Ignoring.", path, original_header_pos);
+        reporter.report(&Event::Decision(format!(
+            "  - Info: Duplicate standalone DELETE action found for '{}' at original pos {}. Ignoring.",
+            path,
+            original_header_pos
+        )));
     }
 }
@@ -25,10 +25,10 @@ pub static HEADER_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(&pattern).expect("Failed to compile HEADER_REGEX")
 });
 
-// Regex to find the START of a fenced code block.
+// Regex to find the START of a fenced code block (3+ backticks or tildes).
 pub static OPENING_FENCE_REGEX: Lazy<Regex> = Lazy::new(|| {
     // Handle optional carriage return for CRLF compatibility
-    Regex::new("(?m)^\\s*(?P<fence>`{3,})(?P<lang>[^\\n\\r]*)(\\r?\\n)")
+    Regex::new("(?m)^\\s*(?P<fence>`{3,}|~{3,})(?P<lang>[^\\n\\r]*)(\\r?\\n)")
         .expect("Failed to compile OPENING_FENCE_REGEX")
 });
 
@@ -1,52 +1,108 @@
 //! Handles processing a single action item.
 
-use crate::core_types::{Action, ActionType, Summary};
+use crate::core_types::{Action, ActionOutcome, ActionType, ProcessOptions, Summary};
 use crate::errors::ProcessError;
-use crate::processor::{append, create, delete, move_file, prepend, safety, summary_updater}; // Added append, prepend
+use crate::processor::journal::Journal;
+use crate::processor::overlay::{Overlay, Workspace};
+use crate::processor::{
+    append, copy_file, create, delete, directory, edit, insert, move_file, patch, prepend, safety,
+    summary_updater, symlink,
+}; // Added append, prepend
+use crate::reporter::{Event, Reporter};
 use std::path::{Path, PathBuf};
 
 /// Processes a single action item, handling path validation, safety, and dispatching.
-/// Updates the summary based on the outcome.
+/// Updates the summary based on the outcome and returns that outcome.
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn process_single_action(
     item: &Action,
     item_index: usize,
     total_actions: usize,
+    later_actions: &[Action],
     resolved_base: &Path,
-    overwrite: bool,
+    options: &ProcessOptions,
     summary: &mut Summary,
-) {
+    journal: Option<&mut Journal>,
+    overlay: Option<&mut Overlay>,
+    reporter: &dyn Reporter,
+) -> ActionOutcome {
+    let outcome = determine_outcome(
+        item,
+        item_index,
+        total_actions,
+        later_actions,
+        resolved_base,
+        options,
+        journal,
+        overlay,
+        reporter,
+    );
+    summary_updater::update_summary(summary, &outcome);
+    reporter.report(&Event::ActionOutcome {
+        index: item_index,
+        total: total_actions,
+        action: item,
+        outcome: &outcome,
+    });
+    outcome
+}
+
+/// Runs validation, safety checks and the action handler, producing the action's outcome.
+#[allow(clippy::too_many_arguments)]
+fn determine_outcome(
+    item: &Action,
+    item_index: usize,
+    total_actions: usize,
+    later_actions: &[Action],
+    resolved_base: &Path,
+    options: &ProcessOptions,
+    journal: Option<&mut Journal>,
+    mut overlay: Option<&mut Overlay>,
+    reporter: &dyn Reporter,
+) -> ActionOutcome {
     let action_type = &item.action_type;
     let relative_path_str = &item.path; // This is source_path for Move
 
+    reporter.report(&Event::ActionStarted {
+        index: item_index,
+        total: total_actions,
+        action: item,
+    });
+
+    // Includes name another markdown document, not a path to change
+    if *action_type == ActionType::Include {
+        let e = ProcessError::UnresolvedInclude {
+            path: relative_path_str.clone(),
+        };
+        reporter.report(&Event::Error(e.to_string()));
+        return ActionOutcome::Failed(e);
+    }
+
     // --- Path Validation and Setup ---
     // For Move, we need to validate and resolve both source and destination paths.
     // For Create/Delete/Append/Prepend, only item.path is relevant here.
 
+    // Name the markdown input in errors when actions were merged from several files
+    let origin = item
+        .source_file
+        .as_ref()
+        .map(|source_file| format!(" (from {})", source_file))
+        .unwrap_or_default();
+
     let (log_path_display, validated_primary_path, validated_secondary_path_opt) = match action_type
     {
-        ActionType::Move => {
+        ActionType::Move | ActionType::MoveDirectory | ActionType::Copy => {
             let dest_path_str = item
                 .dest_path
                 .as_ref()
                 .expect("Move action missing destination path");
-            println!(
-                "\n[{}/{}] Action: {:?}, From: '{}', To: '{}'",
-                item_index + 1,
-                total_actions,
-                action_type,
-                relative_path_str,
-                dest_path_str
-            );
             // Validate source path
-            if let Err(e) = validate_and_prepare_path(relative_path_str, resolved_base) {
-                summary_updater::update_summary_error(summary, e);
-                return;
+            if let Err(e) = validate_and_prepare_path(relative_path_str, resolved_base, reporter) {
+                return ActionOutcome::Failed(e);
             }
             // Validate destination path
-            if let Err(e) = validate_and_prepare_path(dest_path_str, resolved_base) {
-                summary_updater::update_summary_error(summary, e);
-                return;
+            if let Err(e) = validate_and_prepare_path(dest_path_str, resolved_base, reporter) {
+                return ActionOutcome::Failed(e);
             }
             (
                 format!("{} -> {}", relative_path_str, dest_path_str),
@@ -54,18 +110,27 @@ pub(crate) fn process_single_action(
                 Some(resolved_base.join(dest_path_str)),
             )
         }
+        ActionType::Symlink => {
+            let target_str = item
+                .dest_path
+                .as_ref()
+                .expect("Symlink action missing target path");
+            if let Err(e) = validate_and_prepare_path(relative_path_str, resolved_base, reporter) {
+                return ActionOutcome::Failed(e);
+            }
+            // The target is relative to the link, not to the base; check where it leads
+            let link_path = resolved_base.join(relative_path_str);
+            let resolved_target = safety::resolve_link_target(&link_path, Path::new(target_str));
+            (
+                format!("{} -> {}", relative_path_str, target_str),
+                link_path,
+                Some(resolved_target),
+            )
+        }
         _ => {
             // Create, Delete, Append, or Prepend
-            println!(
-                "\n[{}/{}] Action: {:?}, Path: '{}'",
-                item_index + 1,
-                total_actions,
-                action_type,
-                relative_path_str
-            );
-            if let Err(e) = validate_and_prepare_path(relative_path_str, resolved_base) {
-                summary_updater::update_summary_error(summary, e);
-                return;
+            if let Err(e) = validate_and_prepare_path(relative_path_str, resolved_base, reporter) {
+                return ActionOutcome::Failed(e);
             }
             (
                 relative_path_str.to_string(),
@@ -77,33 +142,83 @@ pub(crate) fn process_single_action(
 
     // --- Safety Check ---
     // Check primary path (source for Move, target for Create/Delete/Append/Prepend)
-    if let Err(e) = safety::ensure_path_safe(resolved_base, &validated_primary_path) {
-        eprintln!("Error processing action for '{}': {}", log_path_display, e);
-        summary_updater::update_summary_error(summary, e);
-        return;
+    if let Err(e) = safety::ensure_path_safe(resolved_base, &validated_primary_path)
+        .and_then(|()| safety::ensure_not_reserved(resolved_base, &validated_primary_path))
+    {
+        reporter.report(&Event::Error(format!(
+            "Could not process action for '{}'{}: {}",
+            log_path_display, origin, e
+        )));
+        return ActionOutcome::Failed(e);
     }
-    // Check secondary path if it exists (destination for Move)
+    // Check secondary path if it exists (destination for Move, resolved target for Symlink)
     if let Some(ref secondary_path) = validated_secondary_path_opt {
-        if let Err(e) = safety::ensure_path_safe(resolved_base, secondary_path) {
-            eprintln!("Error processing action for '{}': {}", log_path_display, e);
-            summary_updater::update_summary_error(summary, e);
-            return;
+        if let Err(e) = safety::ensure_path_safe(resolved_base, secondary_path)
+            .and_then(|()| safety::ensure_not_reserved(resolved_base, secondary_path))
+        {
+            reporter.report(&Event::Error(format!(
+                "Could not process action for '{}'{}: {}",
+                log_path_display, origin, e
+            )));
+            return ActionOutcome::Failed(e);
+        }
+    }
+
+    // --- Record Prior State (--atomic) ---
+    // Only paths that passed the safety checks are read and recorded
+    if let Some(journal) = journal {
+        // Directory actions change everything below the path, not just the path itself
+        let recorded = match (action_type, &validated_secondary_path_opt) {
+            (ActionType::DeleteDirectory, _) => journal.record_tree(&validated_primary_path),
+            (ActionType::MoveDirectory, Some(secondary_path)) => {
+                journal.record_move_tree(&validated_primary_path, secondary_path)
+            }
+            // Creating a link never touches its target
+            (ActionType::Symlink, _) => journal.record(&validated_primary_path),
+            (_, secondary_path) => {
+                journal
+                    .record(&validated_primary_path)
+                    .and_then(|()| match secondary_path {
+                        Some(secondary_path) => journal.record(secondary_path),
+                        None => Ok(()),
+                    })
+            }
+        };
+        if let Err(e) = recorded {
+            reporter.report(&Event::Error(format!(
+                "Could not record prior state of '{}'{}: {}",
+                log_path_display, origin, e
+            )));
+            return ActionOutcome::Failed(e);
         }
     }
 
     // --- Dispatch to Action Handler ---
-    let result: Result<(), ProcessError> = match action_type {
+    // A dry run plans against the overlay, so later actions see what earlier ones would do
+    if let Some(overlay) = overlay.as_deref_mut() {
+        overlay.start_action(item_index);
+    }
+    let mut workspace = Workspace::new(overlay);
+    let result: Result<ActionOutcome, ProcessError> = match action_type {
         ActionType::Create => create::process_create(
+            &mut workspace,
             item,
             &validated_primary_path, // This is the target path for create
             relative_path_str,       // Original relative path for logging
             resolved_base,
-            overwrite,
+            options,
+            reporter,
         )
-        .map(|status| summary_updater::update_summary_create(summary, status)),
+        .map(ActionOutcome::Create),
         ActionType::Delete => {
-            delete::process_delete(&validated_primary_path, relative_path_str) // Target path for delete
-                .map(|status| summary_updater::update_summary_delete(summary, status))
+            delete::process_delete(
+                &mut workspace,
+                &validated_primary_path,
+                relative_path_str,
+                options,
+                reporter,
+            ) // Target path for delete
+            .map(ActionOutcome::Delete)
         }
         ActionType::Move => {
             let dest_path_str = item
@@ -111,6 +226,7 @@ pub(crate) fn process_single_action(
                 .as_ref()
                 .expect("Move action missing dest_path string for logging");
             move_file::process_move(
+                &mut workspace,
                 &validated_primary_path, // Source path for move
                 validated_secondary_path_opt
                     .as_ref()
@@ -118,44 +234,180 @@ pub(crate) fn process_single_action(
                 relative_path_str,       // Original source relative path for logging
                 dest_path_str,           // Original dest relative path for logging
                 resolved_base,
-                overwrite,
+                options,
+                reporter,
             )
-            .map(|status| summary_updater::update_summary_move(summary, status))
+            .map(ActionOutcome::Move)
         }
         ActionType::Append => append::process_append(
+            &mut workspace,
             item,
+            &following_blocks(item, later_actions),
             &validated_primary_path,
             relative_path_str,
             resolved_base,
+            options,
+            reporter,
         )
-        .map(|status| summary_updater::update_summary_append(summary, status)),
+        .map(ActionOutcome::Append),
         ActionType::Prepend => prepend::process_prepend(
+            &mut workspace,
+            item,
+            &following_blocks(item, later_actions),
+            &validated_primary_path,
+            relative_path_str,
+            resolved_base,
+            options,
+            reporter,
+        )
+        .map(ActionOutcome::Prepend),
+        ActionType::CreateDirectory => directory::process_create_directory(
+            &mut workspace,
+            &validated_primary_path,
+            relative_path_str,
+            resolved_base,
+            options,
+            reporter,
+        )
+        .map(ActionOutcome::CreateDirectory),
+        ActionType::DeleteDirectory => directory::process_delete_directory(
+            &mut workspace,
+            &validated_primary_path,
+            relative_path_str,
+            resolved_base,
+            options,
+            reporter,
+        )
+        .map(ActionOutcome::DeleteDirectory),
+        ActionType::MoveDirectory => {
+            let dest_path_str = item
+                .dest_path
+                .as_ref()
+                .expect("Move action missing dest_path string for logging");
+            directory::process_move_directory(
+                &mut workspace,
+                &validated_primary_path,
+                validated_secondary_path_opt
+                    .as_ref()
+                    .expect("Move action missing resolved dest_path"),
+                relative_path_str,
+                dest_path_str,
+                resolved_base,
+                options,
+                reporter,
+            )
+            .map(ActionOutcome::MoveDirectory)
+        }
+        ActionType::Copy => {
+            let dest_path_str = item
+                .dest_path
+                .as_ref()
+                .expect("Copy action missing dest_path string for logging");
+            copy_file::process_copy(
+                &mut workspace,
+                &validated_primary_path,
+                validated_secondary_path_opt
+                    .as_ref()
+                    .expect("Copy action missing resolved dest_path"),
+                relative_path_str,
+                dest_path_str,
+                resolved_base,
+                options,
+                reporter,
+            )
+            .map(ActionOutcome::Copy)
+        }
+        ActionType::Edit => edit::process_edit(
+            &mut workspace,
+            item,
+            &validated_primary_path,
+            relative_path_str,
+            options,
+            reporter,
+        )
+        .map(ActionOutcome::Edit),
+        ActionType::Patch => patch::process_patch(
+            &mut workspace,
             item,
             &validated_primary_path,
             relative_path_str,
             resolved_base,
+            options,
+            reporter,
+        )
+        .map(ActionOutcome::Patch),
+        ActionType::InsertAfter | ActionType::InsertBefore => insert::process_insert(
+            &mut workspace,
+            item,
+            &validated_primary_path,
+            relative_path_str,
+            options,
+            reporter,
         )
-        .map(|status| summary_updater::update_summary_prepend(summary, status)),
+        .map(ActionOutcome::Insert),
+        ActionType::Symlink => symlink::process_symlink(
+            &mut workspace,
+            &validated_primary_path,
+            item.dest_path
+                .as_deref()
+                .expect("Symlink action missing target path"),
+            relative_path_str,
+            resolved_base,
+            options,
+            reporter,
+        )
+        .map(ActionOutcome::Symlink),
+        ActionType::Include => unreachable!("Include actions are rejected before validation"),
     };
 
     // --- Handle Errors from Action Handlers ---
-    if let Err(e) = result {
-        eprintln!("Error processing action for '{}': {}", log_path_display, e);
-        summary_updater::update_summary_error(summary, e);
-    }
+    result.unwrap_or_else(|e| {
+        reporter.report(&Event::Error(format!(
+            "Could not process action for '{}'{}: {}",
+            log_path_display, origin, e
+        )));
+        ActionOutcome::Failed(e)
+    })
+}
+
+/// Content of the later actions of the same type on the same file as `item`, up to the first
+/// other action on that file. With --idempotent, a block counts as present when the file
+/// ends (or starts) with it plus some of these, as a rerun of the document leaves it.
+pub(crate) fn following_blocks<'a>(item: &Action, later_actions: &'a [Action]) -> Vec<&'a [u8]> {
+    let path = safety::normalize(Path::new(&item.path));
+    later_actions
+        .iter()
+        .filter(|later| {
+            safety::normalize(Path::new(&later.path)) == path
+                || later
+                    .dest_path
+                    .as_deref()
+                    .is_some_and(|dest| safety::normalize(Path::new(dest)) == path)
+        })
+        .map_while(|later| match &later.content {
+            Some(content)
+                if later.action_type == item.action_type
+                    && safety::normalize(Path::new(&later.path)) == path =>
+            {
+                Some(content.as_bytes())
+            }
+            _ => None,
+        })
+        .collect()
 }
 
 /// Validates path format (string-based checks) and ensures no empty components after PathBuf conversion.
 fn validate_and_prepare_path(
     relative_path_str: &str,
     _resolved_base: &Path, // Currently unused, but kept for signature consistency
+    reporter: &dyn Reporter,
 ) -> Result<(), ProcessError> {
     // String-based format checks
     if relative_path_str.contains("//") || relative_path_str.contains(r"\\") {
-        eprintln!(
-            "Error: Invalid path format (consecutive separators) for '{}'. Skipping.",
+        reporter.report(&Event::Error(format!(
+            "Invalid path format (consecutive separators) for '{}'. Skipping.",
             relative_path_str
-        );
+        )));
         return Err(ProcessError::InvalidPathFormat {
             path: relative_path_str.to_string(),
         });
@@ -163,16 +415,18 @@ fn validate_and_prepare_path(
     if (relative_path_str.ends_with('/') || relative_path_str.ends_with('\\'))
         && relative_path_str.len() > 1
     {
-        eprintln!(
-            "Error: Invalid path format (trailing separator) for '{}'. Skipping.",
+        reporter.report(&Event::Error(format!(
+            "Invalid path format (trailing separator) for '{}'. Skipping.",
             relative_path_str
-        );
+        )));
         return Err(ProcessError::InvalidPathFormat {
             path: relative_path_str.to_string(),
         });
     }
     if relative_path_str.trim().is_empty() {
-        eprintln!("Error: Invalid path format (empty path string). Skipping.");
+        reporter.report(&Event::Error(
+            "Invalid path format (empty path string). Skipping.".to_string(),
+        ));
         return Err(ProcessError::InvalidPathFormat {
             path: relative_path_str.to_string(),
         });
@@ -184,7 +438,10 @@ fn validate_and_prepare_path(
         .components()
         .any(|comp| comp.as_os_str().is_empty())
     {
-        eprintln!("Error: Invalid path format (empty components detected after PathBuf conversion) for '{}'. Skipping.", relative_path_str);
+        reporter.report(&Event::Error(format!(
+            "Invalid path format (empty components detected after PathBuf conversion) for '{}'. Skipping.",
+            relative_path_str
+        )));
         return Err(ProcessError::InvalidPathFormat {
             path: relative_path_str.to_string(),
         });
@@ -1,18 +1,28 @@
 //! Handles the 'append' file action.
 
-use crate::core_types::{Action, AppendStatus};
+use crate::core_types::{Action, AppendStatus, ProcessOptions};
 use crate::errors::ProcessError;
 use crate::processor::create::ensure_parent_directory; // Re-use for dest parent
-use std::fs;
-use std::io::{ErrorKind, Write}; // Import Write for append mode
+use crate::processor::overlay::{Kind, Workspace};
+use crate::processor::permissions;
+use crate::reporter::{Event, Reporter};
+use std::io::ErrorKind;
 use std::path::Path;
 
 /// Appends content to a file. If the file does not exist, it's created.
+/// With `options.idempotent`, a file that already ends with the content (possibly followed by
+/// `following_blocks`, the document's later appends to the file) is left as is.
+/// The file's mode is applied after writing.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn process_append(
+    workspace: &mut Workspace,
     item: &Action,
+    following_blocks: &[&[u8]],
     resolved_full_path: &Path,
     relative_path_str: &str, // For logging
     resolved_base: &Path,    // For ensure_parent_directory
+    options: &ProcessOptions,
+    reporter: &dyn Reporter,
 ) -> Result<AppendStatus, ProcessError> {
     let content_to_append = item
         .content
@@ -20,41 +30,107 @@ pub(crate) fn process_append(
         .ok_or_else(|| ProcessError::Internal("Missing content for append action".to_string()))?;
 
     // Ensure parent directory exists
-    ensure_parent_directory(resolved_full_path, resolved_base)?;
+    ensure_parent_directory(
+        workspace,
+        resolved_full_path,
+        resolved_base,
+        options.dry_run,
+        reporter,
+    )?;
 
     // Check if the target path itself exists
-    match fs::metadata(resolved_full_path) {
-        Ok(metadata) => {
-            if metadata.is_dir() {
+    let status = match workspace.kind(resolved_full_path) {
+        Ok(kind) => {
+            if kind == Kind::Directory {
                 return Err(ProcessError::TargetIsDirectoryForAppend {
                     path: resolved_full_path.to_path_buf(),
                 });
             }
+            if options.idempotent
+                && ends_with_block(
+                    workspace,
+                    resolved_full_path,
+                    content_to_append.as_bytes(),
+                    following_blocks,
+                )?
+            {
+                reporter.report(&Event::Info(format!(
+                    "  Skipping append: '{}' already ends with this content (--idempotent).",
+                    relative_path_str
+                )));
+                return Ok(AppendStatus::SkippedAlreadyPresent);
+            }
             // File exists, open in append mode
-            println!(
-                "  Appending to file: {} ({} bytes)",
+            reporter.report(&Event::Info(format!(
+                "  {} file: {} ({} bytes)",
+                if options.dry_run {
+                    "Would append to"
+                } else {
+                    "Appending to"
+                },
                 relative_path_str,
                 content_to_append.len()
-            );
-            let mut file = fs::OpenOptions::new()
-                .append(true)
-                .open(resolved_full_path)
-                .map_err(|e| ProcessError::Io { source: e })?;
-            file.write_all(content_to_append.as_bytes())
+            )));
+            workspace
+                .append(resolved_full_path, content_to_append.as_bytes())
                 .map_err(|e| ProcessError::Io { source: e })?;
-            Ok(AppendStatus::Appended)
+            AppendStatus::Appended
         }
         Err(e) if e.kind() == ErrorKind::NotFound => {
             // File does not exist, create it
-            println!(
-                "  File not found, creating and writing: {} ({} bytes)",
+            reporter.report(&Event::Info(format!(
+                "  File not found, {}: {} ({} bytes)",
+                if options.dry_run {
+                    "would create and write"
+                } else {
+                    "creating and writing"
+                },
                 relative_path_str,
                 content_to_append.len()
-            );
-            fs::write(resolved_full_path, content_to_append.as_bytes())
+            )));
+            workspace
+                .write(resolved_full_path, content_to_append.as_bytes())
                 .map_err(|e| ProcessError::Io { source: e })?;
-            Ok(AppendStatus::Created)
+            AppendStatus::Created
+        }
+        Err(e) => return Err(ProcessError::Io { source: e }), // Other metadata error
+    };
+    permissions::apply_mode(
+        workspace,
+        item,
+        resolved_full_path,
+        relative_path_str,
+        options,
+        reporter,
+    )?;
+    Ok(status)
+}
+
+/// Whether the file already ends with the exact bytes of `block`, possibly followed by some
+/// of `following_blocks` (in order).
+fn ends_with_block(
+    workspace: &Workspace,
+    path: &Path,
+    block: &[u8],
+    following_blocks: &[&[u8]],
+) -> Result<bool, ProcessError> {
+    let existing = workspace
+        .read(path)
+        .map_err(|e| ProcessError::Io { source: e })?;
+    Ok(is_appended(&existing, block, following_blocks))
+}
+
+/// Whether `existing` ends with `block` followed by the first `n` of `following_blocks`,
+/// for some `n`.
+pub(crate) fn is_appended(existing: &[u8], block: &[u8], following_blocks: &[&[u8]]) -> bool {
+    let mut tail = block.to_vec();
+    for n in 0..=following_blocks.len() {
+        if n > 0 {
+            tail.extend_from_slice(following_blocks[n - 1]);
+        }
+        if existing.ends_with(&tail) {
+            return true;
         }
-        Err(e) => Err(ProcessError::Io { source: e }), // Other metadata error
     }
+    false
 }
@@ -1,29 +1,61 @@
 //! Contains logic for setting up the base directory for processing.
 
 use crate::errors::AppError;
+use crate::reporter::{Event, Reporter};
 use std::fs;
 use std::path::Path;
 
 /// Ensures the base directory exists and is a directory. Accepts the user-provided path.
-pub(crate) fn setup_base_directory(base_dir_to_setup: &Path) -> Result<(), AppError> {
+pub(crate) fn setup_base_directory(
+    base_dir_to_setup: &Path,
+    reporter: &dyn Reporter,
+) -> Result<(), AppError> {
     if !base_dir_to_setup.exists() {
         fs::create_dir_all(base_dir_to_setup).map_err(|e| {
-            eprintln!(
-                "Error: Could not create base directory '{}': {}",
+            reporter.report(&Event::Error(format!(
+                "Could not create base directory '{}': {}",
                 base_dir_to_setup.display(),
                 e
-            );
+            )));
             AppError::Io(e)
         })?;
-        println!("Created base directory: {}", base_dir_to_setup.display());
+        reporter.report(&Event::Info(format!(
+            "Created base directory: {}",
+            base_dir_to_setup.display()
+        )));
     } else if !base_dir_to_setup.is_dir() {
-        eprintln!(
-            "Error: Specified base path '{}' exists but is not a directory.",
+        reporter.report(&Event::Error(format!(
+            "Specified base path '{}' exists but is not a directory.",
             base_dir_to_setup.display()
-        );
+        )));
         return Err(AppError::Argument(
             "Base path is not a directory".to_string(),
         ));
     }
     Ok(())
 }
+
+/// Dry-run counterpart of `setup_base_directory`: validates the base path without creating it.
+/// Returns whether the base directory already exists.
+pub(crate) fn check_base_directory(
+    base_dir_to_check: &Path,
+    reporter: &dyn Reporter,
+) -> Result<bool, AppError> {
+    if !base_dir_to_check.exists() {
+        reporter.report(&Event::Info(format!(
+            "Base directory does not exist and would be created: {}",
+            base_dir_to_check.display()
+        )));
+        Ok(false)
+    } else if !base_dir_to_check.is_dir() {
+        reporter.report(&Event::Error(format!(
+            "Specified base path '{}' exists but is not a directory.",
+            base_dir_to_check.display()
+        )));
+        Err(AppError::Argument(
+            "Base path is not a directory".to_string(),
+        ))
+    } else {
+        Ok(true)
+    }
+}
@@ -0,0 +1,98 @@
+//! Handles the 'copy' file action.
+
+use crate::core_types::{CopyStatus, ProcessOptions};
+use crate::errors::ProcessError;
+use crate::processor::create::ensure_parent_directory; // Re-use for dest parent
+use crate::processor::overlay::{Kind, Workspace};
+use crate::reporter::{Event, Reporter};
+use std::path::Path;
+
+/// Copies a file from a source path to a destination path, leaving the source in place.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn process_copy(
+    workspace: &mut Workspace,
+    resolved_source_path: &Path,
+    resolved_dest_path: &Path,
+    relative_source_str: &str, // For logging
+    relative_dest_str: &str,   // For logging
+    resolved_base: &Path,      // For ensure_parent_directory
+    options: &ProcessOptions,
+    reporter: &dyn Reporter,
+) -> Result<CopyStatus, ProcessError> {
+    // 1. Check source path
+    if !workspace.exists(resolved_source_path) {
+        reporter.report(&Event::Info(format!(
+            "  Skipping copy: Source file not found: {}",
+            relative_source_str
+        )));
+        return Ok(CopyStatus::SkippedSourceNotFound);
+    }
+    let source_kind = workspace
+        .symlink_kind(resolved_source_path)
+        .map_err(|e| ProcessError::Io { source: e })?;
+    if source_kind == Kind::Directory {
+        reporter.report(&Event::Warning(format!(
+            "Skipping copy. Source path '{}' is a directory, not a file.",
+            relative_source_str
+        )));
+        return Ok(CopyStatus::SkippedSourceIsDir);
+    }
+
+    // --- Source and Destination are the same: copying a file onto itself changes nothing ---
+    if resolved_source_path == resolved_dest_path {
+        reporter.report(&Event::Info(format!(
+            "  Skipping copy: Source and destination are the same: {}",
+            relative_source_str
+        )));
+        return Ok(CopyStatus::SkippedDestinationExists);
+    }
+
+    // 2. Ensure parent directory of destination exists
+    ensure_parent_directory(
+        workspace,
+        resolved_dest_path,
+        resolved_base,
+        options.dry_run,
+        reporter,
+    )?;
+
+    // 3. Check destination path
+    let mut final_status = CopyStatus::Copied; // Optimistic default
+    if workspace.exists(resolved_dest_path) {
+        let dest_kind = workspace
+            .symlink_kind(resolved_dest_path)
+            .map_err(|e| ProcessError::Io { source: e })?;
+        if dest_kind == Kind::Directory {
+            reporter.report(&Event::Warning(format!(
+                "Skipping copy. Destination path '{}' exists and is a directory.",
+                relative_dest_str
+            )));
+            return Ok(CopyStatus::SkippedDestinationIsDir);
+        }
+        if !options.overwrite {
+            reporter.report(&Event::Info(format!(
+                "  Skipping copy: Destination file '{}' exists (use --force to overwrite).",
+                relative_dest_str
+            )));
+            return Ok(CopyStatus::SkippedDestinationExists);
+        }
+        final_status = CopyStatus::CopiedOverwritten;
+    }
+
+    // 4. Perform the copy
+    reporter.report(&Event::Info(format!(
+        "  {} file: '{}' to '{}'",
+        if options.dry_run {
+            "Would copy"
+        } else {
+            "Copying"
+        },
+        relative_source_str,
+        relative_dest_str
+    )));
+    workspace
+        .copy(resolved_source_path, resolved_dest_path)
+        .map_err(|e| ProcessError::Io { source: e })?;
+
+    Ok(final_status)
+}
@@ -1,18 +1,22 @@
 //! Handles the 'create' file action.
 
-use crate::core_types::{Action, CreateStatus};
+use crate::core_types::{Action, CreateStatus, ProcessOptions};
 use crate::errors::ProcessError;
-use std::fs;
+use crate::processor::overlay::{Kind, Workspace};
+use crate::processor::permissions;
+use crate::reporter::{Event, Reporter};
 use std::io; // Import io for ErrorKind
 use std::path::Path;
 
-/// Creates or overwrites a file with the provided content.
+/// Creates or overwrites a file with the provided content, then applies its mode.
 pub(crate) fn process_create(
+    workspace: &mut Workspace,
     item: &Action,
     resolved_full_path: &Path,
     relative_path_str: &str, // For logging
     resolved_base: &Path,    // For logging relative paths
-    overwrite: bool,
+    options: &ProcessOptions,
+    reporter: &dyn Reporter,
 ) -> Result<CreateStatus, ProcessError> {
     let content = item
         .content
@@ -21,69 +25,99 @@ pub(crate) fn process_create(
 
     // Ensure parent directory exists and is a directory
     // This might return ParentIsNotDirectory if parent exists as file or if creation fails
-    ensure_parent_directory(resolved_full_path, resolved_base)?;
+    ensure_parent_directory(
+        workspace,
+        resolved_full_path,
+        resolved_base,
+        options.dry_run,
+        reporter,
+    )?;
 
     let mut status = CreateStatus::Created; // Default optimistic status
 
     // Check if the target path itself exists
-    if resolved_full_path.exists() {
-        if resolved_full_path.is_dir() {
+    if workspace.exists(resolved_full_path) {
+        if workspace.is_dir(resolved_full_path) {
             return Err(ProcessError::TargetIsDirectory {
                 path: resolved_full_path.to_path_buf(),
             });
-        } else if !overwrite {
-            println!(
+        } else if !options.overwrite {
+            reporter.report(&Event::Info(format!(
                 "  Skipping existing file: {} (use --force to overwrite)",
                 relative_path_str
-            );
+            )));
             return Ok(CreateStatus::SkippedExists);
         } else {
-            println!(
-                "  Overwriting file: {} ({} bytes)",
+            reporter.report(&Event::Info(format!(
+                "  {} file: {} ({} bytes)",
+                if options.dry_run {
+                    "Would overwrite"
+                } else {
+                    "Overwriting"
+                },
                 relative_path_str,
                 content.len()
-            );
+            )));
             status = CreateStatus::Overwritten;
         }
     } else {
-        println!(
-            "  Creating file: {} ({} bytes)",
+        reporter.report(&Event::Info(format!(
+            "  {} file: {} ({} bytes)",
+            if options.dry_run {
+                "Would create"
+            } else {
+                "Creating"
+            },
             relative_path_str,
             content.len()
-        );
+        )));
         // Status remains Created
     }
 
     // Write the file content (as bytes to preserve line endings)
-    fs::write(resolved_full_path, content.as_bytes()).map_err(|e| {
-        // Check if the write failed because the parent path component is a file
-        if e.kind() == io::ErrorKind::NotADirectory {
-            // Map this specific IO error to our more descriptive error
-            let parent_path = resolved_full_path
-                .parent()
-                .unwrap_or(resolved_full_path)
-                .to_path_buf();
-            // *** DEBUG LOG ***
-            // eprintln!("[DEBUG] fs::write failed with NotADirectory, mapping to ParentIsNotDirectory for path: {}", resolved_full_path.display());
-            ProcessError::ParentIsNotDirectory {
-                path: resolved_full_path.to_path_buf(),
-                parent_path, // Report the parent path
+    workspace
+        .write(resolved_full_path, content.as_bytes())
+        .map_err(|e| {
+            // Check if the write failed because the parent path component is a file
+            if e.kind() == io::ErrorKind::NotADirectory {
+                // Map this specific IO error to our more descriptive error
+                let parent_path = resolved_full_path
+                    .parent()
+                    .unwrap_or(resolved_full_path)
+                    .to_path_buf();
+                // *** DEBUG LOG ***
+                // eprintln!("[DEBUG] fs::write failed with NotADirectory, mapping to ParentIsNotDirectory for path: {}", resolved_full_path.display());
+                ProcessError::ParentIsNotDirectory {
+                    path: resolved_full_path.to_path_buf(),
+                    parent_path, // Report the parent path
+                }
+            } else {
+                // *** DEBUG LOG ***
+                // eprintln!("[DEBUG] fs::write failed with other IO error: {:?}, mapping to Io for path: {}", e.kind(), resolved_full_path.display());
+                ProcessError::Io { source: e }
             }
-        } else {
-            // *** DEBUG LOG ***
-            // eprintln!("[DEBUG] fs::write failed with other IO error: {:?}, mapping to Io for path: {}", e.kind(), resolved_full_path.display());
-            ProcessError::Io { source: e }
-        }
-    })?;
+        })?;
+    permissions::apply_mode(
+        workspace,
+        item,
+        resolved_full_path,
+        relative_path_str,
+        options,
+        reporter,
+    )?;
 
     Ok(status)
 }
 
 /// Ensures the parent directory of a path exists, creating it if necessary.
 /// Also checks if the parent path itself is unexpectedly a file.
+/// In dry-run mode a missing parent is only created in the workspace's overlay.
 pub(crate) fn ensure_parent_directory(
+    workspace: &mut Workspace,
     target_path: &Path,
     resolved_base: &Path,
+    dry_run: bool,
+    reporter: &dyn Reporter,
 ) -> Result<(), ProcessError> {
     if let Some(parent_dir) = target_path.parent() {
         // Avoid checking the base directory itself if it's the parent
@@ -91,10 +125,10 @@ pub(crate) fn ensure_parent_directory(
             return Ok(()); // Base directory is guaranteed to exist and be a dir, or path is in root
         }
 
-        match fs::metadata(parent_dir) {
-            Ok(metadata) => {
+        match workspace.kind(parent_dir) {
+            Ok(kind) => {
                 // Parent exists, check if it's a directory
-                if !metadata.is_dir() {
+                if kind != Kind::Directory {
                     // *** DEBUG LOG ***
                     // eprintln!("[DEBUG] Parent metadata exists but is not dir, returning ParentIsNotDirectory for parent: {}", parent_dir.display());
                     return Err(ProcessError::ParentIsNotDirectory {
@@ -108,9 +142,13 @@ pub(crate) fn ensure_parent_directory(
                 // Parent does not exist, try to create it
                 let relative_parent_dir =
                     parent_dir.strip_prefix(resolved_base).unwrap_or(parent_dir);
-                println!("  Creating directory: {}", relative_parent_dir.display());
+                reporter.report(&Event::Info(format!(
+                    "  {} directory: {}",
+                    if dry_run { "Would create" } else { "Creating" },
+                    relative_parent_dir.display()
+                )));
 
-                if let Err(create_err) = fs::create_dir_all(parent_dir) {
+                if let Err(create_err) = workspace.create_dir_all(parent_dir) {
                     // Check if the error is specifically "Not a directory"
                     // This often indicates an intermediate path component was a file during creation attempt.
                     if create_err.kind() == io::ErrorKind::NotADirectory {
@@ -1,55 +1,80 @@
 //! Handles the 'delete' file action.
 
-use crate::core_types::DeleteStatus;
+use crate::core_types::{DeleteStatus, ProcessOptions};
 use crate::errors::ProcessError;
-use std::fs;
+use crate::processor::overlay::{Kind, Workspace};
+use crate::reporter::{Event, Reporter};
 use std::path::Path;
 
 /// Deletes the specified file path. Handles non-existence and non-file types.
 pub(crate) fn process_delete(
+    workspace: &mut Workspace,
     resolved_full_path: &Path,
     relative_path_str: &str, // For logging
+    options: &ProcessOptions,
+    reporter: &dyn Reporter,
 ) -> Result<DeleteStatus, ProcessError> {
-    if resolved_full_path.exists() {
-        // Use symlink_metadata to check type without following symlinks
-        let metadata =
-            fs::symlink_metadata(resolved_full_path).map_err(|e| ProcessError::Io { source: e })?;
+    if workspace.exists(resolved_full_path) {
+        // Check the type without following symlinks
+        let kind = workspace
+            .symlink_kind(resolved_full_path)
+            .map_err(|e| ProcessError::Io { source: e })?;
 
-        if metadata.is_file() {
-            // It's a regular file (or a symlink to one, but we delete the link)
-            println!("  Deleting file: {}", relative_path_str);
-            fs::remove_file(resolved_full_path).map_err(|e| ProcessError::Io { source: e })?;
+        if kind == Kind::File {
+            reporter.report(&Event::Info(format!(
+                "  {} file: {}",
+                if options.dry_run {
+                    "Would delete"
+                } else {
+                    "Deleting"
+                },
+                relative_path_str
+            )));
+            workspace
+                .remove_file(resolved_full_path)
+                .map_err(|e| ProcessError::Io { source: e })?;
             Ok(DeleteStatus::Deleted)
-        } else if metadata.is_dir() {
+        } else if kind == Kind::Directory {
             // It's a directory, skip deletion
-            eprintln!(
-                "Warning: Skipping deletion. Path '{}' is a directory.",
+            reporter.report(&Event::Warning(format!(
+                "Skipping deletion. Path '{}' is a directory (use 'Deleted Directory' to remove it).",
                 relative_path_str
-            );
+            )));
             Ok(DeleteStatus::SkippedIsDir)
         } else {
-            // It's something else (e.g., a broken symlink). Attempt deletion.
-            println!(
-                "  Attempting to delete non-file/non-dir path: {}",
+            // It's something else (e.g., a symlink). Attempt deletion.
+            reporter.report(&Event::Info(format!(
+                "  {} to delete non-file/non-dir path: {}",
+                if options.dry_run {
+                    "Would attempt"
+                } else {
+                    "Attempting"
+                },
                 relative_path_str
-            );
-            match fs::remove_file(resolved_full_path) {
+            )));
+            match workspace.remove_file(resolved_full_path) {
                 Ok(_) => {
-                    println!("    Successfully deleted non-file/non-dir path.");
+                    reporter.report(&Event::Info(
+                        "    Successfully deleted non-file/non-dir path.".to_string(),
+                    ));
                     Ok(DeleteStatus::Deleted)
                 }
                 Err(e) => {
-                    eprintln!(
-                        "Warning: Skipping deletion. Path '{}' exists but is not a regular file or directory and could not be deleted: {}",
-                        relative_path_str, e
-                    );
+                    reporter.report(&Event::Warning(format!(
+                        "Skipping deletion. Path '{}' exists but is not a regular file or directory and could not be deleted: {}",
+                        relative_path_str,
+                        e
+                    )));
                     Ok(DeleteStatus::SkippedOtherType)
                 }
             }
         }
     } else {
         // File specified for deletion does not exist
-        println!("  Skipping deletion: File not found: {}", relative_path_str);
+        reporter.report(&Event::Info(format!(
+            "  Skipping deletion: File not found: {}",
+            relative_path_str
+        )));
         Ok(DeleteStatus::SkippedNotFound)
     }
 }
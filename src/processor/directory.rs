@@ -0,0 +1,282 @@
+//! Handles the 'create directory', 'delete directory' and 'move directory' actions.
+
+use crate::core_types::{
+    DeleteDirectoryStatus, DirectoryStatus, MoveDirectoryStatus, ProcessOptions,
+};
+use crate::errors::ProcessError;
+use crate::processor::create::ensure_parent_directory; // Re-use for missing parents
+use crate::processor::overlay::{Kind, Workspace};
+use crate::processor::safety::normalize;
+use crate::reporter::{Event, Reporter};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Creates an empty directory (and any missing parents). An existing directory is left alone.
+pub(crate) fn process_create_directory(
+    workspace: &mut Workspace,
+    resolved_full_path: &Path,
+    relative_path_str: &str, // For logging
+    resolved_base: &Path,    // For ensure_parent_directory
+    options: &ProcessOptions,
+    reporter: &dyn Reporter,
+) -> Result<DirectoryStatus, ProcessError> {
+    match workspace.symlink_kind(resolved_full_path) {
+        Ok(Kind::Directory) => {
+            reporter.report(&Event::Info(format!(
+                "  Skipping directory: {} already exists",
+                relative_path_str
+            )));
+            return Ok(DirectoryStatus::SkippedExists);
+        }
+        Ok(_) => {
+            return Err(ProcessError::TargetIsFile {
+                path: resolved_full_path.to_path_buf(),
+            });
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(ProcessError::Io { source: e }),
+    }
+
+    ensure_parent_directory(
+        workspace,
+        resolved_full_path,
+        resolved_base,
+        options.dry_run,
+        reporter,
+    )?;
+
+    reporter.report(&Event::Info(format!(
+        "  {} directory: {}",
+        if options.dry_run {
+            "Would create"
+        } else {
+            "Creating"
+        },
+        relative_path_str
+    )));
+    workspace
+        .create_dir(resolved_full_path)
+        .map_err(|e| ProcessError::Io { source: e })?;
+    Ok(DirectoryStatus::Created)
+}
+
+/// Deletes a directory. A non-empty directory is only removed (with everything in it)
+/// when `options.overwrite` (--force) is set.
+pub(crate) fn process_delete_directory(
+    workspace: &mut Workspace,
+    resolved_full_path: &Path,
+    relative_path_str: &str, // For logging
+    resolved_base: &Path,    // The base directory itself is never deleted
+    options: &ProcessOptions,
+    reporter: &dyn Reporter,
+) -> Result<DeleteDirectoryStatus, ProcessError> {
+    let kind = match workspace.symlink_kind(resolved_full_path) {
+        Ok(kind) => kind,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            reporter.report(&Event::Info(format!(
+                "  Skipping directory deletion: Directory not found: {}",
+                relative_path_str
+            )));
+            return Ok(DeleteDirectoryStatus::SkippedNotFound);
+        }
+        Err(e) => return Err(ProcessError::Io { source: e }),
+    };
+
+    if kind != Kind::Directory {
+        reporter.report(&Event::Warning(format!(
+            "Skipping directory deletion. Path '{}' is not a directory (use 'Deleted File' for files).",
+            relative_path_str
+        )));
+        return Ok(DeleteDirectoryStatus::SkippedNotADirectory);
+    }
+
+    // `## Deleted Directory: .` would take the output directory (and the undo journal) with it
+    if workspace.canonicalize(resolved_full_path)? == resolved_base {
+        return Err(ProcessError::PathNotSafe {
+            resolved_path: resolved_full_path.to_path_buf(),
+            base_path: resolved_base.to_path_buf(),
+        });
+    }
+
+    let is_empty = workspace.read_dir(resolved_full_path)?.is_empty();
+    if !is_empty && !options.overwrite {
+        reporter.report(&Event::Warning(format!(
+            "Skipping directory deletion. Directory '{}' is not empty (use --force to delete it with its contents).",
+            relative_path_str
+        )));
+        return Ok(DeleteDirectoryStatus::SkippedNotEmpty);
+    }
+
+    let what = if is_empty {
+        "directory"
+    } else {
+        "directory and its contents"
+    };
+    reporter.report(&Event::Info(format!(
+        "  {} {}: {}",
+        if options.dry_run {
+            "Would delete"
+        } else {
+            "Deleting"
+        },
+        what,
+        relative_path_str
+    )));
+    workspace
+        .remove_dir_all(resolved_full_path)
+        .map_err(|e| ProcessError::Io { source: e })?;
+    Ok(DeleteDirectoryStatus::Deleted)
+}
+
+/// Moves a directory tree. An existing destination directory is only merged into (files
+/// with the same relative path are overwritten) when `options.overwrite` (--force) is set.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn process_move_directory(
+    workspace: &mut Workspace,
+    resolved_source_path: &Path,
+    resolved_dest_path: &Path,
+    relative_source_str: &str, // For logging
+    relative_dest_str: &str,   // For logging
+    resolved_base: &Path,      // For ensure_parent_directory
+    options: &ProcessOptions,
+    reporter: &dyn Reporter,
+) -> Result<MoveDirectoryStatus, ProcessError> {
+    // 1. Check source path
+    let source_kind = match workspace.symlink_kind(resolved_source_path) {
+        Ok(kind) => kind,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            reporter.report(&Event::Info(format!(
+                "  Skipping directory move: Source directory not found: {}",
+                relative_source_str
+            )));
+            return Ok(MoveDirectoryStatus::SkippedSourceNotFound);
+        }
+        Err(e) => return Err(ProcessError::Io { source: e }),
+    };
+    if source_kind != Kind::Directory {
+        reporter.report(&Event::Warning(format!(
+            "Skipping directory move. Source path '{}' is not a directory (use 'Moved File' for files).",
+            relative_source_str
+        )));
+        return Ok(MoveDirectoryStatus::SkippedSourceNotADirectory);
+    }
+    if workspace.canonicalize(resolved_source_path)? == resolved_base {
+        return Err(ProcessError::PathNotSafe {
+            resolved_path: resolved_source_path.to_path_buf(),
+            base_path: resolved_base.to_path_buf(),
+        });
+    }
+    if normalize(resolved_dest_path).starts_with(normalize(resolved_source_path)) {
+        reporter.report(&Event::Warning(format!(
+            "Skipping directory move. Destination '{}' is inside source '{}'.",
+            relative_dest_str, relative_source_str
+        )));
+        return Ok(MoveDirectoryStatus::SkippedDestinationInsideSource);
+    }
+
+    // 2. Check destination path
+    let status = match workspace.symlink_kind(resolved_dest_path) {
+        Ok(Kind::Directory) => {
+            if !options.overwrite {
+                reporter.report(&Event::Info(format!(
+                    "  Skipping directory move: Destination directory '{}' exists (use --force to merge into it).",
+                    relative_dest_str
+                )));
+                return Ok(MoveDirectoryStatus::SkippedDestinationExists);
+            }
+            // Check the whole tree first, so a conflict never leaves a half-merged directory
+            if let Some(conflict) =
+                find_merge_conflict(workspace, resolved_source_path, resolved_dest_path)?
+            {
+                reporter.report(&Event::Warning(format!(
+                    "Skipping directory move. Merging '{}' into '{}' would replace a file with a directory (or vice versa) at '{}'.",
+                    relative_source_str,
+                    relative_dest_str,
+                    conflict.strip_prefix(resolved_base).unwrap_or(&conflict).display()
+                )));
+                return Ok(MoveDirectoryStatus::SkippedMergeConflict);
+            }
+            MoveDirectoryStatus::Merged
+        }
+        Ok(_) => {
+            reporter.report(&Event::Warning(format!(
+                "Skipping directory move. Destination path '{}' exists and is not a directory.",
+                relative_dest_str
+            )));
+            return Ok(MoveDirectoryStatus::SkippedDestinationIsFile);
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            ensure_parent_directory(
+                workspace,
+                resolved_dest_path,
+                resolved_base,
+                options.dry_run,
+                reporter,
+            )?;
+            MoveDirectoryStatus::Moved
+        }
+        Err(e) => return Err(ProcessError::Io { source: e }),
+    };
+
+    // 3. Perform the move (or merge)
+    let verb = match (status, options.dry_run) {
+        (MoveDirectoryStatus::Merged, true) => "Would merge directory",
+        (MoveDirectoryStatus::Merged, false) => "Merging directory",
+        (_, true) => "Would move directory",
+        (_, false) => "Moving directory",
+    };
+    reporter.report(&Event::Info(format!(
+        "  {}: '{}' into '{}'",
+        verb, relative_source_str, relative_dest_str
+    )));
+    if status == MoveDirectoryStatus::Merged {
+        merge_into(workspace, resolved_source_path, resolved_dest_path)?;
+        workspace.remove_dir_all(resolved_source_path)?; // Only emptied directories are left
+    } else {
+        workspace.rename(resolved_source_path, resolved_dest_path)?;
+    }
+    Ok(status)
+}
+
+/// Finds the first path where merging `source` into `dest` would put a directory on a
+/// non-directory or the other way round. Files replacing files are fine.
+fn find_merge_conflict(
+    workspace: &Workspace,
+    source: &Path,
+    dest: &Path,
+) -> Result<Option<PathBuf>, ProcessError> {
+    for (name, kind) in workspace.read_dir(source)? {
+        let target = dest.join(&name);
+        let target_is_dir = match workspace.symlink_kind(&target) {
+            Ok(target_kind) => target_kind == Kind::Directory,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(ProcessError::Io { source: e }),
+        };
+        match (kind == Kind::Directory, target_is_dir) {
+            (true, true) => {
+                if let Some(conflict) =
+                    find_merge_conflict(workspace, &source.join(&name), &target)?
+                {
+                    return Ok(Some(conflict));
+                }
+            }
+            (false, false) => {}
+            _ => return Ok(Some(target)),
+        }
+    }
+    Ok(None)
+}
+
+/// Moves the contents of `source` into the existing directory `dest`, recursing into
+/// directories present on both sides. Must only run after `find_merge_conflict` found none.
+fn merge_into(workspace: &mut Workspace, source: &Path, dest: &Path) -> io::Result<()> {
+    for (name, kind) in workspace.read_dir(source)? {
+        let target = dest.join(&name);
+        if kind == Kind::Directory && workspace.is_dir(&target) {
+            merge_into(workspace, &source.join(&name), &target)?;
+        } else {
+            workspace.rename(&source.join(&name), &target)?; // Replaces an existing file
+        }
+    }
+    Ok(())
+}
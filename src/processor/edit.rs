@@ -0,0 +1,193 @@
+//! Handles the 'edit' file action: SEARCH/REPLACE hunks applied to an existing file.
+//!
+//! An `Edit File` block holds one or more hunks of the form
+//!
+//! ```text
+//! <<<<<<< SEARCH
+//! exact text to find
+//! =======
+//! text to put in its place
+//! >>>>>>> REPLACE
+//! ```
+//!
+//! Hunks are applied in order, each to the result of the previous ones. Every search text
+//! must occur exactly once; otherwise nothing is written.
+
+use crate::constants::{EDIT_DIVIDER_MARKER, EDIT_REPLACE_MARKER, EDIT_SEARCH_MARKER};
+use crate::core_types::{Action, Content, EditStatus, ProcessOptions};
+use crate::errors::ProcessError;
+use crate::processor::overlay::Workspace;
+use crate::reporter::{Event, Reporter};
+use std::path::Path;
+
+/// One SEARCH/REPLACE pair.
+#[derive(Debug)]
+struct Hunk {
+    search: String,
+    replace: String,
+}
+
+/// Applies the hunks in an `Edit File` block to an existing file.
+pub(crate) fn process_edit(
+    workspace: &mut Workspace,
+    item: &Action,
+    resolved_full_path: &Path,
+    relative_path_str: &str, // For logging
+    options: &ProcessOptions,
+    reporter: &dyn Reporter,
+) -> Result<EditStatus, ProcessError> {
+    let block = item
+        .content
+        .as_ref()
+        .and_then(Content::as_text)
+        .ok_or_else(|| ProcessError::Internal("Missing content for edit action".to_string()))?;
+
+    if workspace.is_dir(resolved_full_path) {
+        return Err(ProcessError::TargetIsDirectory {
+            path: resolved_full_path.to_path_buf(),
+        });
+    }
+    let original = workspace
+        .read_to_string(resolved_full_path)
+        .map_err(|e| ProcessError::Io { source: e })?;
+    let hunks = parse_hunks(block, resolved_full_path)?;
+    let edited = apply_hunks(&original, &hunks, resolved_full_path)?;
+
+    reporter.report(&Event::Info(format!(
+        "  {} file: {} ({} hunk{})",
+        if options.dry_run {
+            "Would edit"
+        } else {
+            "Editing"
+        },
+        relative_path_str,
+        hunks.len(),
+        if hunks.len() == 1 { "" } else { "s" }
+    )));
+    workspace
+        .write(resolved_full_path, edited.as_bytes())
+        .map_err(|e| ProcessError::Io { source: e })?;
+    Ok(EditStatus::Edited)
+}
+
+/// Parses an edit block and applies it to `original` in memory (used by diff and check).
+pub(crate) fn edit_text(original: &str, block: &str, path: &Path) -> Result<String, ProcessError> {
+    let hunks = parse_hunks(block, path)?;
+    apply_hunks(original, &hunks, path)
+}
+
+/// The (SEARCH, REPLACE) texts of an edit block's hunks, in order (used by check).
+pub(crate) fn hunk_texts(block: &str, path: &Path) -> Result<Vec<(String, String)>, ProcessError> {
+    let hunks = parse_hunks(block, path)?;
+    Ok(hunks
+        .into_iter()
+        .map(|hunk| (hunk.search, hunk.replace))
+        .collect())
+}
+
+/// Splits an edit block into its hunks. Blank lines between hunks are allowed; any other
+/// text outside a hunk, an unclosed hunk or an empty search text makes the block invalid.
+fn parse_hunks(block: &str, path: &Path) -> Result<Vec<Hunk>, ProcessError> {
+    enum State {
+        Outside,
+        Search(String),
+        Replace(String, String),
+    }
+    let invalid = |details: String| ProcessError::InvalidEditBlock {
+        path: path.to_path_buf(),
+        details,
+    };
+
+    let mut hunks = Vec::new();
+    let mut state = State::Outside;
+    for (line_index, line) in block.split_inclusive('\n').enumerate() {
+        let marker = line.trim_end();
+        state = match state {
+            State::Outside if marker.is_empty() => State::Outside,
+            State::Outside if marker == EDIT_SEARCH_MARKER => State::Search(String::new()),
+            State::Outside => {
+                return Err(invalid(format!(
+                    "line {} is outside a hunk (expected '{}')",
+                    line_index + 1,
+                    EDIT_SEARCH_MARKER
+                )))
+            }
+            State::Search(search) if marker == EDIT_DIVIDER_MARKER => {
+                State::Replace(search, String::new())
+            }
+            State::Search(mut search) => {
+                if marker == EDIT_SEARCH_MARKER || marker == EDIT_REPLACE_MARKER {
+                    return Err(invalid(format!(
+                        "line {}: expected '{}' before '{}'",
+                        line_index + 1,
+                        EDIT_DIVIDER_MARKER,
+                        marker
+                    )));
+                }
+                search.push_str(line);
+                State::Search(search)
+            }
+            State::Replace(search, replace) if marker == EDIT_REPLACE_MARKER => {
+                if search.is_empty() {
+                    return Err(invalid(format!(
+                        "hunk {} has an empty search text",
+                        hunks.len() + 1
+                    )));
+                }
+                hunks.push(Hunk { search, replace });
+                State::Outside
+            }
+            State::Replace(search, mut replace) => {
+                if marker == EDIT_SEARCH_MARKER || marker == EDIT_DIVIDER_MARKER {
+                    return Err(invalid(format!(
+                        "line {}: expected '{}' before '{}'",
+                        line_index + 1,
+                        EDIT_REPLACE_MARKER,
+                        marker
+                    )));
+                }
+                replace.push_str(line);
+                State::Replace(search, replace)
+            }
+        };
+    }
+
+    if !matches!(state, State::Outside) {
+        return Err(invalid(format!(
+            "hunk {} is not closed with '{}'",
+            hunks.len() + 1,
+            EDIT_REPLACE_MARKER
+        )));
+    }
+    if hunks.is_empty() {
+        return Err(invalid("no SEARCH/REPLACE hunks found".to_string()));
+    }
+    Ok(hunks)
+}
+
+/// Applies hunks in order. A search text that is missing or occurs more than once fails
+/// the whole edit. Blocks written with `\n` still match files that use `\r\n`.
+fn apply_hunks(original: &str, hunks: &[Hunk], path: &Path) -> Result<String, ProcessError> {
+    let crlf = original.contains("\r\n");
+    let mut text = original.to_string();
+    for (index, hunk) in hunks.iter().enumerate() {
+        let (search, replace) = if crlf && !hunk.search.contains("\r\n") {
+            (
+                hunk.search.replace('\n', "\r\n"),
+                hunk.replace.replace('\n', "\r\n"),
+            )
+        } else {
+            (hunk.search.clone(), hunk.replace.clone())
+        };
+        let matches = text.matches(&search).count();
+        if matches != 1 {
+            return Err(ProcessError::EditSearchMismatch {
+                path: path.to_path_buf(),
+                hunk: index + 1,
+                matches,
+            });
+        }
+        text = text.replacen(&search, &replace, 1);
+    }
+    Ok(text)
+}
@@ -0,0 +1,197 @@
+//! Handles the 'insert after' and 'insert before' file actions.
+//!
+//! The anchor is either a literal line (compared without surrounding whitespace) or a
+//! regex between slashes (`/^mod /`), matched against each line. `Insert After` uses the
+//! last matching line and `Insert Before` the first one.
+
+use crate::core_types::{Action, ActionType, Content, InsertStatus, ProcessOptions};
+use crate::errors::ProcessError;
+use crate::processor::overlay::Workspace;
+use crate::reporter::{Event, Reporter};
+use regex::Regex;
+use std::path::Path;
+
+/// An anchor as written in an `Insert After`/`Insert Before` header.
+enum Anchor {
+    Line(String),
+    Pattern(Regex),
+}
+
+impl Anchor {
+    fn parse(anchor: &str, path: &Path) -> Result<Anchor, ProcessError> {
+        match anchor
+            .strip_prefix('/')
+            .and_then(|rest| rest.strip_suffix('/'))
+        {
+            Some(pattern) if !pattern.is_empty() => Regex::new(pattern)
+                .map(Anchor::Pattern)
+                .map_err(|e| ProcessError::InvalidAnchor {
+                    path: path.to_path_buf(),
+                    anchor: anchor.to_string(),
+                    details: e.to_string(),
+                }),
+            _ => Ok(Anchor::Line(anchor.trim().to_string())),
+        }
+    }
+
+    fn matches(&self, line: &str) -> bool {
+        match self {
+            Anchor::Line(text) => line.trim() == text,
+            Anchor::Pattern(regex) => regex.is_match(line),
+        }
+    }
+}
+
+/// Inserts content next to the anchor line of an existing file.
+pub(crate) fn process_insert(
+    workspace: &mut Workspace,
+    item: &Action,
+    resolved_full_path: &Path,
+    relative_path_str: &str, // For logging
+    options: &ProcessOptions,
+    reporter: &dyn Reporter,
+) -> Result<InsertStatus, ProcessError> {
+    if workspace.is_dir(resolved_full_path) {
+        return Err(ProcessError::TargetIsDirectory {
+            path: resolved_full_path.to_path_buf(),
+        });
+    }
+    let original = workspace
+        .read_to_string(resolved_full_path)
+        .map_err(|e| ProcessError::Io { source: e })?;
+    let (inserted, anchor_line) =
+        insert_text(&original, item, options.overwrite, resolved_full_path)?;
+
+    let status = match anchor_line {
+        Some(line) => {
+            reporter.report(&Event::Info(format!(
+                "  {} file: {} ({} line {})",
+                if options.dry_run {
+                    "Would insert into"
+                } else {
+                    "Inserting into"
+                },
+                relative_path_str,
+                if item.action_type == ActionType::InsertAfter {
+                    "after"
+                } else {
+                    "before"
+                },
+                line
+            )));
+            InsertStatus::Inserted
+        }
+        None => {
+            reporter.report(&Event::Warning(format!(
+                "No line of '{}' matches the anchor '{}'. {} at the end of the file (--force).",
+                relative_path_str,
+                item.anchor.as_deref().unwrap_or_default(),
+                if options.dry_run {
+                    "Would insert"
+                } else {
+                    "Inserting"
+                }
+            )));
+            InsertStatus::InsertedAtEnd
+        }
+    };
+    workspace
+        .write(resolved_full_path, inserted.as_bytes())
+        .map_err(|e| ProcessError::Io { source: e })?;
+    Ok(status)
+}
+
+/// Inserts the action's content into `original` in memory (also used by diff and check).
+/// Returns the new text and the 1-based anchor line, or `None` when `force` made it fall
+/// back to the end of the file.
+pub(crate) fn insert_text(
+    original: &str,
+    item: &Action,
+    force: bool,
+    path: &Path,
+) -> Result<(String, Option<usize>), ProcessError> {
+    let content = item
+        .content
+        .as_ref()
+        .and_then(Content::as_text)
+        .ok_or_else(|| ProcessError::Internal("Missing content for insert action".to_string()))?;
+    let anchor_str = item
+        .anchor
+        .as_deref()
+        .ok_or_else(|| ProcessError::Internal("Missing anchor for insert action".to_string()))?;
+    let anchor = Anchor::parse(anchor_str, path)?;
+
+    // Insert whole lines, using the file's line ending
+    let eol = if original.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut block = if eol == "\r\n" && !content.contains("\r\n") {
+        content.replace('\n', "\r\n")
+    } else {
+        content.to_string()
+    };
+    if !block.ends_with('\n') {
+        block.push_str(eol);
+    }
+
+    // (line number, start, end including the line ending) of every matching line
+    let mut matching = Vec::new();
+    let mut start = 0;
+    for (index, line) in original.split_inclusive('\n').enumerate() {
+        let end = start + line.len();
+        if anchor.matches(line.trim_end_matches(['\n', '\r'])) {
+            matching.push((index + 1, start, end));
+        }
+        start = end;
+    }
+    let chosen = if item.action_type == ActionType::InsertAfter {
+        matching.last()
+    } else {
+        matching.first()
+    };
+
+    let inserted = match chosen {
+        Some(&(_, line_start, _)) if item.action_type == ActionType::InsertBefore => {
+            format!(
+                "{}{}{}",
+                &original[..line_start],
+                block,
+                &original[line_start..]
+            )
+        }
+        Some(&(_, _, line_end)) if original[..line_end].ends_with('\n') => {
+            format!(
+                "{}{}{}",
+                &original[..line_end],
+                block,
+                &original[line_end..]
+            )
+        }
+        // The anchor is the last line and has no line ending: keep it that way
+        Some(_) => join_at_end(original, &block, eol),
+        None if force => join_at_end(original, &block, eol),
+        None => {
+            return Err(ProcessError::AnchorNotFound {
+                path: path.to_path_buf(),
+                anchor: anchor_str.to_string(),
+            })
+        }
+    };
+    Ok((inserted, chosen.map(|&(line, _, _)| line)))
+}
+
+/// Adds `block` as new last lines, keeping a missing final line ending missing.
+fn join_at_end(original: &str, block: &str, eol: &str) -> String {
+    if original.is_empty() || original.ends_with('\n') {
+        format!("{}{}", original, block)
+    } else {
+        format!(
+            "{}{}{}",
+            original,
+            eol,
+            block.trim_end_matches(['\n', '\r'])
+        )
+    }
+}
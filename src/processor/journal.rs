@@ -0,0 +1,347 @@
+//! Records the prior state of every path a run touches, so the run can be rolled back
+//! (--atomic) or saved as an undo journal under the output directory and reverted later.
+//!
+//! A saved journal lives in `.strux/journal/<unix-millis>/` and holds `journal.json`
+//! (one entry per path: its state before the run and a fingerprint of its state after it)
+//! plus `blobs/<n>` with the prior contents of every file that existed before the run.
+//! Only the newest `KEEP_JOURNALS` journals are kept; saving one drops the oldest beyond that.
+
+use crate::errors::ProcessError;
+use crate::processor::symlink::create_symlink;
+use crate::reporter::{Event, Reporter};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Directory (relative to the output directory) that actions may not touch.
+pub(crate) const RESERVED_DIR: &str = ".strux";
+/// Directory (relative to the output directory) holding one subdirectory per saved journal.
+pub(crate) const JOURNAL_DIR: &str = ".strux/journal";
+/// How many saved journals (and so successive `undo` steps) are kept per output directory.
+pub(crate) const KEEP_JOURNALS: usize = 10;
+/// Version of the `journal.json` layout; journals with another version are not replayed.
+/// Version 2 added file permissions.
+pub(crate) const JOURNAL_VERSION: u64 = 2;
+
+/// What was at a path before the run first touched it.
+#[derive(Debug)]
+pub(crate) enum PriorState {
+    Missing, // Nothing existed; anything found there on rollback is removed
+    File {
+        content: Vec<u8>,  // A regular file with these exact bytes
+        mode: Option<u32>, // and these permission bits (None where the platform has none)
+    },
+    Directory,        // An existing directory; recreated (empty) if the run removed it
+    Symlink(PathBuf), // A symbolic link with this target (never followed)
+}
+
+/// Prior states in the order they were recorded. Only the first record of a path counts.
+#[derive(Debug, Default)]
+pub(crate) struct Journal {
+    entries: Vec<(PathBuf, PriorState)>,
+    recorded: HashSet<PathBuf>,
+}
+
+impl Journal {
+    pub(crate) fn new() -> Self {
+        Journal::default()
+    }
+
+    /// Records `path` and any of its missing ancestor directories before they are modified.
+    pub(crate) fn record(&mut self, path: &Path) -> Result<(), ProcessError> {
+        // Ancestors first, so rollback (in reverse) empties a directory before removing it
+        let mut missing_ancestors: Vec<&Path> = path
+            .ancestors()
+            .skip(1)
+            .take_while(|ancestor| !ancestor.as_os_str().is_empty() && !ancestor.exists())
+            .collect();
+        missing_ancestors.reverse();
+        for ancestor in missing_ancestors {
+            self.push(ancestor, PriorState::Missing);
+        }
+
+        if self.recorded.contains(path) {
+            return Ok(());
+        }
+        let prior = match fs::symlink_metadata(path) {
+            Ok(metadata) if metadata.is_dir() => PriorState::Directory,
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                PriorState::Symlink(fs::read_link(path)?)
+            }
+            Ok(metadata) => PriorState::File {
+                content: fs::read(path)?,
+                mode: file_mode(&metadata),
+            },
+            Err(e) if e.kind() == ErrorKind::NotFound || e.kind() == ErrorKind::NotADirectory => {
+                PriorState::Missing
+            }
+            Err(e) => return Err(ProcessError::Io { source: e }),
+        };
+        self.push(path, prior);
+        Ok(())
+    }
+
+    /// Records `path` and, if it is a directory, everything below it (for `Deleted Directory`).
+    pub(crate) fn record_tree(&mut self, path: &Path) -> Result<(), ProcessError> {
+        self.record(path)?;
+        if fs::symlink_metadata(path).is_ok_and(|metadata| metadata.is_dir()) {
+            let mut entries = fs::read_dir(path)?.collect::<Result<Vec<_>, _>>()?;
+            entries.sort_by_key(|entry| entry.file_name());
+            for entry in entries {
+                self.record_tree(&entry.path())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Records everything a directory move can touch: the source tree, the existing
+    /// destination tree, and every path the source's entries will land on below `dest`.
+    pub(crate) fn record_move_tree(
+        &mut self,
+        source: &Path,
+        dest: &Path,
+    ) -> Result<(), ProcessError> {
+        self.record_tree(source)?;
+        self.record_tree(dest)?;
+        self.record_landing_paths(source, dest)
+    }
+
+    /// Records `dest`-side counterparts of everything below `source`, parents first.
+    fn record_landing_paths(&mut self, source: &Path, dest: &Path) -> Result<(), ProcessError> {
+        if !fs::symlink_metadata(source).is_ok_and(|metadata| metadata.is_dir()) {
+            return Ok(());
+        }
+        let mut entries = fs::read_dir(source)?.collect::<Result<Vec<_>, _>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+        for entry in entries {
+            let landing = dest.join(entry.file_name());
+            self.record(&landing)?;
+            self.record_landing_paths(&entry.path(), &landing)?;
+        }
+        Ok(())
+    }
+
+    /// Whether any recorded path inside `resolved_base` (what `persist` saves) is no longer
+    /// in its prior state. Skipped actions leave their recorded paths as they were.
+    pub(crate) fn has_changes(&self, resolved_base: &Path) -> bool {
+        self.entries.iter().any(|(path, prior)| {
+            path.strip_prefix(resolved_base)
+                .is_ok_and(|relative| !relative.as_os_str().is_empty())
+                && !is_in_state(path, prior)
+        })
+    }
+
+    fn push(&mut self, path: &Path, prior: PriorState) {
+        if self.recorded.insert(path.to_path_buf()) {
+            self.entries.push((path.to_path_buf(), prior));
+        }
+    }
+
+    /// Restores every recorded path to its prior state, newest record first.
+    /// Keeps going after a failure and returns how many paths could not be restored.
+    pub(crate) fn rollback(&self, reporter: &dyn Reporter) -> usize {
+        let mut failures = 0;
+        for (path, prior) in self.entries.iter().rev() {
+            if let Err(e) = restore(path, prior) {
+                reporter.report(&Event::Error(format!(
+                    "Could not restore '{}' during rollback: {}",
+                    path.display(),
+                    e
+                )));
+                failures += 1;
+            }
+        }
+        failures
+    }
+}
+
+impl Journal {
+    /// Saves the journal under `resolved_base` for a later `undo`, returning its directory.
+    /// Only paths inside the base are saved; the base directory itself is left alone by undo.
+    pub(crate) fn persist(&self, resolved_base: &Path) -> Result<PathBuf, ProcessError> {
+        let journal_dir = new_journal_dir(&resolved_base.join(JOURNAL_DIR))?;
+        let blobs_dir = journal_dir.join("blobs");
+        fs::create_dir_all(&blobs_dir)?;
+
+        let mut entries_json = Vec::new();
+        for (path, prior) in &self.entries {
+            let relative = match path.strip_prefix(resolved_base) {
+                Ok(relative) if !relative.as_os_str().is_empty() => relative,
+                _ => continue, // The base directory or one of its ancestors
+            };
+            let before = match prior {
+                PriorState::Missing => json!({ "state": "missing" }),
+                PriorState::Directory => json!({ "state": "directory" }),
+                PriorState::Symlink(target) => {
+                    json!({ "state": "symlink", "target": target.to_string_lossy() })
+                }
+                PriorState::File { content, mode } => {
+                    let blob = entries_json.len().to_string();
+                    fs::write(blobs_dir.join(&blob), content)?;
+                    json!({ "state": "file", "blob": blob, "mode": mode })
+                }
+            };
+            entries_json.push(json!({
+                "path": relative.to_string_lossy(),
+                "before": before,
+                "after": fingerprint(path)?,
+            }));
+        }
+
+        let document = json!({ "version": JOURNAL_VERSION, "entries": entries_json });
+        let manifest = serde_json::to_string_pretty(&document)
+            .map_err(|e| ProcessError::Internal(format!("Could not serialize journal: {}", e)))?;
+        fs::write(journal_dir.join("journal.json"), manifest)?;
+        prune_journals(&resolved_base.join(JOURNAL_DIR), KEEP_JOURNALS);
+        Ok(journal_dir)
+    }
+}
+
+/// Removes all but the newest `keep` journals under `journals_root`. Best effort: a journal
+/// that cannot be removed only takes up space, and other entries are never touched.
+fn prune_journals(journals_root: &Path, keep: usize) {
+    let mut journals: Vec<(u128, PathBuf)> = fs::read_dir(journals_root)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let millis = entry.file_name().to_str()?.parse::<u128>().ok()?;
+            Some((millis, entry.path()))
+        })
+        .collect();
+    journals.sort_by_key(|(millis, _)| std::cmp::Reverse(*millis));
+    for (_, journal_dir) in journals.into_iter().skip(keep) {
+        let _ = fs::remove_dir_all(journal_dir);
+    }
+}
+
+/// Creates a fresh, uniquely named journal directory under `journals_root`.
+fn new_journal_dir(journals_root: &Path) -> Result<PathBuf, ProcessError> {
+    fs::create_dir_all(journals_root)?;
+    let mut millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis())
+        .unwrap_or_default();
+    loop {
+        let candidate = journals_root.join(millis.to_string());
+        match fs::create_dir(&candidate) {
+            Ok(()) => return Ok(candidate),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => millis += 1,
+            Err(e) => return Err(ProcessError::Io { source: e }),
+        }
+    }
+}
+
+/// Describes the current state of a path compactly enough to detect later changes.
+pub(crate) fn fingerprint(path: &Path) -> Result<Value, ProcessError> {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => Ok(json!({ "state": "directory" })),
+        Ok(metadata) if metadata.file_type().is_symlink() => Ok(json!({
+            "state": "symlink",
+            "target": fs::read_link(path)?.to_string_lossy(),
+        })),
+        Ok(metadata) => {
+            let bytes = fs::read(path)?;
+            Ok(json!({
+                "state": "file",
+                "len": bytes.len(),
+                "hash": format!("{:016x}", fnv1a(&bytes)),
+                "mode": file_mode(&metadata),
+            }))
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound || e.kind() == ErrorKind::NotADirectory => {
+            Ok(json!({ "state": "missing" }))
+        }
+        Err(e) => Err(ProcessError::Io { source: e }),
+    }
+}
+
+/// 64-bit FNV-1a: fast and good enough to notice edits (it is not a security check).
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Whether `path` is currently exactly in `prior` state. Unreadable paths count as changed.
+fn is_in_state(path: &Path, prior: &PriorState) -> bool {
+    match (prior, fs::symlink_metadata(path)) {
+        (PriorState::Missing, Err(e)) => {
+            e.kind() == ErrorKind::NotFound || e.kind() == ErrorKind::NotADirectory
+        }
+        (PriorState::Directory, Ok(metadata)) => metadata.is_dir(),
+        (PriorState::Symlink(target), Ok(metadata)) => {
+            metadata.file_type().is_symlink()
+                && fs::read_link(path).is_ok_and(|current| current == *target)
+        }
+        (PriorState::File { content, mode }, Ok(metadata)) => {
+            metadata.is_file()
+                && file_mode(&metadata) == *mode
+                && fs::read(path).is_ok_and(|current| current == *content)
+        }
+        _ => false,
+    }
+}
+
+/// Puts a single path back into its prior state.
+pub(crate) fn restore(path: &Path, prior: &PriorState) -> std::io::Result<()> {
+    match prior {
+        PriorState::Missing => match fs::symlink_metadata(path) {
+            Ok(metadata) if metadata.is_dir() => fs::remove_dir(path),
+            Ok(_) => fs::remove_file(path),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e),
+        },
+        PriorState::File { content, mode } => {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            // Replace a link that took the file's place instead of writing through it
+            if fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_symlink()) {
+                fs::remove_file(path)?;
+            }
+            fs::write(path, content)?;
+            // Writing keeps the permissions of a file that is still there (e.g. after `mode=`)
+            match mode {
+                Some(mode) => set_file_mode(path, *mode),
+                None => Ok(()),
+            }
+        }
+        PriorState::Directory => fs::create_dir_all(path),
+        PriorState::Symlink(target) => {
+            match fs::symlink_metadata(path) {
+                Ok(metadata) if metadata.is_dir() => fs::remove_dir(path)?,
+                Ok(_) => fs::remove_file(path)?,
+                Err(e) if e.kind() == ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+            create_symlink(target, path)
+        }
+    }
+}
+
+/// Permission bits of a regular file, where the platform has them.
+#[cfg(unix)]
+fn file_mode(metadata: &fs::Metadata) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    Some(metadata.permissions().mode() & 0o7777)
+}
+
+#[cfg(not(unix))]
+fn file_mode(_metadata: &fs::Metadata) -> Option<u32> {
+    None
+}
+
+#[cfg(unix)]
+fn set_file_mode(path: &Path, mode: u32) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+}
+
+#[cfg(not(unix))]
+fn set_file_mode(_path: &Path, _mode: u32) -> std::io::Result<()> {
+    Ok(())
+}
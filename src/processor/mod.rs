@@ -1,20 +1,37 @@
-//! Orchestrates the processing of parsed file actions (create, delete).
+//! Orchestrates the processing of parsed file actions (create, delete, move, directories).
 
-use crate::core_types::{Action, Summary};
+use crate::core_types::{Action, ActionOutcome, ProcessOptions, ProcessReport, Summary};
 use crate::errors::AppError;
+use crate::reporter::{ConsoleReporter, Event, Reporter};
 // Removed unused fs import
+use journal::Journal;
+use overlay::Overlay;
 use std::path::Path;
 
 // Declare processor submodules
 mod action_handler;
 mod append; // ADDED
 mod base_setup;
+mod copy_file;
 mod create;
 mod delete;
+mod directory;
+pub(crate) mod edit; // Also used by `check`
+pub(crate) mod insert; // Also used by `check`
+mod journal;
 mod move_file;
+mod overlay;
+pub(crate) mod patch; // Also used by `check`
+mod permissions;
 mod prepend; // ADDED
+mod preview;
 mod safety;
 mod summary_updater;
+mod symlink; // Also used by the journal to restore links
+mod undo;
+
+pub use preview::{preview_actions, preview_actions_with, ChangeKind, FileChange, Preview};
+pub use undo::{undo_last_run, UndoReport};
 
 // Declare the unit test module for safety
 #[cfg(test)]
@@ -28,54 +45,184 @@ pub fn process_actions(
     actions: Vec<Action>,
     overwrite: bool,
 ) -> Result<Summary, AppError> {
-    let mut summary = Summary::default();
+    let options = ProcessOptions {
+        overwrite,
+        journal: true,
+        ..ProcessOptions::default()
+    };
+    process_actions_with(base_dir, actions, &options, &ConsoleReporter::new())
+        .map(|report| report.summary)
+}
+
+/// Plans a list of actions without touching the filesystem (dry run).
+/// Every action goes through the same validation, safety checks and handler decisions
+/// as `process_actions`, but nothing is written, deleted, moved or created.
+/// The returned report holds the outcome each action would produce.
+pub fn plan_actions(
+    base_dir: &Path,
+    actions: Vec<Action>,
+    overwrite: bool,
+) -> Result<ProcessReport, AppError> {
+    let options = ProcessOptions {
+        overwrite,
+        dry_run: true,
+        ..ProcessOptions::default()
+    };
+    process_actions_with(base_dir, actions, &options, &ConsoleReporter::new())
+}
+
+/// Processes a list of actions with explicit options, returning the summary
+/// together with the outcome of every individual action.
+/// All diagnostics are sent to `reporter`.
+///
+/// With `options.journal`, the prior state of every touched path is saved as an undo journal
+/// in the output directory (see `undo_last_run`).
+/// With `options.atomic`, the prior state of every touched path is recorded first. Processing
+/// stops at the first failed action and all changes (including created directories) are undone.
+pub fn process_actions_with(
+    base_dir: &Path,
+    actions: Vec<Action>,
+    options: &ProcessOptions,
+    reporter: &dyn Reporter,
+) -> Result<ProcessReport, AppError> {
+    run_actions(base_dir, &actions, options, reporter).map(|(report, _)| report)
+}
+
+/// Runs the actions like `process_actions_with`. A dry run also returns the overlay it was
+/// planned against, which holds every change the run would make.
+pub(crate) fn run_actions(
+    base_dir: &Path,
+    actions: &[Action],
+    options: &ProcessOptions,
+    reporter: &dyn Reporter,
+) -> Result<(ProcessReport, Option<Overlay>), AppError> {
+    let mut report = ProcessReport::default();
+    // Dry runs never modify anything, so there is nothing to roll back
+    let mut journal = ((options.atomic || options.journal) && !options.dry_run).then(Journal::new);
+
+    // The base directory (and its missing parents) is part of the pre-run state too
+    if let Some(journal) = journal.as_mut() {
+        journal.record(&std::path::absolute(base_dir)?)?;
+    }
 
     // --- Ensure base directory exists FIRST ---
-    // Use the user-provided path for setup.
-    println!(
-        "Ensuring target base directory exists: {}",
-        base_dir.display()
-    );
-    base_setup::setup_base_directory(base_dir)?; // Use new module
+    // Use the user-provided path for setup. A dry run only validates it.
+    let base_exists = if options.dry_run {
+        reporter.report(&Event::Info(
+            "Dry run: no changes will be made to the filesystem.".to_string(),
+        ));
+        base_setup::check_base_directory(base_dir, reporter)?
+    } else {
+        reporter.report(&Event::Info(format!(
+            "Ensuring target base directory exists: {}",
+            base_dir.display()
+        )));
+        base_setup::setup_base_directory(base_dir, reporter)?; // Use new module
+        true
+    };
 
     // --- Resolve base directory path AFTER ensuring it exists ---
-    // This is needed for safety checks.
-    let resolved_base = match base_dir.canonicalize() {
+    // This is needed for safety checks. A missing base (dry run only) is made absolute instead.
+    let resolved_base_result = if base_exists {
+        base_dir.canonicalize()
+    } else {
+        std::path::absolute(base_dir)
+    };
+    let resolved_base = match resolved_base_result {
         Ok(path) => path,
         Err(e) => {
             // If canonicalize fails even after setup_base_directory, it's a more serious issue.
-            eprintln!(
-                "Error: Could not resolve base directory path '{}' after ensuring existence: {}",
+            reporter.report(&Event::Error(format!(
+                "Could not resolve base directory path '{}' after ensuring existence: {}",
                 base_dir.display(),
                 e
-            );
+            )));
             return Err(AppError::Io(e)); // Treat as fatal setup error
         }
     };
-    println!(
+    // A dry run plans every action against this, so each one sees what the earlier ones did
+    let mut overlay = options.dry_run.then(|| Overlay::new(&resolved_base));
+    reporter.report(&Event::Info(format!(
         "Processing actions relative to resolved base: {}",
         resolved_base.display()
-    );
+    )));
 
-    println!(
+    reporter.report(&Event::Info(format!(
         "\nProcessing {} actions in '{}'...",
         actions.len(),
         resolved_base.display()
-    ); // Use resolved for consistency
+    ))); // Use resolved for consistency
 
     for (item_index, item) in actions.iter().enumerate() {
         // Delegate processing of a single action
-        action_handler::process_single_action(
+        let outcome = action_handler::process_single_action(
             item,
             item_index,
             actions.len(),
+            &actions[item_index + 1..],
             &resolved_base, // Pass the canonicalized path for safety checks
-            overwrite,
-            &mut summary,
+            options,
+            &mut report.summary,
+            journal.as_mut(),
+            overlay.as_mut(),
+            reporter,
         );
+        report.outcomes.push(outcome);
+        if options.atomic && journal.is_some() && report.summary.has_failures() {
+            break; // Atomic: no point applying the rest, everything is undone below
+        }
+    }
+
+    if let Some(journal) = journal {
+        if options.atomic && report.summary.has_failures() {
+            reporter.report(&Event::Warning(
+                "An action failed in atomic mode. Rolling back all changes...".to_string(),
+            ));
+            let failures = journal.rollback(reporter);
+            if failures > 0 {
+                reporter.report(&Event::Error(format!(
+                    "Rollback incomplete: {} path(s) could not be restored.",
+                    failures
+                )));
+            } else {
+                reporter.report(&Event::Info(
+                    "Rollback complete: the output directory is back to its pre-run state."
+                        .to_string(),
+                ));
+            }
+            report.rolled_back = true;
+            // Every action stays listed: the applied ones were undone, the rest never ran
+            report.outcomes = std::mem::take(&mut report.outcomes)
+                .into_iter()
+                .map(|outcome| match outcome {
+                    ActionOutcome::Failed(_) => outcome,
+                    applied => ActionOutcome::RolledBack(Box::new(applied)),
+                })
+                .collect();
+            report
+                .outcomes
+                .resize_with(actions.len(), || ActionOutcome::NotRun);
+        } else if options.journal && journal.has_changes(&resolved_base) {
+            // Paths are recorded before handlers decide to skip; a run that changed nothing
+            // saves no journal, so `undo` reverts the last run that did.
+            // The run itself succeeded; a journal that cannot be saved only costs the undo.
+            match journal.persist(&resolved_base) {
+                Ok(journal_dir) => {
+                    reporter.report(&Event::Info(format!(
+                        "Undo journal saved to: {}",
+                        journal_dir.display()
+                    )));
+                    report.journal_dir = Some(journal_dir);
+                }
+                Err(e) => reporter.report(&Event::Warning(format!(
+                    "Could not save undo journal: {}",
+                    e
+                ))),
+            }
+        }
     }
 
-    Ok(summary)
+    Ok((report, overlay))
 }
 
 // --- Moved to base_setup.rs ---
@@ -0,0 +1,604 @@
+//! The output directory as the action handlers see it.
+//!
+//! A real run reads and writes the disk. A dry run goes through an in-memory overlay
+//! instead: every write is recorded there and every later read sees it, so each action is
+//! planned against the state the earlier ones would leave (e.g. a `Moved File` of a file
+//! created above it). The overlay also keeps the file-level changes it was asked to make,
+//! which is what `--diff` renders.
+
+use crate::processor::preview::{ChangeKind, FileChange};
+use crate::processor::safety::{normalize, resolve_link_target};
+use crate::processor::symlink::create_symlink;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+/// Permissions assumed for a file that a dry run would create.
+const NEW_FILE_MODE: u32 = 0o644;
+/// Symbolic links followed before a path is considered unresolvable (a loop).
+const MAX_LINKS: usize = 40;
+
+/// What a path is, without following a final symbolic link (like `fs::symlink_metadata`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    File,
+    Directory,
+    Symlink,
+    Other, // Sockets, devices and the like
+}
+
+impl Kind {
+    fn of(file_type: fs::FileType) -> Kind {
+        if file_type.is_symlink() {
+            Kind::Symlink
+        } else if file_type.is_dir() {
+            Kind::Directory
+        } else if file_type.is_file() {
+            Kind::File
+        } else {
+            Kind::Other
+        }
+    }
+}
+
+/// A path's state as planned by an earlier action of a dry run.
+#[derive(Debug, Clone)]
+enum Node {
+    Missing,
+    File { content: Vec<u8>, mode: u32 },
+    Directory, // Created by the run: nothing on disk below it is visible
+    Symlink(PathBuf),
+}
+
+/// Where a (normalized) path's current state comes from.
+enum State {
+    Planned(Node),
+    Disk(fs::Metadata),
+}
+
+/// Pending changes of a dry run, layered over the real output directory.
+#[derive(Debug)]
+pub(crate) struct Overlay {
+    resolved_base: PathBuf,
+    nodes: HashMap<PathBuf, Node>, // Keyed by normalized absolute path
+    changes: Vec<FileChange>,
+    action_index: usize, // The action whose changes are being recorded
+}
+
+impl Overlay {
+    /// An empty overlay. A base directory that does not exist yet is planned as created.
+    pub(crate) fn new(resolved_base: &Path) -> Self {
+        let mut nodes = HashMap::new();
+        if fs::symlink_metadata(resolved_base).is_err() {
+            nodes.insert(normalize(resolved_base), Node::Directory);
+        }
+        Overlay {
+            resolved_base: resolved_base.to_path_buf(),
+            nodes,
+            changes: Vec::new(),
+            action_index: 0,
+        }
+    }
+
+    /// Attributes the changes recorded from now on to the action at `action_index`.
+    pub(crate) fn start_action(&mut self, action_index: usize) {
+        self.action_index = action_index;
+    }
+
+    /// The file changes recorded so far, in the order they were made.
+    pub(crate) fn into_changes(self) -> Vec<FileChange> {
+        self.changes
+    }
+
+    /// Finds the state of a normalized path, following planned links along the way.
+    fn locate(&self, path: &Path, links_followed: usize) -> io::Result<(PathBuf, State)> {
+        if let Some(node) = self.nodes.get(path) {
+            return match node {
+                Node::Missing => Err(ErrorKind::NotFound.into()),
+                node => Ok((path.to_path_buf(), State::Planned(node.clone()))),
+            };
+        }
+        // The nearest planned ancestor decides whether the disk below it still shows through
+        for ancestor in path.ancestors().skip(1) {
+            match self.nodes.get(ancestor) {
+                None => continue,
+                Some(Node::Missing | Node::Directory) => return Err(ErrorKind::NotFound.into()),
+                Some(Node::File { .. }) => return Err(ErrorKind::NotADirectory.into()),
+                Some(Node::Symlink(target)) => {
+                    if links_followed >= MAX_LINKS {
+                        return Err(io::Error::other("too many levels of symbolic links"));
+                    }
+                    let rest = path.strip_prefix(ancestor).unwrap_or(path);
+                    let followed = normalize(&resolve_link_target(ancestor, target).join(rest));
+                    return self.locate(&followed, links_followed + 1);
+                }
+            }
+        }
+        let metadata = fs::symlink_metadata(path)?;
+        Ok((path.to_path_buf(), State::Disk(metadata)))
+    }
+
+    /// Like `locate`, but also follows a final link (planned or on disk).
+    fn follow(&self, path: &Path) -> io::Result<(PathBuf, State)> {
+        let mut current = normalize(path);
+        for _ in 0..MAX_LINKS {
+            let (located, state) = self.locate(&current, 0)?;
+            let target = match &state {
+                State::Planned(Node::Symlink(target)) => target.clone(),
+                State::Disk(metadata) if metadata.file_type().is_symlink() => {
+                    fs::read_link(&located)?
+                }
+                _ => return Ok((located, state)),
+            };
+            current = normalize(&resolve_link_target(&located, &target));
+        }
+        Err(io::Error::other("too many levels of symbolic links"))
+    }
+
+    fn symlink_kind(&self, path: &Path) -> io::Result<Kind> {
+        Ok(match self.locate(&normalize(path), 0)?.1 {
+            State::Planned(node) => node_kind(&node),
+            State::Disk(metadata) => Kind::of(metadata.file_type()),
+        })
+    }
+
+    fn kind(&self, path: &Path) -> io::Result<Kind> {
+        Ok(match self.follow(path)?.1 {
+            State::Planned(node) => node_kind(&node),
+            State::Disk(metadata) => Kind::of(metadata.file_type()),
+        })
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        match self.follow(path)? {
+            (_, State::Planned(Node::File { content, .. })) => Ok(content),
+            (_, State::Planned(_)) => Err(ErrorKind::IsADirectory.into()),
+            (located, State::Disk(_)) => fs::read(located),
+        }
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        match self.locate(&normalize(path), 0)? {
+            (_, State::Planned(Node::Symlink(target))) => Ok(target),
+            (_, State::Planned(_)) => Err(ErrorKind::InvalidInput.into()),
+            (located, State::Disk(_)) => fs::read_link(located),
+        }
+    }
+
+    fn mode(&self, path: &Path) -> io::Result<u32> {
+        match self.follow(path)? {
+            (_, State::Planned(Node::File { mode, .. })) => Ok(mode),
+            (_, State::Planned(_)) => Ok(0o755),
+            (_, State::Disk(metadata)) => Ok(disk_mode(&metadata)),
+        }
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<(OsString, Kind)>> {
+        let (located, state) = self.follow(path)?;
+        let mut names = Vec::new();
+        match state {
+            State::Planned(Node::Directory) => {}
+            State::Disk(metadata) if metadata.is_dir() => {
+                for entry in fs::read_dir(&located)? {
+                    names.push(entry?.file_name());
+                }
+            }
+            _ => return Err(ErrorKind::NotADirectory.into()),
+        }
+        names.extend(
+            self.nodes
+                .keys()
+                .filter(|key| key.parent() == Some(located.as_path()))
+                .filter_map(|key| key.file_name().map(OsString::from)),
+        );
+        names.sort();
+        names.dedup();
+        // Planned removals hide entries still on disk
+        Ok(names
+            .into_iter()
+            .filter_map(|name| {
+                let kind = self.symlink_kind(&located.join(&name)).ok()?;
+                Some((name, kind))
+            })
+            .collect())
+    }
+
+    /// Plans `node` at `path`, dropping everything planned below it.
+    fn set(&mut self, path: &Path, node: Node) {
+        let path = normalize(path);
+        self.nodes
+            .retain(|key, _| !key.starts_with(&path) || *key == path);
+        self.nodes.insert(path, node);
+    }
+
+    /// Fails like the OS would when `path` cannot be created because its parent is missing
+    /// or not a directory.
+    fn check_parent(&self, path: &Path) -> io::Result<()> {
+        match normalize(path).parent() {
+            Some(parent) => match self.kind(parent)? {
+                Kind::Directory => Ok(()),
+                _ => Err(ErrorKind::NotADirectory.into()),
+            },
+            None => Ok(()),
+        }
+    }
+
+    fn record(
+        &mut self,
+        path: &Path,
+        kind: ChangeKind,
+        before: Option<&[u8]>,
+        after: Option<&[u8]>,
+    ) {
+        self.changes.push(FileChange {
+            action_index: self.action_index,
+            path: self.relative(path),
+            kind,
+            before: before.map(|bytes| String::from_utf8_lossy(bytes).into_owned()),
+            after: after.map(|bytes| String::from_utf8_lossy(bytes).into_owned()),
+        });
+    }
+
+    fn relative(&self, path: &Path) -> String {
+        let path = normalize(path);
+        path.strip_prefix(&self.resolved_base)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn write(&mut self, path: &Path, bytes: &[u8]) -> io::Result<()> {
+        // Writing through a link writes its target
+        let target = match self.follow(path) {
+            Ok((located, _)) => located,
+            Err(e) if e.kind() == ErrorKind::NotFound => normalize(path),
+            Err(e) => return Err(e),
+        };
+        self.check_parent(&target)?;
+        let (before, mode) = match self.kind(&target) {
+            Ok(Kind::Directory) => return Err(ErrorKind::IsADirectory.into()),
+            Ok(_) => (Some(self.read(&target)?), self.mode(&target)?),
+            Err(_) => (None, NEW_FILE_MODE),
+        };
+        self.record(&target, ChangeKind::Modify, before.as_deref(), Some(bytes));
+        self.set(
+            &target,
+            Node::File {
+                content: bytes.to_vec(),
+                mode,
+            },
+        );
+        Ok(())
+    }
+
+    fn remove_file(&mut self, path: &Path) -> io::Result<()> {
+        match self.symlink_kind(path)? {
+            Kind::Directory => return Err(ErrorKind::IsADirectory.into()),
+            Kind::File => {
+                let before = self.read(path)?;
+                self.record(path, ChangeKind::Delete, Some(&before), None);
+            }
+            Kind::Symlink | Kind::Other => {}
+        }
+        self.set(path, Node::Missing);
+        Ok(())
+    }
+
+    fn create_dir(&mut self, path: &Path) -> io::Result<()> {
+        if self.symlink_kind(path).is_ok() {
+            return Err(ErrorKind::AlreadyExists.into());
+        }
+        self.check_parent(path)?;
+        self.set(path, Node::Directory);
+        Ok(())
+    }
+
+    fn create_dir_all(&mut self, path: &Path) -> io::Result<()> {
+        match self.kind(path) {
+            Ok(Kind::Directory) => return Ok(()),
+            Ok(_) => return Err(ErrorKind::AlreadyExists.into()),
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        if let Some(parent) = normalize(path).parent() {
+            self.create_dir_all(parent)?;
+        }
+        self.create_dir(path)
+    }
+
+    fn remove_dir_all(&mut self, path: &Path) -> io::Result<()> {
+        for (name, kind) in self.read_dir(path)? {
+            let child = path.join(name);
+            match kind {
+                Kind::Directory => self.remove_dir_all(&child)?,
+                _ => self.remove_file(&child)?,
+            }
+        }
+        self.set(path, Node::Missing);
+        Ok(())
+    }
+
+    fn rename(&mut self, source: &Path, dest: &Path) -> io::Result<()> {
+        let kind = self.symlink_kind(source)?;
+        self.check_parent(dest)?;
+        if kind == Kind::Directory {
+            match self.symlink_kind(dest) {
+                Ok(Kind::Directory) if self.read_dir(dest)?.is_empty() => {}
+                Ok(_) => return Err(ErrorKind::DirectoryNotEmpty.into()),
+                Err(_) => {}
+            }
+            self.set(dest, Node::Directory);
+            for (name, _) in self.read_dir(source)? {
+                self.rename(&source.join(&name), &dest.join(&name))?;
+            }
+            self.set(source, Node::Missing);
+            return Ok(());
+        }
+
+        let node = match kind {
+            Kind::Symlink => Node::Symlink(self.read_link(source)?),
+            _ => Node::File {
+                content: self.read(source)?,
+                mode: self.mode(source)?,
+            },
+        };
+        let mut overwritten = match self.symlink_kind(dest) {
+            Ok(Kind::Directory) => return Err(ErrorKind::IsADirectory.into()),
+            Ok(Kind::File) => Some(self.read(dest)?),
+            _ => None,
+        };
+        // A destination removed just before (`--force`) shows as overwritten by the rename
+        let dest_relative = self.relative(dest);
+        if let Some(last) = self.changes.last() {
+            if last.action_index == self.action_index
+                && last.kind == ChangeKind::Delete
+                && last.path == dest_relative
+                && overwritten.is_none()
+            {
+                overwritten = self
+                    .changes
+                    .pop()
+                    .and_then(|change| change.before.map(String::into_bytes));
+            }
+        }
+        if let Node::File { content, .. } = &node {
+            let to = dest_relative.clone();
+            self.record(
+                source,
+                ChangeKind::Rename { to },
+                overwritten.as_deref(),
+                Some(content),
+            );
+        }
+        self.set(dest, node);
+        self.set(source, Node::Missing);
+        Ok(())
+    }
+
+    fn copy(&mut self, source: &Path, dest: &Path) -> io::Result<()> {
+        let content = self.read(source)?;
+        let mode = self.mode(source)?;
+        self.write(dest, &content)?;
+        self.set_mode(dest, mode)
+    }
+
+    fn symlink(&mut self, target: &Path, link: &Path) -> io::Result<()> {
+        if self.symlink_kind(link).is_ok() {
+            return Err(ErrorKind::AlreadyExists.into());
+        }
+        self.check_parent(link)?;
+        self.set(link, Node::Symlink(target.to_path_buf()));
+        Ok(())
+    }
+
+    fn set_mode(&mut self, path: &Path, mode: u32) -> io::Result<()> {
+        let (located, _) = self.follow(path)?;
+        let content = self.read(&located)?;
+        self.set(&located, Node::File { content, mode });
+        Ok(())
+    }
+}
+
+fn node_kind(node: &Node) -> Kind {
+    match node {
+        Node::File { .. } => Kind::File,
+        Node::Directory => Kind::Directory,
+        Node::Symlink(_) => Kind::Symlink,
+        Node::Missing => unreachable!("missing paths are reported as not found"),
+    }
+}
+
+#[cfg(unix)]
+fn disk_mode(metadata: &fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o7777
+}
+
+#[cfg(not(unix))]
+fn disk_mode(_metadata: &fs::Metadata) -> u32 {
+    NEW_FILE_MODE
+}
+
+/// The output directory for one action: the disk itself, or a dry run's overlay of it.
+/// Handlers make every decision through it, so a dry run decides exactly as a real run would.
+pub(crate) struct Workspace<'a> {
+    overlay: Option<&'a mut Overlay>,
+}
+
+impl<'a> Workspace<'a> {
+    pub(crate) fn new(overlay: Option<&'a mut Overlay>) -> Self {
+        Workspace { overlay }
+    }
+
+    /// What `path` is, without following a final link. Fails like `fs::symlink_metadata`.
+    pub(crate) fn symlink_kind(&self, path: &Path) -> io::Result<Kind> {
+        match &self.overlay {
+            Some(overlay) => overlay.symlink_kind(path),
+            None => fs::symlink_metadata(path).map(|metadata| Kind::of(metadata.file_type())),
+        }
+    }
+
+    /// What `path` is, following links. Fails like `fs::metadata`.
+    pub(crate) fn kind(&self, path: &Path) -> io::Result<Kind> {
+        match &self.overlay {
+            Some(overlay) => overlay.kind(path),
+            None => fs::metadata(path).map(|metadata| Kind::of(metadata.file_type())),
+        }
+    }
+
+    pub(crate) fn exists(&self, path: &Path) -> bool {
+        self.kind(path).is_ok()
+    }
+
+    pub(crate) fn is_dir(&self, path: &Path) -> bool {
+        self.kind(path).is_ok_and(|kind| kind == Kind::Directory)
+    }
+
+    pub(crate) fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        match &self.overlay {
+            Some(overlay) => overlay.read(path),
+            None => fs::read(path),
+        }
+    }
+
+    pub(crate) fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        String::from_utf8(self.read(path)?).map_err(|_| {
+            io::Error::new(ErrorKind::InvalidData, "stream did not contain valid UTF-8")
+        })
+    }
+
+    pub(crate) fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        match &self.overlay {
+            Some(overlay) => overlay.read_link(path),
+            None => fs::read_link(path),
+        }
+    }
+
+    /// Permission bits of a file (following links).
+    pub(crate) fn mode(&self, path: &Path) -> io::Result<u32> {
+        match &self.overlay {
+            Some(overlay) => overlay.mode(path),
+            None => fs::metadata(path).map(|metadata| disk_mode(&metadata)),
+        }
+    }
+
+    /// Entries of a directory with their kinds (links not followed), sorted by name.
+    pub(crate) fn read_dir(&self, path: &Path) -> io::Result<Vec<(OsString, Kind)>> {
+        match &self.overlay {
+            Some(overlay) => overlay.read_dir(path),
+            None => {
+                let mut entries = fs::read_dir(path)?
+                    .map(|entry| {
+                        let entry = entry?;
+                        Ok((entry.file_name(), Kind::of(entry.file_type()?)))
+                    })
+                    .collect::<io::Result<Vec<_>>>()?;
+                entries.sort_by(|a, b| a.0.cmp(&b.0));
+                Ok(entries)
+            }
+        }
+    }
+
+    /// Resolves `path` like `Path::canonicalize`; a path that only exists in the overlay
+    /// is resolved lexically.
+    pub(crate) fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        match &self.overlay {
+            Some(overlay) => {
+                overlay.kind(path)?;
+                path.canonicalize().or_else(|_| Ok(normalize(path)))
+            }
+            None => path.canonicalize(),
+        }
+    }
+
+    pub(crate) fn write(&mut self, path: &Path, bytes: &[u8]) -> io::Result<()> {
+        match &mut self.overlay {
+            Some(overlay) => overlay.write(path, bytes),
+            None => fs::write(path, bytes),
+        }
+    }
+
+    /// Appends to an existing file.
+    pub(crate) fn append(&mut self, path: &Path, bytes: &[u8]) -> io::Result<()> {
+        match &mut self.overlay {
+            Some(overlay) => {
+                let mut content = overlay.read(path)?;
+                content.extend_from_slice(bytes);
+                overlay.write(path, &content)
+            }
+            None => fs::OpenOptions::new()
+                .append(true)
+                .open(path)?
+                .write_all(bytes),
+        }
+    }
+
+    pub(crate) fn remove_file(&mut self, path: &Path) -> io::Result<()> {
+        match &mut self.overlay {
+            Some(overlay) => overlay.remove_file(path),
+            None => fs::remove_file(path),
+        }
+    }
+
+    pub(crate) fn create_dir(&mut self, path: &Path) -> io::Result<()> {
+        match &mut self.overlay {
+            Some(overlay) => overlay.create_dir(path),
+            None => fs::create_dir(path),
+        }
+    }
+
+    pub(crate) fn create_dir_all(&mut self, path: &Path) -> io::Result<()> {
+        match &mut self.overlay {
+            Some(overlay) => overlay.create_dir_all(path),
+            None => fs::create_dir_all(path),
+        }
+    }
+
+    pub(crate) fn remove_dir_all(&mut self, path: &Path) -> io::Result<()> {
+        match &mut self.overlay {
+            Some(overlay) => overlay.remove_dir_all(path),
+            None => fs::remove_dir_all(path),
+        }
+    }
+
+    pub(crate) fn rename(&mut self, source: &Path, dest: &Path) -> io::Result<()> {
+        match &mut self.overlay {
+            Some(overlay) => overlay.rename(source, dest),
+            None => fs::rename(source, dest),
+        }
+    }
+
+    pub(crate) fn copy(&mut self, source: &Path, dest: &Path) -> io::Result<()> {
+        match &mut self.overlay {
+            Some(overlay) => overlay.copy(source, dest),
+            None => fs::copy(source, dest).map(|_| ()),
+        }
+    }
+
+    pub(crate) fn symlink(&mut self, target: &Path, link: &Path) -> io::Result<()> {
+        match &mut self.overlay {
+            Some(overlay) => overlay.symlink(target, link),
+            None => create_symlink(target, link),
+        }
+    }
+
+    pub(crate) fn set_mode(&mut self, path: &Path, mode: u32) -> io::Result<()> {
+        match &mut self.overlay {
+            Some(overlay) => overlay.set_mode(path, mode),
+            None => set_disk_mode(path, mode),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn set_disk_mode(path: &Path, mode: u32) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+}
+
+#[cfg(not(unix))]
+fn set_disk_mode(_path: &Path, _mode: u32) -> io::Result<()> {
+    Ok(())
+}
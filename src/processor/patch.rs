@@ -0,0 +1,462 @@
+//! Handles the 'patch' file action: unified diff hunks applied to a file.
+//!
+//! Hunks are applied in order. Each one is looked for at the line its `@@ -l,s +l,s @@`
+//! header names (shifted by the hunks applied before it) and, failing that, at the nearest
+//! line where its context and removed lines match. With a fuzz factor of N, up to N leading
+//! and trailing context lines may be ignored when no exact match exists. If any hunk fails,
+//! nothing is written.
+
+use crate::core_types::{Action, Content, PatchStatus, ProcessOptions};
+use crate::errors::ProcessError;
+use crate::processor::create::ensure_parent_directory; // Re-use for new files
+use crate::processor::overlay::Workspace;
+use crate::reporter::{Event, Reporter};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+/// `@@ -12,3 +12,4 @@ optional section text`; the line numbers may be left out (`@@ @@`).
+static HUNK_HEADER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^@@(?: -(?P<old>\d+)(?:,\d+)? \+\d+(?:,\d+)?)? @@")
+        .expect("Failed to compile HUNK_HEADER_REGEX")
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineKind {
+    Context,
+    Remove,
+    Add,
+}
+
+#[derive(Debug)]
+struct Hunk {
+    old_start: Option<usize>, // 1-based line from the header, if it had one
+    lines: Vec<(LineKind, String)>, // Without line endings
+    old_no_eol: bool,         // `\ No newline at end of file` after an old-side line
+    new_no_eol: bool,         // ... after a new-side line
+}
+
+impl Hunk {
+    /// Lines the hunk expects to find (context and removed lines).
+    fn old_lines(&self) -> Vec<&str> {
+        self.side(LineKind::Remove)
+    }
+
+    /// Lines the hunk leaves in their place (context and added lines).
+    fn new_lines(&self) -> Vec<&str> {
+        self.side(LineKind::Add)
+    }
+
+    fn side(&self, changed: LineKind) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter(|(kind, _)| *kind == LineKind::Context || *kind == changed)
+            .map(|(_, text)| text.as_str())
+            .collect()
+    }
+
+    /// Number of context lines before the first and after the last change.
+    fn context_margins(&self) -> (usize, usize) {
+        let is_context = |(kind, _): &&(LineKind, String)| *kind == LineKind::Context;
+        let leading = self.lines.iter().take_while(is_context).count();
+        let trailing = self.lines.iter().rev().take_while(is_context).count();
+        (leading, trailing)
+    }
+}
+
+/// Where (or whether) a hunk applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HunkResult {
+    Applied {
+        line: usize,   // 1-based line of the first matched line
+        offset: isize, // Distance from the expected line
+        fuzz: usize,   // Context lines ignored on each side
+    },
+    Failed {
+        expected_line: Option<usize>,
+    },
+}
+
+/// Applies the unified diff in a `Patch File` block to a file. A file that does not exist
+/// is created when every hunk only adds lines (a diff against `/dev/null`).
+pub(crate) fn process_patch(
+    workspace: &mut Workspace,
+    item: &Action,
+    resolved_full_path: &Path,
+    relative_path_str: &str, // For logging
+    resolved_base: &Path,    // For ensure_parent_directory
+    options: &ProcessOptions,
+    reporter: &dyn Reporter,
+) -> Result<PatchStatus, ProcessError> {
+    let block = item
+        .content
+        .as_ref()
+        .and_then(Content::as_text)
+        .ok_or_else(|| ProcessError::Internal("Missing content for patch action".to_string()))?;
+
+    if workspace.is_dir(resolved_full_path) {
+        return Err(ProcessError::TargetIsDirectory {
+            path: resolved_full_path.to_path_buf(),
+        });
+    }
+    let hunks = parse_hunks(block, resolved_full_path)?;
+    let original = match workspace.read_to_string(resolved_full_path) {
+        Ok(text) => Some(text),
+        Err(e) if e.kind() == ErrorKind::NotFound => None,
+        Err(e) => return Err(ProcessError::Io { source: e }),
+    };
+    let base_text = base_text(original.as_deref(), &hunks)?;
+
+    let (patched, results) = apply_hunks(base_text, &hunks, options.fuzz);
+    for (index, result) in results.iter().enumerate() {
+        report_hunk(index + 1, *result, relative_path_str, reporter);
+    }
+    let Some(patched) = patched else {
+        return Err(ProcessError::PatchFailed {
+            path: resolved_full_path.to_path_buf(),
+            failed_hunks: failed_hunk_numbers(&results),
+            total_hunks: hunks.len(),
+        });
+    };
+
+    let status = if original.is_some() {
+        PatchStatus::Patched
+    } else {
+        PatchStatus::Created
+    };
+    reporter.report(&Event::Info(format!(
+        "  {} file: {} ({} hunk{})",
+        match (status, options.dry_run) {
+            (PatchStatus::Patched, true) => "Would patch",
+            (PatchStatus::Patched, false) => "Patching",
+            (PatchStatus::Created, true) => "Would create (from patch)",
+            (PatchStatus::Created, false) => "Creating (from patch)",
+        },
+        relative_path_str,
+        hunks.len(),
+        if hunks.len() == 1 { "" } else { "s" }
+    )));
+    if status == PatchStatus::Created {
+        ensure_parent_directory(
+            workspace,
+            resolved_full_path,
+            resolved_base,
+            options.dry_run,
+            reporter,
+        )?;
+    }
+    workspace
+        .write(resolved_full_path, patched.as_bytes())
+        .map_err(|e| ProcessError::Io { source: e })?;
+    Ok(status)
+}
+
+/// Parses a patch block and applies it in memory (used by diff and check).
+/// `original` is `None` when the file does not exist.
+pub(crate) fn patch_text(
+    original: Option<&str>,
+    block: &str,
+    fuzz: usize,
+    path: &Path,
+) -> Result<String, ProcessError> {
+    let hunks = parse_hunks(block, path)?;
+    let (patched, results) = apply_hunks(base_text(original, &hunks)?, &hunks, fuzz);
+    patched.ok_or_else(|| ProcessError::PatchFailed {
+        path: path.to_path_buf(),
+        failed_hunks: failed_hunk_numbers(&results),
+        total_hunks: hunks.len(),
+    })
+}
+
+/// The runs of consecutive removed and of consecutive added lines in a patch block, each
+/// joined with `\n` (used by check).
+pub(crate) fn changed_runs(
+    block: &str,
+    path: &Path,
+) -> Result<(Vec<String>, Vec<String>), ProcessError> {
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    for hunk in parse_hunks(block, path)? {
+        for run in hunk.lines.chunk_by(|(a, _), (b, _)| a == b) {
+            let joined = run
+                .iter()
+                .map(|(_, text)| text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n");
+            match run[0].0 {
+                _ if joined.is_empty() => {}
+                LineKind::Remove => removed.push(joined),
+                LineKind::Add => added.push(joined),
+                LineKind::Context => {}
+            }
+        }
+    }
+    Ok((removed, added))
+}
+
+/// The text hunks are applied to: the file, or nothing for a new file made only of additions.
+fn base_text<'a>(original: Option<&'a str>, hunks: &[Hunk]) -> Result<&'a str, ProcessError> {
+    match original {
+        Some(text) => Ok(text),
+        None if hunks.iter().all(|hunk| hunk.old_lines().is_empty()) => Ok(""),
+        None => Err(ProcessError::Io {
+            source: io::Error::new(ErrorKind::NotFound, "file to patch not found"),
+        }),
+    }
+}
+
+fn failed_hunk_numbers(results: &[HunkResult]) -> Vec<usize> {
+    results
+        .iter()
+        .enumerate()
+        .filter(|(_, result)| matches!(result, HunkResult::Failed { .. }))
+        .map(|(index, _)| index + 1)
+        .collect()
+}
+
+fn report_hunk(
+    number: usize,
+    result: HunkResult,
+    relative_path_str: &str,
+    reporter: &dyn Reporter,
+) {
+    match result {
+        HunkResult::Applied { line, offset, fuzz } => {
+            let mut details = Vec::new();
+            if offset != 0 {
+                details.push(format!(
+                    "offset {} line{}",
+                    offset,
+                    if offset.abs() == 1 { "" } else { "s" }
+                ));
+            }
+            if fuzz > 0 {
+                details.push(format!("fuzz {}", fuzz));
+            }
+            let details = if details.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", details.join(", "))
+            };
+            reporter.report(&Event::Info(format!(
+                "    Hunk #{} of '{}' applies at line {}{}",
+                number, relative_path_str, line, details
+            )));
+        }
+        HunkResult::Failed { expected_line } => {
+            reporter.report(&Event::Warning(format!(
+                "Hunk #{} of '{}' FAILED: its context was not found{}.",
+                number,
+                relative_path_str,
+                expected_line
+                    .map(|line| format!(" (expected near line {})", line))
+                    .unwrap_or_default()
+            )));
+        }
+    }
+}
+
+/// Extracts the hunks of a single-file unified diff. File headers (`---`/`+++`, `diff`,
+/// `index`) and other text outside hunks are ignored. Empty lines inside a hunk count as
+/// empty context lines, since editors often strip the leading space.
+fn parse_hunks(block: &str, path: &Path) -> Result<Vec<Hunk>, ProcessError> {
+    let invalid = |details: String| ProcessError::InvalidPatch {
+        path: path.to_path_buf(),
+        details,
+    };
+    let lines: Vec<&str> = block.lines().collect();
+    let is_file_header = |index: usize| {
+        lines[index].starts_with("--- ")
+            && lines
+                .get(index + 1)
+                .is_some_and(|next| next.starts_with("+++ "))
+    };
+
+    let mut hunks: Vec<Hunk> = Vec::new();
+    let mut current: Option<Hunk> = None;
+    let mut pending_blank_lines = 0;
+    let mut file_headers = 0;
+    for (index, line) in lines.iter().enumerate() {
+        if let Some(caps) = HUNK_HEADER_REGEX.captures(line) {
+            hunks.extend(current.take());
+            pending_blank_lines = 0;
+            current = Some(Hunk {
+                old_start: caps.name("old").and_then(|m| m.as_str().parse().ok()),
+                lines: Vec::new(),
+                old_no_eol: false,
+                new_no_eol: false,
+            });
+            continue;
+        }
+        if is_file_header(index) {
+            file_headers += 1;
+            hunks.extend(current.take());
+            continue;
+        }
+        let Some(hunk) = current.as_mut() else {
+            continue; // Text before the first hunk
+        };
+        let kind = match line.chars().next() {
+            None => {
+                pending_blank_lines += 1;
+                continue;
+            }
+            Some(' ') => LineKind::Context,
+            Some('-') => LineKind::Remove,
+            Some('+') => LineKind::Add,
+            Some('\\') => {
+                // `\ No newline at end of file` refers to the line before it
+                match hunk.lines.last() {
+                    Some((LineKind::Add, _)) => hunk.new_no_eol = true,
+                    Some((LineKind::Remove, _)) => hunk.old_no_eol = true,
+                    Some((LineKind::Context, _)) => {
+                        hunk.old_no_eol = true;
+                        hunk.new_no_eol = true;
+                    }
+                    None => {}
+                }
+                continue;
+            }
+            Some(_) => {
+                hunks.extend(current.take()); // Any other text ends the hunk
+                continue;
+            }
+        };
+        for _ in 0..pending_blank_lines {
+            hunk.lines.push((LineKind::Context, String::new()));
+        }
+        pending_blank_lines = 0;
+        hunk.lines.push((kind, line[1..].to_string()));
+    }
+    hunks.extend(current);
+
+    if file_headers > 1 {
+        return Err(invalid(format!(
+            "the block holds a diff for {} files (use a header-less diff block for several files)",
+            file_headers
+        )));
+    }
+    if hunks.is_empty() {
+        return Err(invalid("no hunks ('@@ -l,s +l,s @@') found".to_string()));
+    }
+    Ok(hunks)
+}
+
+/// Applies every hunk it can, returning the patched text (only if all of them applied)
+/// and the result of each hunk.
+fn apply_hunks(original: &str, hunks: &[Hunk], fuzz: usize) -> (Option<String>, Vec<HunkResult>) {
+    let eol = if original.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut lines: Vec<String> = original.lines().map(str::to_string).collect();
+    let mut final_newline = original.is_empty() || original.ends_with('\n');
+    let mut shift: isize = 0; // Lines added minus lines removed so far, plus the last offset
+    let mut min_pos = 0; // Hunks never overlap or go backwards
+    let mut results = Vec::new();
+
+    for hunk in hunks {
+        let old = hunk.old_lines();
+        let new = hunk.new_lines();
+        let (leading, trailing) = hunk.context_margins();
+        let expected = hunk.old_start.map(|start| {
+            let index = if old.is_empty() {
+                start
+            } else {
+                start.saturating_sub(1)
+            };
+            (index as isize + shift).max(0) as usize
+        });
+
+        let mut result = HunkResult::Failed {
+            expected_line: expected.map(|index| index + 1),
+        };
+        for fuzz_used in 0..=fuzz.min(leading.max(trailing)) {
+            let skip_start = fuzz_used.min(leading);
+            let skip_end = fuzz_used.min(trailing);
+            if skip_start + skip_end >= old.len() && !old.is_empty() {
+                break; // Nothing left to anchor the hunk
+            }
+            let pattern = &old[skip_start..old.len() - skip_end];
+            let anchor = expected.map(|index| index + skip_start);
+            let Some(pos) = find_pattern(&lines, pattern, anchor, min_pos) else {
+                continue;
+            };
+
+            let replacement: Vec<String> = new[skip_start..new.len() - skip_end]
+                .iter()
+                .map(|line| line.to_string())
+                .collect();
+            let replacement_len = replacement.len();
+            let touches_end = pos + pattern.len() == lines.len();
+            let offset = anchor.map_or(0, |anchor| pos as isize - anchor as isize);
+            lines.splice(pos..pos + pattern.len(), replacement);
+            if touches_end && skip_end == 0 {
+                if hunk.new_no_eol {
+                    final_newline = false;
+                } else if hunk.old_no_eol {
+                    final_newline = true;
+                }
+            }
+            shift += replacement_len as isize - pattern.len() as isize + offset;
+            min_pos = pos + replacement_len;
+            result = HunkResult::Applied {
+                line: pos - skip_start + 1,
+                offset,
+                fuzz: fuzz_used,
+            };
+            break;
+        }
+        results.push(result);
+    }
+
+    if results
+        .iter()
+        .any(|result| matches!(result, HunkResult::Failed { .. }))
+    {
+        return (None, results);
+    }
+    let mut patched = lines.join(eol);
+    if final_newline && !lines.is_empty() {
+        patched.push_str(eol);
+    }
+    (Some(patched), results)
+}
+
+/// Finds where `pattern` occurs at or after `min_pos`, preferring the occurrence nearest
+/// to `anchor` (or the first one when the hunk header had no line numbers).
+fn find_pattern(
+    lines: &[String],
+    pattern: &[&str],
+    anchor: Option<usize>,
+    min_pos: usize,
+) -> Option<usize> {
+    let last_start = lines.len().checked_sub(pattern.len())?;
+    if min_pos > last_start {
+        return None;
+    }
+    let matches_at = |pos: usize| {
+        lines[pos..pos + pattern.len()]
+            .iter()
+            .zip(pattern)
+            .all(|(line, expected)| line == expected)
+    };
+    let Some(anchor) = anchor else {
+        return (min_pos..=last_start).find(|&pos| matches_at(pos));
+    };
+    let anchor = anchor.clamp(min_pos, last_start);
+    for distance in 0..=last_start - min_pos {
+        if let Some(pos) = anchor.checked_sub(distance).filter(|&pos| pos >= min_pos) {
+            if matches_at(pos) {
+                return Some(pos);
+            }
+        }
+        let pos = anchor + distance;
+        if distance > 0 && pos <= last_start && matches_at(pos) {
+            return Some(pos);
+        }
+    }
+    None
+}
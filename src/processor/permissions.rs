@@ -0,0 +1,67 @@
+//! Applies Unix permissions to files written by the content actions: an explicit
+//! `mode=` fence attribute, or the executable bits for shebang scripts (`--auto-exec`).
+
+use crate::core_types::{Action, ProcessOptions};
+use crate::errors::ProcessError;
+use crate::processor::overlay::Workspace;
+use crate::reporter::{Event, Reporter};
+use std::path::Path;
+
+/// Sets the permissions of a file just written by a File, Append File or Prepend File action.
+/// An explicit mode wins; otherwise `--auto-exec` adds the executable bits (for everyone
+/// who can read the file) when its content begins with a `#!` shebang.
+pub(crate) fn apply_mode(
+    workspace: &mut Workspace,
+    item: &Action,
+    resolved_full_path: &Path,
+    relative_path_str: &str, // For logging
+    options: &ProcessOptions,
+    reporter: &dyn Reporter,
+) -> Result<(), ProcessError> {
+    let mode = match item.mode {
+        Some(mode) => mode,
+        None if options.auto_exec && begins_with_shebang(workspace, resolved_full_path)? => {
+            let current = workspace
+                .mode(resolved_full_path)
+                .map_err(|e| ProcessError::Io { source: e })?;
+            let executable = current | ((current & 0o444) >> 2);
+            if executable == current {
+                return Ok(()); // Already executable
+            }
+            executable
+        }
+        None => return Ok(()),
+    };
+
+    if !cfg!(unix) {
+        reporter.report(&Event::Warning(format!(
+            "File modes are not supported on this platform. Leaving '{}' as is.",
+            relative_path_str
+        )));
+        return Ok(());
+    }
+    reporter.report(&Event::Info(format!(
+        "  {} mode {:03o} on: {}",
+        if options.dry_run {
+            "Would set"
+        } else {
+            "Setting"
+        },
+        mode,
+        relative_path_str
+    )));
+    workspace
+        .set_mode(resolved_full_path, mode)
+        .map_err(|e| ProcessError::Io { source: e })
+}
+
+/// Whether the file (as the action leaves it) begins with `#!`.
+fn begins_with_shebang(
+    workspace: &Workspace,
+    resolved_full_path: &Path,
+) -> Result<bool, ProcessError> {
+    let content = workspace
+        .read(resolved_full_path)
+        .map_err(|e| ProcessError::Io { source: e })?;
+    Ok(content.starts_with(b"#!"))
+}
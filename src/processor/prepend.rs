@@ -1,18 +1,28 @@
 //! Handles the 'prepend' file action.
 
-use crate::core_types::{Action, PrependStatus};
+use crate::core_types::{Action, PrependStatus, ProcessOptions};
 use crate::errors::ProcessError;
 use crate::processor::create::ensure_parent_directory; // Re-use for dest parent
-use std::fs;
+use crate::processor::overlay::{Kind, Workspace};
+use crate::processor::permissions;
+use crate::reporter::{Event, Reporter};
 use std::io::ErrorKind;
 use std::path::Path;
 
 /// Prepends content to a file. If the file does not exist, it's created.
+/// With `options.idempotent`, a file that already starts with the content (possibly preceded
+/// by `following_blocks`, the document's later prepends to the file) is left as is.
+/// The file's mode is applied after writing.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn process_prepend(
+    workspace: &mut Workspace,
     item: &Action,
+    following_blocks: &[&[u8]],
     resolved_full_path: &Path,
     relative_path_str: &str, // For logging
     resolved_base: &Path,    // For ensure_parent_directory
+    options: &ProcessOptions,
+    reporter: &dyn Reporter,
 ) -> Result<PrependStatus, ProcessError> {
     let content_to_prepend = item
         .content
@@ -20,40 +30,111 @@ pub(crate) fn process_prepend(
         .ok_or_else(|| ProcessError::Internal("Missing content for prepend action".to_string()))?;
 
     // Ensure parent directory exists
-    ensure_parent_directory(resolved_full_path, resolved_base)?;
+    ensure_parent_directory(
+        workspace,
+        resolved_full_path,
+        resolved_base,
+        options.dry_run,
+        reporter,
+    )?;
 
     // Check if the target path itself exists
-    match fs::metadata(resolved_full_path) {
-        Ok(metadata) => {
-            if metadata.is_dir() {
+    let status = match workspace.kind(resolved_full_path) {
+        Ok(kind) => {
+            if kind == Kind::Directory {
                 return Err(ProcessError::TargetIsDirectoryForPrepend {
                     path: resolved_full_path.to_path_buf(),
                 });
             }
+            if options.idempotent
+                && starts_with_block(
+                    workspace,
+                    resolved_full_path,
+                    content_to_prepend.as_bytes(),
+                    following_blocks,
+                )?
+            {
+                reporter.report(&Event::Info(format!(
+                    "  Skipping prepend: '{}' already starts with this content (--idempotent).",
+                    relative_path_str
+                )));
+                return Ok(PrependStatus::SkippedAlreadyPresent);
+            }
             // File exists, read existing, prepend, then write
-            println!(
-                "  Prepending to file: {} ({} bytes)",
+            reporter.report(&Event::Info(format!(
+                "  {} file: {} ({} bytes)",
+                if options.dry_run {
+                    "Would prepend to"
+                } else {
+                    "Prepending to"
+                },
                 relative_path_str,
                 content_to_prepend.len()
-            );
-            let existing_content = fs::read_to_string(resolved_full_path)
+            )));
+            let existing_content = workspace
+                .read(resolved_full_path)
                 .map_err(|e| ProcessError::Io { source: e })?;
-            let new_content = format!("{}{}", content_to_prepend, existing_content);
-            fs::write(resolved_full_path, new_content.as_bytes())
+            let new_content = [content_to_prepend.as_bytes(), &existing_content].concat();
+            workspace
+                .write(resolved_full_path, &new_content)
                 .map_err(|e| ProcessError::Io { source: e })?;
-            Ok(PrependStatus::Prepended)
+            PrependStatus::Prepended
         }
         Err(e) if e.kind() == ErrorKind::NotFound => {
             // File does not exist, create it
-            println!(
-                "  File not found, creating and writing: {} ({} bytes)",
+            reporter.report(&Event::Info(format!(
+                "  File not found, {}: {} ({} bytes)",
+                if options.dry_run {
+                    "would create and write"
+                } else {
+                    "creating and writing"
+                },
                 relative_path_str,
                 content_to_prepend.len()
-            );
-            fs::write(resolved_full_path, content_to_prepend.as_bytes())
+            )));
+            workspace
+                .write(resolved_full_path, content_to_prepend.as_bytes())
                 .map_err(|e| ProcessError::Io { source: e })?;
-            Ok(PrependStatus::Created)
+            PrependStatus::Created
+        }
+        Err(e) => return Err(ProcessError::Io { source: e }), // Other metadata error
+    };
+    permissions::apply_mode(
+        workspace,
+        item,
+        resolved_full_path,
+        relative_path_str,
+        options,
+        reporter,
+    )?;
+    Ok(status)
+}
+
+/// Whether the file already starts with the exact bytes of `block`, possibly preceded by
+/// some of `following_blocks` (each later prepend lands in front of the earlier ones).
+fn starts_with_block(
+    workspace: &Workspace,
+    path: &Path,
+    block: &[u8],
+    following_blocks: &[&[u8]],
+) -> Result<bool, ProcessError> {
+    let existing = workspace
+        .read(path)
+        .map_err(|e| ProcessError::Io { source: e })?;
+    Ok(is_prepended(&existing, block, following_blocks))
+}
+
+/// Whether `existing` starts with the first `n` of `following_blocks` (last one first)
+/// followed by `block`, for some `n`.
+pub(crate) fn is_prepended(existing: &[u8], block: &[u8], following_blocks: &[&[u8]]) -> bool {
+    let mut head = block.to_vec();
+    for n in 0..=following_blocks.len() {
+        if n > 0 {
+            head.splice(0..0, following_blocks[n - 1].iter().copied());
+        }
+        if existing.starts_with(&head) {
+            return true;
         }
-        Err(e) => Err(ProcessError::Io { source: e }), // Other metadata error
     }
+    false
 }
@@ -0,0 +1,138 @@
+//! Builds content-level previews (unified diffs) of the changes actions would make.
+//!
+//! The changes are those recorded by the dry run's in-memory overlay of the output
+//! directory, so later actions on the same path (e.g. a `File` followed by an
+//! `Append File`) are previewed against the state left by the earlier ones.
+
+use crate::core_types::{Action, ProcessOptions, ProcessReport};
+use crate::errors::AppError;
+use crate::processor::overlay::Overlay;
+use crate::processor::run_actions;
+use crate::reporter::Reporter;
+use similar::TextDiff;
+use std::path::Path;
+
+/// The kind of change an action makes to a single file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeKind {
+    Modify,                // File is created or its content changes
+    Delete,                // File is removed
+    Rename { to: String }, // File is moved to a new relative path
+}
+
+/// A file-level change that a single action would make.
+#[derive(Debug, Clone)]
+pub struct FileChange {
+    pub action_index: usize, // Index into the previewed action list
+    pub path: String,        // Relative path (source path for renames)
+    pub kind: ChangeKind,
+    pub before: Option<String>, // Content before (for renames: the overwritten destination)
+    pub after: Option<String>,  // Content after the action (None = file absent)
+}
+
+/// Result of previewing a list of actions: the dry-run report plus per-file changes.
+#[derive(Debug, Default)]
+pub struct Preview {
+    pub report: ProcessReport,
+    pub changes: Vec<FileChange>,
+}
+
+impl FileChange {
+    /// Renders the change as a unified diff (with a rename header for moves). Binary files
+    /// are only reported as differing, as `git diff` does.
+    pub fn unified_diff(&self) -> String {
+        let is_binary = [&self.before, &self.after]
+            .into_iter()
+            .flatten()
+            .any(|content| looks_binary(content));
+        match &self.kind {
+            ChangeKind::Rename { to } => {
+                let mut out = format!("rename from {}\nrename to {}\n", self.path, to);
+                // Show the destination being replaced when the move overwrites a file
+                if let Some(overwritten) = &self.before {
+                    if is_binary {
+                        out.push_str(&format!("Binary files a/{} and b/{} differ\n", to, to));
+                        return out;
+                    }
+                    let moved = self.after.as_deref().unwrap_or("");
+                    out.push_str(&render_diff(
+                        overwritten,
+                        moved,
+                        &format!("a/{}", to),
+                        &format!("b/{}", to),
+                    ));
+                }
+                out
+            }
+            ChangeKind::Modify | ChangeKind::Delete => {
+                let old_header = match self.before {
+                    Some(_) => format!("a/{}", self.path),
+                    None => "/dev/null".to_string(),
+                };
+                let new_header = match self.after {
+                    Some(_) => format!("b/{}", self.path),
+                    None => "/dev/null".to_string(),
+                };
+                if is_binary {
+                    return format!("Binary files {} and {} differ\n", old_header, new_header);
+                }
+                render_diff(
+                    self.before.as_deref().unwrap_or(""),
+                    self.after.as_deref().unwrap_or(""),
+                    &old_header,
+                    &new_header,
+                )
+            }
+        }
+    }
+}
+
+/// Whether previewed content came from bytes that are not text: a NUL byte, or bytes that
+/// were not valid UTF-8 (decoded to replacement characters).
+fn looks_binary(content: &str) -> bool {
+    content.contains(['\0', char::REPLACEMENT_CHARACTER])
+}
+
+/// Renders a unified diff between two texts with three lines of context.
+fn render_diff(old: &str, new: &str, old_header: &str, new_header: &str) -> String {
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(old_header, new_header)
+        .to_string()
+}
+
+/// Previews the content changes of a list of actions without touching the filesystem.
+/// This is a dry run (`plan_actions`): actions that fail or are skipped produce no change.
+pub fn preview_actions(
+    base_dir: &Path,
+    actions: Vec<Action>,
+    overwrite: bool,
+    reporter: &dyn Reporter,
+) -> Result<Preview, AppError> {
+    let options = ProcessOptions {
+        overwrite,
+        ..ProcessOptions::default()
+    };
+    preview_actions_with(base_dir, actions, &options, reporter)
+}
+
+/// Previews the content changes of a list of actions with explicit options (`--force`,
+/// patch fuzz). The preview is always a dry run, whatever `options.dry_run` says.
+pub fn preview_actions_with(
+    base_dir: &Path,
+    actions: Vec<Action>,
+    options: &ProcessOptions,
+    reporter: &dyn Reporter,
+) -> Result<Preview, AppError> {
+    let options = ProcessOptions {
+        dry_run: true,
+        atomic: false,
+        journal: false,
+        ..options.clone()
+    };
+    // Statuses and diffs come from the same overlay, so they always agree
+    let (report, overlay) = run_actions(base_dir, &actions, &options, reporter)?;
+    let changes = overlay.map(Overlay::into_changes).unwrap_or_default();
+    Ok(Preview { report, changes })
+}
@@ -1,9 +1,10 @@
 //! Path safety validation logic.
 
 use crate::errors::ProcessError;
+use crate::processor::journal::RESERVED_DIR;
 use std::fs; // Use fs::metadata
 use std::io::ErrorKind; // Import io and ErrorKind
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 
 /// Checks if the target path is safely within the base directory.
 /// Canonicalizes paths for reliable comparison.
@@ -19,6 +20,11 @@ pub(crate) fn ensure_path_safe(base_dir: &Path, target_path: &Path) -> Result<()
                 parent_path: base_dir.parent().unwrap_or(base_dir).to_path_buf(),
             });
         }
+        // Only happens in dry-run mode, where the base directory is not created.
+        // Nothing exists below it, so a lexical check is sufficient.
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            return check_lexical_containment(base_dir, target_path);
+        }
         Err(e) => return Err(ProcessError::Io { source: e }),
     };
 
@@ -169,4 +175,96 @@ fn check_nonexistent_path_safety(
     }
 }
 
+/// Resolves where a symlink at `link_path` pointing to `target` would lead, for
+/// `ensure_path_safe`. A relative target starts at the link's directory, and every `..`
+/// steps out of the real (canonical) location reached so far, as the OS would when
+/// following the link, so a symlinked directory along the way cannot hide an escape.
+pub(crate) fn resolve_link_target(link_path: &Path, target: &Path) -> PathBuf {
+    let mut resolved = link_path.parent().unwrap_or(link_path).to_path_buf();
+    for component in target.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if let Ok(canonical) = resolved.canonicalize() {
+                    resolved = canonical;
+                }
+                resolved.pop();
+            }
+            // A root or prefix component replaces everything before it (absolute targets)
+            other => resolved.push(other),
+        }
+    }
+    resolved
+}
+
+/// Checks that a target (already known to be inside `resolved_base`) does not lead into the
+/// reserved `.strux/` directory, through a symlink or otherwise, so a document cannot tamper
+/// with saved undo journals.
+pub(crate) fn ensure_not_reserved(
+    resolved_base: &Path,
+    target_path: &Path,
+) -> Result<(), ProcessError> {
+    // The deepest existing ancestor says where the path really is; the rest is lexical
+    let Some((mut resolved, rest)) = target_path.ancestors().find_map(|ancestor| {
+        let rest = target_path.strip_prefix(ancestor).ok()?;
+        if ancestor == resolved_base {
+            return Some((resolved_base.to_path_buf(), rest));
+        }
+        Some((ancestor.canonicalize().ok()?, rest))
+    }) else {
+        return Ok(());
+    };
+    for component in rest.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            other => resolved.push(other),
+        }
+    }
+    if resolved.starts_with(resolved_base.join(RESERVED_DIR)) {
+        return Err(ProcessError::ReservedPath {
+            path: target_path.to_path_buf(),
+        });
+    }
+    Ok(())
+}
+
+/// Lexically resolves `.` and `..`, so paths that do not exist can be compared.
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
+}
+
+/// Checks that a target stays inside a base directory that does not exist (yet),
+/// using only its path components. No symlinks can exist below a missing base.
+fn check_lexical_containment(base_dir: &Path, target_path: &Path) -> Result<(), ProcessError> {
+    let not_safe = || ProcessError::PathNotSafe {
+        resolved_path: target_path.to_path_buf(),
+        base_path: base_dir.to_path_buf(),
+    };
+    // An absolute relative path replaces the base entirely when joined.
+    let relative = target_path.strip_prefix(base_dir).map_err(|_| not_safe())?;
+    let mut depth: usize = 0;
+    for component in relative.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir => depth = depth.checked_sub(1).ok_or_else(not_safe)?,
+            Component::RootDir | Component::Prefix(_) => return Err(not_safe()),
+        }
+    }
+    Ok(())
+}
+
 // --- REMOVED old check_nonexistent_target_safety function ---
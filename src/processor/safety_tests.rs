@@ -143,3 +143,43 @@ fn test_safety_symlink_points_outside_base() {
     // Clean up the sibling directory
     std::fs::remove_dir_all(&sibling_dir).expect("Failed to remove sibling dir");
 }
+
+#[test]
+fn test_resolve_link_target_is_relative_to_link_dir() {
+    let base = std::path::Path::new("/base");
+    let resolve = |link: &str, target: &str| {
+        super::safety::resolve_link_target(&base.join(link), std::path::Path::new(target))
+    };
+    assert_eq!(resolve("current", "releases/v1"), base.join("releases/v1"));
+    assert_eq!(resolve("bin/tool", "./../lib/tool"), base.join("lib/tool"));
+    assert_eq!(
+        resolve("bin/tool", "/usr/bin/env"),
+        std::path::Path::new("/usr/bin/env")
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_resolve_link_target_follows_symlinked_dirs_before_parent() {
+    let temp_dir = setup_temp_dir();
+    let outside = setup_temp_dir();
+    let canonical_outside = outside.path().canonicalize().unwrap();
+    outside.child("inner").create_dir_all().unwrap();
+    std::os::unix::fs::symlink(
+        canonical_outside.join("inner"),
+        temp_dir.child("jump").path(),
+    )
+    .unwrap();
+
+    // `jump/..` is the outside directory, not the base
+    let resolved = super::safety::resolve_link_target(
+        &temp_dir.path().join("link"),
+        std::path::Path::new("jump/../x"),
+    );
+    assert_eq!(resolved, canonical_outside.join("x"));
+    let canonical_base = temp_dir.path().canonicalize().unwrap();
+    assert!(matches!(
+        super::safety::ensure_path_safe(&canonical_base, &resolved),
+        Err(ProcessError::PathNotSafe { .. })
+    ));
+}
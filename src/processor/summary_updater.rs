@@ -1,10 +1,34 @@
 //! Helper functions to update the Summary struct based on processing outcomes.
 
 use crate::core_types::{
-    AppendStatus, CreateStatus, DeleteStatus, MoveStatus, PrependStatus, Summary,
+    ActionOutcome, AppendStatus, CopyStatus, CreateStatus, DeleteDirectoryStatus, DeleteStatus,
+    DirectoryStatus, EditStatus, InsertStatus, MoveDirectoryStatus, MoveStatus, PatchStatus,
+    PrependStatus, Summary, SymlinkStatus,
 }; // Import enums directly
 use crate::errors::ProcessError;
 
+/// Updates the summary counters for a single action outcome.
+pub(crate) fn update_summary(summary: &mut Summary, outcome: &ActionOutcome) {
+    match outcome {
+        ActionOutcome::Create(status) => update_summary_create(summary, *status),
+        ActionOutcome::Delete(status) => update_summary_delete(summary, *status),
+        ActionOutcome::Move(status) => update_summary_move(summary, *status),
+        ActionOutcome::Append(status) => update_summary_append(summary, *status),
+        ActionOutcome::Prepend(status) => update_summary_prepend(summary, *status),
+        ActionOutcome::CreateDirectory(status) => update_summary_create_directory(summary, *status),
+        ActionOutcome::DeleteDirectory(status) => update_summary_delete_directory(summary, *status),
+        ActionOutcome::MoveDirectory(status) => update_summary_move_directory(summary, *status),
+        ActionOutcome::Copy(status) => update_summary_copy(summary, *status),
+        ActionOutcome::Edit(status) => update_summary_edit(summary, *status),
+        ActionOutcome::Patch(status) => update_summary_patch(summary, *status),
+        ActionOutcome::Insert(status) => update_summary_insert(summary, *status),
+        ActionOutcome::Symlink(status) => update_summary_symlink(summary, *status),
+        ActionOutcome::Failed(error) => update_summary_error(summary, error),
+        // Only set after the run, when its counters are final
+        ActionOutcome::RolledBack(_) | ActionOutcome::NotRun => {}
+    }
+}
+
 pub(crate) fn update_summary_create(summary: &mut Summary, status: CreateStatus) {
     match status {
         CreateStatus::Created => summary.created += 1,
@@ -33,10 +57,50 @@ pub(crate) fn update_summary_move(summary: &mut Summary, status: MoveStatus) {
     }
 }
 
+pub(crate) fn update_summary_copy(summary: &mut Summary, status: CopyStatus) {
+    match status {
+        CopyStatus::Copied => summary.copied += 1,
+        CopyStatus::CopiedOverwritten => summary.copied_overwritten += 1,
+        CopyStatus::SkippedSourceNotFound => summary.skipped_copy_src_not_found += 1,
+        CopyStatus::SkippedSourceIsDir => summary.skipped_copy_src_is_dir += 1,
+        CopyStatus::SkippedDestinationExists => summary.skipped_copy_dst_exists += 1,
+        CopyStatus::SkippedDestinationIsDir => summary.skipped_copy_dst_isdir += 1,
+    }
+}
+
+pub(crate) fn update_summary_edit(summary: &mut Summary, status: EditStatus) {
+    match status {
+        EditStatus::Edited => summary.edited += 1,
+    }
+}
+
+pub(crate) fn update_summary_insert(summary: &mut Summary, status: InsertStatus) {
+    match status {
+        InsertStatus::Inserted => summary.inserted += 1,
+        InsertStatus::InsertedAtEnd => summary.inserted_at_end += 1,
+    }
+}
+
+pub(crate) fn update_summary_symlink(summary: &mut Summary, status: SymlinkStatus) {
+    match status {
+        SymlinkStatus::Created => summary.symlinks_created += 1,
+        SymlinkStatus::Replaced => summary.symlinks_replaced += 1,
+        SymlinkStatus::SkippedUnchanged => summary.skipped_symlink_unchanged += 1,
+    }
+}
+
+pub(crate) fn update_summary_patch(summary: &mut Summary, status: PatchStatus) {
+    match status {
+        PatchStatus::Patched => summary.patched += 1,
+        PatchStatus::Created => summary.created += 1, // Counts as a creation if new
+    }
+}
+
 pub(crate) fn update_summary_append(summary: &mut Summary, status: AppendStatus) {
     match status {
         AppendStatus::Appended => summary.appended += 1,
         AppendStatus::Created => summary.created += 1, // Counts as a creation if new
+        AppendStatus::SkippedAlreadyPresent => summary.skipped_append_present += 1,
     }
 }
 
@@ -44,15 +108,54 @@ pub(crate) fn update_summary_prepend(summary: &mut Summary, status: PrependStatu
     match status {
         PrependStatus::Prepended => summary.prepended += 1,
         PrependStatus::Created => summary.created += 1, // Counts as a creation if new
+        PrependStatus::SkippedAlreadyPresent => summary.skipped_prepend_present += 1,
     }
 }
 
-pub(crate) fn update_summary_error(summary: &mut Summary, error: ProcessError) {
+pub(crate) fn update_summary_create_directory(summary: &mut Summary, status: DirectoryStatus) {
+    match status {
+        DirectoryStatus::Created => summary.dirs_created += 1,
+        DirectoryStatus::SkippedExists => summary.skipped_dir_exists += 1,
+    }
+}
+
+pub(crate) fn update_summary_delete_directory(
+    summary: &mut Summary,
+    status: DeleteDirectoryStatus,
+) {
+    match status {
+        DeleteDirectoryStatus::Deleted => summary.dirs_deleted += 1,
+        DeleteDirectoryStatus::SkippedNotFound => summary.skipped_dir_not_found += 1,
+        DeleteDirectoryStatus::SkippedNotEmpty => summary.skipped_dir_not_empty += 1,
+        DeleteDirectoryStatus::SkippedNotADirectory => summary.skipped_dir_not_dir += 1,
+    }
+}
+
+pub(crate) fn update_summary_move_directory(summary: &mut Summary, status: MoveDirectoryStatus) {
+    match status {
+        MoveDirectoryStatus::Moved => summary.dirs_moved += 1,
+        MoveDirectoryStatus::Merged => summary.dirs_merged += 1,
+        MoveDirectoryStatus::SkippedSourceNotFound => summary.skipped_dir_move_src_not_found += 1,
+        MoveDirectoryStatus::SkippedSourceNotADirectory => {
+            summary.skipped_dir_move_src_not_dir += 1
+        }
+        MoveDirectoryStatus::SkippedDestinationExists => summary.skipped_dir_move_dst_exists += 1,
+        MoveDirectoryStatus::SkippedDestinationIsFile => summary.skipped_dir_move_dst_is_file += 1,
+        MoveDirectoryStatus::SkippedDestinationInsideSource => {
+            summary.skipped_dir_move_into_self += 1
+        }
+        MoveDirectoryStatus::SkippedMergeConflict => summary.skipped_dir_merge_conflict += 1,
+    }
+}
+
+pub(crate) fn update_summary_error(summary: &mut Summary, error: &ProcessError) {
     match error {
         ProcessError::Io { .. } | ProcessError::PathResolution { .. } => {
             summary.failed_io += 1;
         }
-        ProcessError::PathNotSafe { .. } | ProcessError::InvalidPathFormat { .. } => {
+        ProcessError::PathNotSafe { .. }
+        | ProcessError::ReservedPath { .. }
+        | ProcessError::InvalidPathFormat { .. } => {
             summary.failed_unsafe += 1;
         }
         ProcessError::TargetIsDirectory { .. } => {
@@ -77,7 +180,36 @@ pub(crate) fn update_summary_error(summary: &mut Summary, error: ProcessError) {
         ProcessError::TargetIsDirectoryForPrepend { .. } => {
             summary.failed_isdir_prepend += 1;
         }
-        ProcessError::UnknownAction | ProcessError::Internal(_) => {
+        ProcessError::TargetIsFile { .. } => {
+            summary.failed_dir_target_is_file += 1;
+        }
+        ProcessError::EditSearchMismatch { matches: 0, .. } => {
+            summary.failed_edit_no_match += 1;
+        }
+        ProcessError::EditSearchMismatch { .. } => {
+            summary.failed_edit_ambiguous += 1;
+        }
+        ProcessError::InvalidEditBlock { .. } => {
+            summary.failed_edit_invalid += 1;
+        }
+        ProcessError::PatchFailed { .. } => {
+            summary.failed_patch += 1;
+        }
+        ProcessError::InvalidPatch { .. } => {
+            summary.failed_patch_invalid += 1;
+        }
+        ProcessError::AnchorNotFound { .. } => {
+            summary.failed_insert_no_anchor += 1;
+        }
+        ProcessError::InvalidAnchor { .. } => {
+            summary.failed_insert_invalid_anchor += 1;
+        }
+        ProcessError::SymlinkExists { .. } | ProcessError::SymlinkPathOccupied { .. } => {
+            summary.failed_symlink_exists += 1;
+        }
+        ProcessError::UnresolvedInclude { .. }
+        | ProcessError::UnknownAction
+        | ProcessError::Internal(_) => {
             summary.error_other += 1;
         }
     }
@@ -64,7 +64,7 @@ fn test_update_summary_error() {
     summary = empty_summary();
     update_summary_error(
         &mut summary,
-        ProcessError::Io {
+        &ProcessError::Io {
             source: io::Error::new(io::ErrorKind::NotFound, "test"),
         },
     );
@@ -73,7 +73,7 @@ fn test_update_summary_error() {
     summary = empty_summary();
     update_summary_error(
         &mut summary,
-        ProcessError::PathResolution {
+        &ProcessError::PathResolution {
             path: PathBuf::new(),
             details: "".into(),
         },
@@ -83,7 +83,7 @@ fn test_update_summary_error() {
     summary = empty_summary();
     update_summary_error(
         &mut summary,
-        ProcessError::PathNotSafe {
+        &ProcessError::PathNotSafe {
             resolved_path: PathBuf::new(),
             base_path: PathBuf::new(),
         },
@@ -93,14 +93,14 @@ fn test_update_summary_error() {
     summary = empty_summary();
     update_summary_error(
         &mut summary,
-        ProcessError::InvalidPathFormat { path: "".into() },
+        &ProcessError::InvalidPathFormat { path: "".into() },
     );
     assert_eq!(summary.failed_unsafe, 1);
 
     summary = empty_summary();
     update_summary_error(
         &mut summary,
-        ProcessError::TargetIsDirectory {
+        &ProcessError::TargetIsDirectory {
             path: PathBuf::new(),
         },
     );
@@ -109,7 +109,7 @@ fn test_update_summary_error() {
     summary = empty_summary();
     update_summary_error(
         &mut summary,
-        ProcessError::ParentIsNotDirectory {
+        &ProcessError::ParentIsNotDirectory {
             path: PathBuf::new(),
             parent_path: PathBuf::new(),
         },
@@ -119,20 +119,20 @@ fn test_update_summary_error() {
     summary = empty_summary();
     update_summary_error(
         &mut summary,
-        ProcessError::MoveSourceIsDir {
+        &ProcessError::MoveSourceIsDir {
             path: PathBuf::new(),
         },
     );
     assert_eq!(summary.skipped_move_src_is_dir, 1); // Check specific mapping
 
     summary = empty_summary();
-    update_summary_error(&mut summary, ProcessError::UnknownAction);
+    update_summary_error(&mut summary, &ProcessError::UnknownAction);
     assert_eq!(summary.error_other, 1);
 
     summary = empty_summary();
     update_summary_error(
         &mut summary,
-        ProcessError::Internal("internal error".into()),
+        &ProcessError::Internal("internal error".into()),
     );
     assert_eq!(summary.error_other, 1);
 
@@ -141,3 +141,39 @@ fn test_update_summary_error() {
     assert_eq!(summary.deleted, 0);
     assert_eq!(summary.moved, 0);
 }
+
+#[test]
+fn test_outcome_label_matches_incremented_counter() {
+    use crate::core_types::{ActionOutcome, AppendStatus, PrependStatus};
+    let outcomes = vec![
+        ActionOutcome::Create(CreateStatus::Created),
+        ActionOutcome::Create(CreateStatus::Overwritten),
+        ActionOutcome::Create(CreateStatus::SkippedExists),
+        ActionOutcome::Delete(DeleteStatus::Deleted),
+        ActionOutcome::Delete(DeleteStatus::SkippedOtherType),
+        ActionOutcome::Move(MoveStatus::MovedOverwritten),
+        ActionOutcome::Move(MoveStatus::SkippedDestinationIsDir),
+        ActionOutcome::Append(AppendStatus::Appended),
+        ActionOutcome::Append(AppendStatus::Created),
+        ActionOutcome::Prepend(PrependStatus::Prepended),
+        ActionOutcome::Failed(ProcessError::InvalidPathFormat {
+            path: "a//b".to_string(),
+        }),
+        ActionOutcome::Failed(ProcessError::ParentIsNotDirectory {
+            path: PathBuf::from("a/b"),
+            parent_path: PathBuf::from("a"),
+        }),
+        ActionOutcome::Failed(ProcessError::Internal("boom".to_string())),
+    ];
+    for outcome in &outcomes {
+        let mut summary = empty_summary();
+        update_summary(&mut summary, outcome);
+        let incremented: Vec<_> = summary
+            .counters()
+            .iter()
+            .filter(|(_, count)| *count == 1)
+            .map(|(name, _)| *name)
+            .collect();
+        assert_eq!(incremented, vec![outcome.label()], "{:?}", outcome);
+    }
+}
@@ -0,0 +1,111 @@
+//! Handles the 'symlink' action.
+
+use crate::core_types::{ProcessOptions, SymlinkStatus};
+use crate::errors::ProcessError;
+use crate::processor::create::ensure_parent_directory; // Re-use for the link's parent
+use crate::processor::overlay::{Kind, Workspace};
+use crate::reporter::{Event, Reporter};
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+/// Creates a symbolic link at `resolved_link_path` pointing to `target` (kept exactly as
+/// written, so relative targets stay relative to the link). An existing link to another
+/// target is only replaced with `options.overwrite`; files and directories never are.
+pub(crate) fn process_symlink(
+    workspace: &mut Workspace,
+    resolved_link_path: &Path,
+    target: &str,
+    relative_link_str: &str, // For logging
+    resolved_base: &Path,    // For ensure_parent_directory
+    options: &ProcessOptions,
+    reporter: &dyn Reporter,
+) -> Result<SymlinkStatus, ProcessError> {
+    ensure_parent_directory(
+        workspace,
+        resolved_link_path,
+        resolved_base,
+        options.dry_run,
+        reporter,
+    )?;
+
+    let status = match workspace.symlink_kind(resolved_link_path) {
+        Ok(Kind::Symlink) => {
+            let existing = workspace
+                .read_link(resolved_link_path)
+                .map_err(|e| ProcessError::Io { source: e })?;
+            if existing == Path::new(target) {
+                reporter.report(&Event::Info(format!(
+                    "  Skipping symlink: '{}' already points to '{}'",
+                    relative_link_str, target
+                )));
+                return Ok(SymlinkStatus::SkippedUnchanged);
+            }
+            if !options.overwrite {
+                return Err(ProcessError::SymlinkExists {
+                    path: resolved_link_path.to_path_buf(),
+                    target: existing,
+                });
+            }
+            SymlinkStatus::Replaced
+        }
+        Ok(kind) => {
+            return Err(ProcessError::SymlinkPathOccupied {
+                path: resolved_link_path.to_path_buf(),
+                kind: if kind == Kind::Directory {
+                    "directory"
+                } else {
+                    "file"
+                },
+            });
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => SymlinkStatus::Created,
+        Err(e) => return Err(ProcessError::Io { source: e }),
+    };
+
+    reporter.report(&Event::Info(format!(
+        "  {} symlink: '{}' -> '{}'",
+        match (options.dry_run, status) {
+            (true, SymlinkStatus::Replaced) => "Would replace",
+            (true, _) => "Would create",
+            (false, SymlinkStatus::Replaced) => "Replacing",
+            (false, _) => "Creating",
+        },
+        relative_link_str,
+        target
+    )));
+    if status == SymlinkStatus::Replaced {
+        workspace
+            .remove_file(resolved_link_path)
+            .map_err(|e| ProcessError::Io { source: e })?;
+    }
+    workspace
+        .symlink(Path::new(target), resolved_link_path)
+        .map_err(|e| ProcessError::Io { source: e })?;
+    Ok(status)
+}
+
+/// Creates a symbolic link at `link` pointing to `target` (also used to roll links back).
+#[cfg(unix)]
+pub(crate) fn create_symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+/// Creates a symbolic link at `link` pointing to `target` (also used to roll links back).
+/// Windows distinguishes file and directory links; a missing target gets a file link.
+#[cfg(windows)]
+pub(crate) fn create_symlink(target: &Path, link: &Path) -> io::Result<()> {
+    let resolved = link.parent().unwrap_or(link).join(target);
+    if resolved.is_dir() {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(crate) fn create_symlink(_target: &Path, _link: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        ErrorKind::Unsupported,
+        "symbolic links are not supported on this platform",
+    ))
+}
@@ -0,0 +1,212 @@
+//! Reverts the most recent run recorded in an output directory's undo journal.
+
+use crate::errors::AppError;
+use crate::errors::ProcessError;
+use crate::processor::journal::{
+    fingerprint, restore, PriorState, JOURNAL_DIR, JOURNAL_VERSION, RESERVED_DIR,
+};
+use crate::processor::safety;
+use crate::reporter::{Event, Reporter};
+use serde_json::Value;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Result of `undo_last_run`.
+#[derive(Debug)]
+pub struct UndoReport {
+    pub journal_dir: PathBuf, // The journal that was replayed
+    pub restored: usize,      // Paths put back into their pre-run state
+    pub failed: usize,        // Paths that could not be restored (the journal is kept)
+}
+
+/// One journal entry, ready to be checked and replayed.
+struct UndoEntry {
+    relative: String,
+    path: PathBuf,
+    before: Value,
+    after: Value,
+}
+
+/// Replays the newest journal under `base_dir` backwards, restoring every path it recorded.
+/// Refuses (without touching anything) if any recorded path changed since that run.
+/// A fully replayed journal is deleted, so the next call reverts the run before it.
+pub fn undo_last_run(base_dir: &Path, reporter: &dyn Reporter) -> Result<UndoReport, AppError> {
+    let resolved_base = base_dir.canonicalize().map_err(|e| {
+        AppError::Undo(format!(
+            "Cannot open output directory '{}': {}",
+            base_dir.display(),
+            e
+        ))
+    })?;
+    let journal_dir = latest_journal_dir(&resolved_base)?;
+    reporter.report(&Event::Info(format!(
+        "Replaying undo journal: {}",
+        journal_dir.display()
+    )));
+    let entries = read_entries(&journal_dir, &resolved_base)?;
+
+    // --- Refuse if anything changed since the run ---
+    let mut changed = Vec::new();
+    for entry in &entries {
+        if fingerprint(&entry.path)? != entry.after {
+            changed.push(entry.relative.as_str());
+        }
+    }
+    if !changed.is_empty() {
+        return Err(AppError::Undo(format!(
+            "Refusing to undo: {} path(s) changed since the run: {}",
+            changed.len(),
+            changed.join(", ")
+        )));
+    }
+
+    // --- Restore, newest entry first ---
+    let mut report = UndoReport {
+        journal_dir,
+        restored: 0,
+        failed: 0,
+    };
+    for entry in entries.iter().rev() {
+        let prior = match entry.before["state"].as_str() {
+            Some("file") => {
+                let blob = entry.before["blob"].as_str().unwrap_or_default();
+                PriorState::File {
+                    content: fs::read(report.journal_dir.join("blobs").join(blob))?,
+                    mode: entry.before["mode"]
+                        .as_u64()
+                        .and_then(|mode| u32::try_from(mode).ok()),
+                }
+            }
+            Some("directory") => PriorState::Directory,
+            Some("symlink") => PriorState::Symlink(PathBuf::from(
+                entry.before["target"].as_str().unwrap_or_default(),
+            )),
+            _ => PriorState::Missing,
+        };
+        // Earlier restores may have put links back; never follow one out of the base
+        let restored = check_restore_path(&resolved_base, &entry.path, &prior)
+            .and_then(|()| restore(&entry.path, &prior).map_err(ProcessError::from));
+        match restored {
+            Ok(()) => {
+                reporter.report(&Event::Info(format!("Restored '{}'", entry.relative)));
+                report.restored += 1;
+            }
+            Err(e) => {
+                reporter.report(&Event::Error(format!(
+                    "Could not restore '{}': {}",
+                    entry.relative, e
+                )));
+                report.failed += 1;
+            }
+        }
+    }
+
+    // --- Drop the replayed journal (and the journal directories once empty) ---
+    if report.failed == 0 {
+        fs::remove_dir_all(&report.journal_dir)?;
+        let journals_root = resolved_base.join(JOURNAL_DIR);
+        for dir in journals_root.ancestors().take(2) {
+            if fs::remove_dir(dir).is_err() {
+                break; // Still holds older journals or other files
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Checks that restoring `prior` at `path` only writes inside the base, and that a restored
+/// link points inside it too.
+fn check_restore_path(
+    resolved_base: &Path,
+    path: &Path,
+    prior: &PriorState,
+) -> Result<(), ProcessError> {
+    safety::ensure_path_safe(resolved_base, path)?;
+    if let PriorState::Symlink(target) = prior {
+        safety::ensure_path_safe(resolved_base, &safety::resolve_link_target(path, target))?;
+    }
+    Ok(())
+}
+
+/// Finds the most recent journal (journal directories are named by creation time in millis).
+fn latest_journal_dir(resolved_base: &Path) -> Result<PathBuf, AppError> {
+    let journals_root = resolved_base.join(JOURNAL_DIR);
+    let latest = fs::read_dir(&journals_root)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let millis = entry.file_name().to_str()?.parse::<u128>().ok()?;
+            Some((millis, entry.path()))
+        })
+        .max_by_key(|(millis, _)| *millis);
+    match latest {
+        // A linked journal directory could replay files from anywhere
+        Some((_, journal_dir))
+            if journal_dir.canonicalize().ok().as_ref() != Some(&journal_dir) =>
+        {
+            Err(AppError::Undo(format!(
+                "Refusing to undo: journal '{}' is not a plain directory",
+                journal_dir.display()
+            )))
+        }
+        Some((_, journal_dir)) => Ok(journal_dir),
+        None => Err(AppError::Undo(format!(
+            "No undo journal found in '{}'",
+            resolved_base.display()
+        ))),
+    }
+}
+
+/// Reads and validates `journal.json`.
+fn read_entries(journal_dir: &Path, resolved_base: &Path) -> Result<Vec<UndoEntry>, AppError> {
+    let manifest_path = journal_dir.join("journal.json");
+    let invalid = |details: &str| {
+        AppError::Undo(format!(
+            "Invalid journal '{}': {}",
+            manifest_path.display(),
+            details
+        ))
+    };
+    let manifest: Value = serde_json::from_str(&fs::read_to_string(&manifest_path)?)
+        .map_err(|e| invalid(&e.to_string()))?;
+    if manifest["version"].as_u64() != Some(JOURNAL_VERSION) {
+        return Err(invalid("unsupported version"));
+    }
+
+    let mut entries = Vec::new();
+    for entry in manifest["entries"].as_array().into_iter().flatten() {
+        let relative = entry["path"]
+            .as_str()
+            .ok_or_else(|| invalid("entry without a path"))?;
+        // Never let a tampered journal reach outside the output directory
+        let is_plain_relative = Path::new(relative)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+        let is_reserved = Path::new(relative).starts_with(RESERVED_DIR);
+        if relative.is_empty() || !is_plain_relative || is_reserved {
+            return Err(invalid(&format!("unsafe path '{}'", relative)));
+        }
+        // Prior contents may only come from this journal's own numbered blobs
+        if entry["before"]["state"].as_str() == Some("file") {
+            let blob = entry["before"]["blob"].as_str().unwrap_or_default();
+            let is_numbered = !blob.is_empty() && blob.bytes().all(|byte| byte.is_ascii_digit());
+            let is_blob_file = is_numbered
+                && fs::symlink_metadata(journal_dir.join("blobs").join(blob))
+                    .is_ok_and(|metadata| metadata.is_file());
+            if !is_blob_file {
+                return Err(invalid(&format!(
+                    "invalid blob '{}' for '{}'",
+                    blob, relative
+                )));
+            }
+        }
+        entries.push(UndoEntry {
+            relative: relative.to_string(),
+            path: resolved_base.join(relative),
+            before: entry["before"].clone(),
+            after: entry["after"].clone(),
+        });
+    }
+    Ok(entries)
+}
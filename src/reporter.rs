@@ -0,0 +1,202 @@
+//! Defines the diagnostics sink used by the parser and processor.
+//!
+//! The library never prints directly. Every progress message, association decision,
+//! warning and error is sent as an `Event` to a `Reporter`, which decides what (if
+//! anything) to show. `ConsoleReporter` prints events up to a chosen `Verbosity` and
+//! `NullReporter` discards everything.
+
+use crate::core_types::{Action, ActionOutcome};
+
+/// A diagnostic event emitted while parsing markdown or processing actions.
+#[derive(Debug)]
+pub enum Event<'a> {
+    /// A parsing or processing stage begins (e.g. "Step 1: Locating code blocks...").
+    Stage(&'a str),
+    /// A fenced code block was located (positions are byte offsets in the original markdown).
+    BlockFound {
+        fence: &'a str,
+        lang: &'a str,
+        start: usize,
+        end: usize,
+    },
+    /// A header was associated with content and produced an action.
+    HeaderAssociated {
+        source: &'a str, // "external", "wrapped", "internal" or "linked"
+        action: &'a Action,
+        original_pos: usize,
+    },
+    /// The processor is about to apply an action.
+    ActionStarted {
+        index: usize, // Zero-based
+        total: usize,
+        action: &'a Action,
+    },
+    /// The processor finished an action (or, in a dry run, decided what it would do).
+    ActionOutcome {
+        index: usize, // Zero-based
+        total: usize,
+        action: &'a Action,
+        outcome: &'a ActionOutcome,
+    },
+    /// Progress detail, e.g. a directory being created.
+    Info(String),
+    /// An association decision made by the parser (why a header or block was used or ignored).
+    Decision(String),
+    /// Low-level trace output (e.g. fence matching).
+    Trace(String),
+    /// Something was skipped or looks wrong, but processing continues.
+    Warning(String),
+    /// An operation failed.
+    Error(String),
+}
+
+/// How much diagnostic output is shown, from least to most.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Verbosity {
+    Quiet, // Errors only (the CLI still prints the final summary)
+    #[default]
+    Normal, // Warnings plus one line per action
+    Verbose, // Parser association decisions and handler progress
+    Debug, // Everything, including pass banners and fence-matching traces
+}
+
+impl Event<'_> {
+    /// The lowest verbosity at which this event is shown.
+    pub fn verbosity(&self) -> Verbosity {
+        match self {
+            Event::Error(_) => Verbosity::Quiet,
+            Event::Warning(_) | Event::ActionOutcome { .. } => Verbosity::Normal,
+            Event::BlockFound { .. }
+            | Event::HeaderAssociated { .. }
+            | Event::ActionStarted { .. }
+            | Event::Info(_)
+            | Event::Decision(_) => Verbosity::Verbose,
+            Event::Stage(_) | Event::Trace(_) => Verbosity::Debug,
+        }
+    }
+}
+
+/// Receives diagnostic events from the parser and processor.
+pub trait Reporter {
+    fn report(&self, event: &Event);
+}
+
+/// Discards every event. Useful when embedding the library.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NullReporter;
+
+impl Reporter for NullReporter {
+    fn report(&self, _event: &Event) {}
+}
+
+/// Prints events up to its verbosity to the console.
+/// Warnings and errors go to stderr, everything else to stdout (or also stderr, see `stderr_only`).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ConsoleReporter {
+    verbosity: Verbosity,
+    stderr_only: bool, // Keep stdout free, e.g. for a machine-readable report
+}
+
+impl ConsoleReporter {
+    /// A console reporter at the default (`Normal`) verbosity.
+    pub fn new() -> Self {
+        ConsoleReporter::default()
+    }
+
+    pub fn with_verbosity(verbosity: Verbosity) -> Self {
+        ConsoleReporter {
+            verbosity,
+            ..ConsoleReporter::default()
+        }
+    }
+
+    /// Writes every event to stderr instead of splitting between stdout and stderr.
+    pub fn stderr_only(mut self) -> Self {
+        self.stderr_only = true;
+        self
+    }
+
+    fn print(&self, line: &str) {
+        if self.stderr_only {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
+    }
+}
+
+impl Reporter for ConsoleReporter {
+    fn report(&self, event: &Event) {
+        if event.verbosity() > self.verbosity {
+            return;
+        }
+        match event {
+            Event::Stage(title) => self.print(&format!("\n{}", title)),
+            Event::BlockFound {
+                fence,
+                lang,
+                start,
+                end,
+            } => self.print(&format!(
+                "  - Found code block: '{}' (lang: '{}') from original pos {} to {}",
+                fence,
+                if lang.is_empty() { "none" } else { lang },
+                start,
+                end
+            )),
+            Event::HeaderAssociated {
+                source,
+                action,
+                original_pos,
+            } => self.print(&format!(
+                "    -> Adding {:?} action for '{}' from source '{}' with original_pos {}",
+                action.action_type, action.path, source, original_pos
+            )),
+            Event::ActionStarted {
+                index,
+                total,
+                action,
+            } => match &action.dest_path {
+                Some(dest_path) => self.print(&format!(
+                    "\n[{}/{}] Action: {:?}, From: '{}', To: '{}'",
+                    index + 1,
+                    total,
+                    action.action_type,
+                    action.path,
+                    dest_path
+                )),
+                None => self.print(&format!(
+                    "\n[{}/{}] Action: {:?}, Path: '{}'",
+                    index + 1,
+                    total,
+                    action.action_type,
+                    action.path
+                )),
+            },
+            Event::ActionOutcome {
+                index,
+                total,
+                action,
+                outcome,
+            } => {
+                let target = match &action.dest_path {
+                    Some(dest_path) => format!("{} -> {}", action.path, dest_path),
+                    None => action.path.clone(),
+                };
+                self.print(&format!(
+                    "  [{}/{}] {:?} '{}': {}",
+                    index + 1,
+                    total,
+                    action.action_type,
+                    target,
+                    outcome
+                ))
+            }
+            Event::Info(message) | Event::Decision(message) | Event::Trace(message) => {
+                self.print(message)
+            }
+            Event::Warning(message) => eprintln!("Warning: {}", message),
+            Event::Error(message) => eprintln!("Error: {}", message),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Per-document settings declared in front matter (see `parse_document_with`).
+//!
+//! Front matter only supplies defaults: the command line overrides every setting. The
+//! policies that act on parsed actions (line endings, allowed action types) live here so
+//! library users can apply them the same way the CLI does.
+
+use crate::core_types::{Action, ActionType, Content};
+use crate::errors::ParseError;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Line endings of the text written by File, Append File and Prepend File actions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEndings {
+    /// Write block content exactly as it appears in the document.
+    #[default]
+    Keep,
+    /// Convert every line ending to `\n`.
+    Lf,
+    /// Convert every line ending to `\r\n`.
+    Crlf,
+}
+
+impl LineEndings {
+    /// The policy with the given name (`keep`, `lf` or `crlf`).
+    pub fn from_name(name: &str) -> Option<LineEndings> {
+        match name {
+            "keep" => Some(LineEndings::Keep),
+            "lf" => Some(LineEndings::Lf),
+            "crlf" => Some(LineEndings::Crlf),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for LineEndings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LineEndings::Keep => "keep",
+            LineEndings::Lf => "lf",
+            LineEndings::Crlf => "crlf",
+        })
+    }
+}
+
+/// Settings a document declares in a leading `---` (YAML) or `+++` (TOML) block.
+/// Unset fields leave the command line's defaults in place.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FrontMatter {
+    pub output_dir: Option<String>,
+    pub force: Option<bool>,
+    pub variables: BTreeMap<String, String>,
+    pub line_endings: Option<LineEndings>,
+    pub allowed_actions: Option<Vec<ActionType>>, // None = every action type
+}
+
+impl FrontMatter {
+    /// Combines the front matter of several inputs: settings of `later` win, variables are
+    /// merged.
+    pub fn merge(&mut self, later: FrontMatter) {
+        if later.output_dir.is_some() {
+            self.output_dir = later.output_dir;
+        }
+        if later.force.is_some() {
+            self.force = later.force;
+        }
+        self.variables.extend(later.variables);
+        if later.line_endings.is_some() {
+            self.line_endings = later.line_endings;
+        }
+        if later.allowed_actions.is_some() {
+            self.allowed_actions = later.allowed_actions;
+        }
+    }
+}
+
+/// Converts the line endings of the text written by File, Append File and Prepend File
+/// actions. Binary content and the hunks of Edit/Patch blocks are never touched.
+pub fn apply_line_endings(actions: &mut [Action], policy: LineEndings) {
+    if policy == LineEndings::Keep {
+        return;
+    }
+    for action in actions.iter_mut().filter(|action| {
+        matches!(
+            action.action_type,
+            ActionType::Create | ActionType::Append | ActionType::Prepend
+        )
+    }) {
+        if let Some(Content::Text(text)) = &mut action.content {
+            let lf = text.replace("\r\n", "\n");
+            *text = match policy {
+                LineEndings::Crlf => lf.replace('\n', "\r\n"),
+                _ => lf,
+            };
+        }
+    }
+}
+
+/// Fails on the first action whose type is not in `allowed`.
+pub fn check_allowed_actions(actions: &[Action], allowed: &[ActionType]) -> Result<(), ParseError> {
+    match actions
+        .iter()
+        .find(|action| !allowed.contains(&action.action_type))
+    {
+        Some(action) => Err(ParseError::ActionNotAllowed {
+            action: action.action_type.label(),
+            path: action.path.clone(),
+            pos: action.original_pos,
+        }),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,114 @@
+//! Template variables: substitutes `{{key}}` placeholders in parsed actions (`--var`).
+//!
+//! Substitution runs on the actions returned by the parser, before they are processed, so
+//! substituted paths still go through every path validation and safety check. It covers
+//! paths, link targets, anchors and text content (binary content is left alone). `\{{`
+//! writes a literal `{{`, also when no variables are defined and placeholders are kept.
+
+use crate::core_types::{Action, Content};
+use crate::errors::ParseError;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use std::collections::BTreeMap;
+
+/// Variable names: a letter or underscore, then letters, digits, `_`, `-` or `.`.
+const VARIABLE_NAME_PATTERN: &str = "[A-Za-z_][A-Za-z0-9_.-]*";
+
+/// An escaped `\{{`, or a `{{ name }}` placeholder (whitespace inside the braces is allowed).
+static PLACEHOLDER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        "\\\\\\{{\\{{|\\{{\\{{\\s*(?P<name>{})\\s*\\}}\\}}",
+        VARIABLE_NAME_PATTERN
+    ))
+    .expect("Failed to compile PLACEHOLDER_REGEX")
+});
+
+static VARIABLE_NAME_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!("^{}$", VARIABLE_NAME_PATTERN))
+        .expect("Failed to compile VARIABLE_NAME_REGEX")
+});
+
+/// Parses a `key=value` assignment (a `--var` argument or a line of a vars file).
+pub fn parse_variable_assignment(assignment: &str) -> Result<(String, String), String> {
+    let (name, value) = assignment
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{}'", assignment))?;
+    let name = name.trim();
+    if !is_valid_variable_name(name) {
+        return Err(format!(
+            "invalid variable name '{}' (use letters, digits, '_', '-' and '.', starting with a letter or '_')",
+            name
+        ));
+    }
+    Ok((name.to_string(), value.trim().to_string()))
+}
+
+/// Whether `name` can be used as a variable name.
+pub(crate) fn is_valid_variable_name(name: &str) -> bool {
+    VARIABLE_NAME_REGEX.is_match(name)
+}
+
+/// Replaces every placeholder in the actions' paths, link targets, anchors and text content.
+/// A placeholder naming an undefined variable fails with the action's `original_pos`.
+pub fn substitute_variables(
+    actions: &mut [Action],
+    variables: &BTreeMap<String, String>,
+) -> Result<(), ParseError> {
+    rewrite(actions, Some(variables))
+}
+
+/// Turns every `\{{` back into `{{` and leaves placeholders as written. Used instead of
+/// `substitute_variables` when no variables are defined, so `{{ ... }}` stays ordinary text.
+pub fn unescape_placeholders(actions: &mut [Action]) {
+    rewrite(actions, None).expect("Placeholders are kept without variables")
+}
+
+/// Rewrites the actions' paths, link targets, anchors and text content. Without
+/// `variables`, only escapes are processed.
+fn rewrite(
+    actions: &mut [Action],
+    variables: Option<&BTreeMap<String, String>>,
+) -> Result<(), ParseError> {
+    for action in actions {
+        let pos = action.original_pos;
+        action.path = substitute(&action.path, variables, pos)?;
+        if let Some(dest_path) = &action.dest_path {
+            action.dest_path = Some(substitute(dest_path, variables, pos)?);
+        }
+        if let Some(anchor) = &action.anchor {
+            action.anchor = Some(substitute(anchor, variables, pos)?);
+        }
+        if let Some(Content::Text(text)) = &action.content {
+            action.content = Some(Content::Text(substitute(text, variables, pos)?));
+        }
+    }
+    Ok(())
+}
+
+/// Substitutes the placeholders of one string.
+fn substitute(
+    text: &str,
+    variables: Option<&BTreeMap<String, String>>,
+    pos: usize,
+) -> Result<String, ParseError> {
+    let mut unknown = None;
+    let substituted = PLACEHOLDER_REGEX.replace_all(text, |caps: &Captures| {
+        let Some(name) = caps.name("name") else {
+            return "{{".to_string(); // Escaped braces
+        };
+        let Some(variables) = variables else {
+            return caps[0].to_string(); // Nothing to substitute
+        };
+        match variables.get(name.as_str()) {
+            Some(value) => value.clone(),
+            None => {
+                unknown.get_or_insert_with(|| name.as_str().to_string());
+                String::new()
+            }
+        }
+    });
+    match unknown {
+        Some(name) => Err(ParseError::UnknownVariable { name, pos }),
+        None => Ok(substituted.into_owned()),
+    }
+}
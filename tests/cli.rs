@@ -7,20 +7,62 @@ mod common;
 // Declare the specific test modules (tests/cli/*.rs)
 #[path = "cli/append_prepend.rs"] // ADDED
 mod append_prepend;
+#[path = "cli/atomic.rs"]
+mod atomic;
 #[path = "cli/basic.rs"]
 mod basic;
+#[path = "cli/check.rs"]
+mod check;
+#[path = "cli/conditionals.rs"]
+mod conditionals;
+#[path = "cli/copy_file.rs"]
+mod copy_file;
 #[path = "cli/create.rs"]
 mod create;
 #[path = "cli/delete.rs"]
 mod delete;
+#[path = "cli/diff.rs"]
+mod diff;
+#[path = "cli/directory.rs"]
+mod directory;
+#[path = "cli/dry_run.rs"]
+mod dry_run;
+#[path = "cli/edit_file.rs"]
+mod edit_file;
 #[path = "cli/empty_input.rs"]
 mod empty_input;
 #[path = "cli/errors.rs"]
 mod errors;
+#[path = "cli/fences.rs"]
+mod fences;
+#[path = "cli/file_mode.rs"]
+mod file_mode;
+#[path = "cli/front_matter.rs"]
+mod front_matter;
+#[path = "cli/include.rs"]
+mod include;
+#[path = "cli/insert.rs"]
+mod insert;
+#[path = "cli/json_report.rs"]
+mod json_report;
 #[path = "cli/move_file.rs"]
 mod move_file;
+#[path = "cli/multiple_inputs.rs"]
+mod multiple_inputs;
 #[path = "cli/overwrite_skip.rs"]
 mod overwrite_skip;
+#[path = "cli/pack.rs"]
+mod pack;
+#[path = "cli/patch_file.rs"]
+mod patch_file;
+#[path = "cli/symlink.rs"]
+mod symlink;
+#[path = "cli/undo.rs"]
+mod undo;
+#[path = "cli/variables.rs"]
+mod variables;
+#[path = "cli/verbosity.rs"]
+mod verbosity;
 
 // Declare the top-level common module (tests/test_common.rs)
 // This makes helpers like setup_temp_dir available via crate::test_common::*
@@ -146,3 +146,39 @@ fn test_cli_append_target_is_dir() {
         "Failed (append, target is dir):     1",
     ));
 }
+
+#[test]
+fn test_cli_idempotent_rerun_does_not_duplicate() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path
+        .write_str("\n## Append File: log.txt\n```\nentry\n```\n")
+        .unwrap();
+    let output_dir = temp_dir.child("generated");
+
+    for _ in 0..2 {
+        let mut cmd = get_cmd();
+        cmd.arg(md_path.path())
+            .arg("-o")
+            .arg(output_dir.path())
+            .arg("--idempotent");
+        cmd.assert().success();
+    }
+    output_dir.child("log.txt").assert("entry\n");
+
+    let mut again = get_cmd();
+    again
+        .arg(md_path.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--idempotent");
+    again.assert().success().stdout(predicate::str::contains(
+        "Skipped (append, already present):  1",
+    ));
+
+    // Without the flag the block is appended again
+    let mut plain = get_cmd();
+    plain.arg(md_path.path()).arg("-o").arg(output_dir.path());
+    plain.assert().success();
+    output_dir.child("log.txt").assert("entry\nentry\n");
+}
@@ -0,0 +1,71 @@
+//! CLI tests for the --atomic flag.
+
+use super::common::get_cmd;
+use crate::test_common::setup_temp_dir;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn test_cli_atomic_rolls_back_and_fails() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path
+        .write_str("\n## File: a.txt\n```\nA\n```\n\n## File: blocker/b.txt\n```\nB\n```\n")
+        .unwrap();
+    let output_dir = temp_dir.child("generated");
+    output_dir.create_dir_all().unwrap();
+    output_dir.child("blocker").write_str("a file").unwrap();
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--atomic");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "all changes were rolled back (--atomic)",
+        ))
+        .stdout(predicate::str::contains("Processing Summary:"));
+    output_dir.child("a.txt").assert(predicate::path::missing());
+}
+
+#[test]
+fn test_cli_atomic_json_report_lists_every_action() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path
+        .write_str("## File: a.txt\n```\nA\n```\n\n## File: blocker/b.txt\n```\nB\n```\n\n## File: c.txt\n```\nC\n```\n")
+        .unwrap();
+    let output_dir = temp_dir.child("generated");
+    output_dir.create_dir_all().unwrap();
+    output_dir.child("blocker").write_str("a file").unwrap();
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--atomic")
+        .arg("--format")
+        .arg("json");
+
+    let output = cmd.assert().failure().get_output().stdout.clone();
+    let report: serde_json::Value =
+        serde_json::from_slice(&output).expect("stdout is not a single JSON document");
+
+    assert_eq!(report["rolled_back"], true);
+    let actions = report["actions"].as_array().unwrap();
+    assert_eq!(actions.len(), 3);
+    assert_eq!(actions[0]["outcome"], "rolled_back");
+    assert!(actions[1]["outcome"]
+        .as_str()
+        .unwrap()
+        .starts_with("failed_"));
+    assert!(actions[1]["error"].is_string());
+    assert_eq!(actions[2]["outcome"], "not_run");
+    assert_eq!(actions[2]["error"], serde_json::Value::Null);
+    output_dir.child("a.txt").assert(predicate::path::missing());
+    output_dir.child("c.txt").assert(predicate::path::missing());
+}
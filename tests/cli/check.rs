@@ -0,0 +1,69 @@
+//! CLI tests for the `check` subcommand.
+
+use super::common::get_cmd;
+use crate::test_common::setup_temp_dir;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn test_cli_check_passes_after_apply() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path
+        .write_str("\n## File: a.txt\n```\nA\n```\n\n## Deleted File: old.txt\n")
+        .unwrap();
+    let output_dir = temp_dir.child("generated");
+
+    get_cmd()
+        .arg(md_path.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .assert()
+        .success();
+
+    let mut check = get_cmd();
+    check
+        .arg("check")
+        .arg(md_path.path())
+        .arg("-o")
+        .arg(output_dir.path());
+    check
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ok    a.txt"))
+        .stdout(predicate::str::contains(
+            "Check passed: all 2 path(s) match",
+        ));
+}
+
+#[test]
+fn test_cli_check_fails_on_drift_without_changing_anything() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path
+        .write_str("\n## File: a.txt\n```\nA\n```\n\n## File: b.txt\n```\nB\n```\n")
+        .unwrap();
+    let output_dir = temp_dir.child("generated");
+    output_dir.child("a.txt").write_str("edited\n").unwrap();
+
+    let mut check = get_cmd();
+    check
+        .arg("check")
+        .arg(md_path.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--quiet");
+    check
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("FAIL  a.txt (content differs)"))
+        .stdout(predicate::str::contains("FAIL  b.txt (missing)"))
+        .stdout(predicate::str::contains("Check failed: 2 of 2 path(s)"));
+
+    output_dir.child("a.txt").assert("edited\n");
+    output_dir.child("b.txt").assert(predicate::path::missing());
+    output_dir
+        .child(".strux")
+        .assert(predicate::path::missing());
+}
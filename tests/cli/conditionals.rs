@@ -0,0 +1,53 @@
+//! CLI tests for conditional regions (`--enable`).
+
+use super::common::get_cmd;
+use crate::test_common::setup_temp_dir;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+const DOC: &str = "## File: app.txt\n```\napp\n```\n\n<!-- strux:if docker -->\n## File: Dockerfile\n```\nFROM scratch\n```\n<!-- strux:else -->\n## File: run.sh\n```\n./app\n```\n<!-- strux:endif -->\n<!-- strux:if ci -->\n## File: ci.yml\n```\nci\n```\n<!-- strux:endif -->\n";
+
+#[test]
+fn test_cli_enable_selects_conditional_regions() {
+    let temp_dir = setup_temp_dir();
+    let md = temp_dir.child("doc.md");
+    md.write_str(DOC).unwrap();
+
+    let plain = temp_dir.child("plain");
+    let mut cmd = get_cmd();
+    cmd.arg(md.path()).arg("-o").arg(plain.path());
+    cmd.assert().success();
+    plain.child("app.txt").assert("app\n");
+    plain.child("run.sh").assert("./app\n");
+    plain.child("Dockerfile").assert(predicate::path::missing());
+    plain.child("ci.yml").assert(predicate::path::missing());
+
+    let enabled = temp_dir.child("enabled");
+    let mut cmd = get_cmd();
+    cmd.arg(md.path())
+        .arg("-o")
+        .arg(enabled.path())
+        .arg("--enable")
+        .arg("docker,ci");
+    cmd.assert().success();
+    enabled.child("Dockerfile").assert("FROM scratch\n");
+    enabled.child("ci.yml").assert("ci\n");
+    enabled.child("run.sh").assert(predicate::path::missing());
+}
+
+#[test]
+fn test_cli_unclosed_conditional_fails() {
+    let temp_dir = setup_temp_dir();
+    let md = temp_dir.child("doc.md");
+    md.write_str("# Doc\n<!-- strux:if docker -->\n## File: a.txt\n```\nA\n```\n")
+        .unwrap();
+    let output_dir = temp_dir.child("out");
+
+    let mut cmd = get_cmd();
+    cmd.arg(md.path()).arg("-o").arg(output_dir.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("opened at byte(s) 6 never closed"));
+    output_dir.assert(predicate::path::missing());
+}
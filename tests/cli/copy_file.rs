@@ -0,0 +1,25 @@
+//! CLI tests for the 'Copied File' action.
+
+use super::common::get_cmd;
+use crate::test_common::setup_temp_dir;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn test_cli_copied_file() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path
+        .write_str("\n## File: a.txt\n```\nhello\n```\n\n## Copied File: a.txt to copies/b.txt\n")
+        .unwrap();
+    let output_dir = temp_dir.child("generated");
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path()).arg("-o").arg(output_dir.path());
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Files copied:                       1",
+    ));
+    output_dir.child("a.txt").assert("hello\n");
+    output_dir.child("copies/b.txt").assert("hello\n");
+}
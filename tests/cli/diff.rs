@@ -0,0 +1,62 @@
+//! CLI tests for the --diff flag.
+
+use super::common::get_cmd;
+use crate::test_common::setup_temp_dir;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn test_cli_diff_prints_unified_diff_without_changes() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path
+        .write_str("## File: config.txt\n```\nmode = fast\n```\n")
+        .unwrap();
+    let output_dir = temp_dir.child("generated");
+    output_dir.create_dir_all().unwrap();
+    output_dir
+        .child("config.txt")
+        .write_str("mode = slow\n")
+        .unwrap();
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--diff")
+        .arg("--force");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("--- a/config.txt"))
+        .stdout(predicate::str::contains("+++ b/config.txt"))
+        .stdout(predicate::str::contains("-mode = slow"))
+        .stdout(predicate::str::contains("+mode = fast"))
+        .stdout(predicate::str::contains(
+            "Dry-Run Summary (no changes were made):",
+        ));
+
+    output_dir.child("config.txt").assert("mode = slow\n");
+}
+
+#[test]
+fn test_cli_diff_reports_no_changes() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path
+        .write_str("## File: keep.txt\n```\nnew\n```\n")
+        .unwrap();
+    temp_dir.child("keep.txt").write_str("old\n").unwrap();
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path())
+        .arg("-o")
+        .arg(temp_dir.path())
+        .arg("--diff");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No file contents would change."));
+    temp_dir.child("keep.txt").assert("old\n");
+}
@@ -0,0 +1,43 @@
+//! CLI tests for the 'Directory' and 'Deleted Directory' actions.
+
+use super::common::get_cmd;
+use crate::test_common::setup_temp_dir;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn test_cli_directory_actions() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path
+        .write_str("\n## Directory: logs\n\n## Deleted Directory: build\n")
+        .unwrap();
+    let output_dir = temp_dir.child("generated");
+    output_dir.child("build/app.o").write_str("obj\n").unwrap();
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path()).arg("-o").arg(output_dir.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Directories created:                1",
+        ))
+        .stdout(predicate::str::contains(
+            "Skipped (dir delete, not empty):    1",
+        ))
+        .stderr(predicate::str::contains("use --force to delete it"));
+    output_dir.child("logs").assert(predicate::path::is_dir());
+    output_dir.child("build/app.o").assert("obj\n");
+
+    let mut forced = get_cmd();
+    forced
+        .arg(md_path.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--force");
+    forced.assert().success().stdout(predicate::str::contains(
+        "Directories deleted:                1",
+    ));
+    output_dir.child("build").assert(predicate::path::missing());
+}
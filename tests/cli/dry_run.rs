@@ -0,0 +1,61 @@
+//! CLI tests for the --dry-run flag.
+
+use super::common::get_cmd;
+use crate::test_common::setup_temp_dir;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn test_cli_dry_run_makes_no_changes() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path
+        .write_str("\n## File: src/main.rs\n```\nfn main() {}\n```\n\n## Deleted File: old.txt\n")
+        .unwrap();
+    let output_dir = temp_dir.child("generated");
+    output_dir.create_dir_all().unwrap();
+    output_dir.child("old.txt").write_str("keep me").unwrap();
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--dry-run");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Planned Actions (dry run):"))
+        .stdout(predicate::str::contains(
+            "[1/2] Create 'src/main.rs': Created",
+        ))
+        .stdout(predicate::str::contains("[2/2] Delete 'old.txt': Deleted"))
+        .stdout(predicate::str::contains(
+            "Dry-Run Summary (no changes were made):",
+        ))
+        .stdout(predicate::str::contains(
+            "Files created:                      1",
+        ));
+
+    output_dir.child("src").assert(predicate::path::missing());
+    output_dir.child("old.txt").assert("keep me");
+}
+
+#[test]
+fn test_cli_dry_run_does_not_create_output_dir() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path
+        .write_str("## File: test.txt\n```\ncontent\n```")
+        .unwrap();
+    let output_dir = temp_dir.child("missing_output");
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--dry-run");
+
+    cmd.assert().success();
+    output_dir.assert(predicate::path::missing());
+}
@@ -0,0 +1,40 @@
+//! CLI tests for the 'Edit File' action.
+
+use super::common::get_cmd;
+use crate::test_common::setup_temp_dir;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn test_cli_edit_file() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path
+        .write_str(
+            "\n## Edit File: app.cfg\n```\n<<<<<<< SEARCH\nport = 80\n=======\nport = 8080\n>>>>>>> REPLACE\n```\n",
+        )
+        .unwrap();
+    let output_dir = temp_dir.child("generated");
+    output_dir
+        .child("app.cfg")
+        .write_str("host = a\nport = 80\n")
+        .unwrap();
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path()).arg("-o").arg(output_dir.path());
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Files edited:                       1",
+    ));
+    output_dir
+        .child("app.cfg")
+        .assert("host = a\nport = 8080\n");
+
+    // Applying the same edit again cannot find the search text anymore
+    let mut again = get_cmd();
+    again.arg(md_path.path()).arg("-o").arg(output_dir.path());
+    // The run itself completes; the failed edit is reported and counted
+    again.assert().success().stderr(predicate::str::contains(
+        "the search text of hunk 1 was not found",
+    ));
+}
@@ -0,0 +1,32 @@
+//! CLI tests for tilde fences and `--indented-blocks`.
+
+use super::common::get_cmd;
+use crate::test_common::setup_temp_dir;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn test_cli_tilde_fences_and_indented_blocks() {
+    let temp_dir = setup_temp_dir();
+    let md = temp_dir.child("doc.md");
+    md.write_str("## File: a.txt\n~~~\nA\n~~~\n\n## File: b.txt\n\n    B\n")
+        .unwrap();
+
+    let plain = temp_dir.child("plain");
+    let mut cmd = get_cmd();
+    cmd.arg(md.path()).arg("-o").arg(plain.path());
+    cmd.assert().success();
+    plain.child("a.txt").assert("A\n");
+    plain.child("b.txt").assert(predicate::path::missing());
+
+    let indented = temp_dir.child("indented");
+    let mut cmd = get_cmd();
+    cmd.arg(md.path())
+        .arg("-o")
+        .arg(indented.path())
+        .arg("--indented-blocks");
+    cmd.assert().success();
+    indented.child("a.txt").assert("A\n");
+    indented.child("b.txt").assert("B\n");
+}
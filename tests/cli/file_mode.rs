@@ -0,0 +1,39 @@
+//! CLI tests for file modes (`mode=` fence attribute) and `--auto-exec`.
+#![cfg(unix)]
+
+use super::common::get_cmd;
+use crate::test_common::setup_temp_dir;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use std::os::unix::fs::PermissionsExt;
+
+#[test]
+fn test_cli_mode_attribute_and_auto_exec() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path
+        .write_str("## File: bin/deploy\n```bash mode=700\n#!/bin/bash\n```\n\n## File: bin/hello.sh\n```bash\n#!/bin/sh\necho hello\n```\n")
+        .unwrap();
+    let output_dir = temp_dir.child("generated");
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--auto-exec")
+        .arg("-v");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Setting mode 700 on: bin/deploy"));
+
+    let mode_of = |relative: &str| {
+        std::fs::metadata(output_dir.child(relative).path())
+            .unwrap()
+            .permissions()
+            .mode()
+            & 0o777
+    };
+    assert_eq!(mode_of("bin/deploy"), 0o700);
+    assert_ne!(mode_of("bin/hello.sh") & 0o100, 0);
+}
@@ -0,0 +1,107 @@
+//! CLI tests for front-matter settings and their command-line overrides.
+
+use super::common::get_cmd;
+use crate::test_common::setup_temp_dir;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn test_cli_front_matter_supplies_defaults() {
+    let temp_dir = setup_temp_dir();
+    let output_dir = temp_dir.child("site");
+    let md_file = temp_dir.child("doc.md");
+    md_file
+        .write_str(&format!(
+            "---\noutput_dir: {}\nforce: true\nline_endings: crlf\nvariables:\n  name: demo\n---\n## File: {{{{name}}}}.txt\n```\none\ntwo\n```\n",
+            output_dir.path().display()
+        ))
+        .unwrap();
+    output_dir.create_dir_all().unwrap();
+    output_dir.child("demo.txt").write_str("old\n").unwrap();
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_file.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Configuration:"))
+        .stdout(predicate::str::contains(
+            "Force:            yes (front matter)",
+        ))
+        .stdout(predicate::str::contains(
+            "Line endings:     crlf (front matter)",
+        ))
+        .stdout(predicate::str::contains("Variables:        name"));
+
+    // force: true overwrote the file, with CRLF line endings
+    output_dir.child("demo.txt").assert("one\r\ntwo\r\n");
+
+    // `check` reads the same settings
+    let mut check = get_cmd();
+    check.arg("check").arg(md_file.path());
+    check.assert().success();
+}
+
+#[test]
+fn test_cli_flags_override_front_matter() {
+    let temp_dir = setup_temp_dir();
+    let md_file = temp_dir.child("doc.md");
+    md_file
+        .write_str("---\noutput_dir: ignored\nforce: true\nline_endings: crlf\nvariables:\n  name: demo\n---\n## File: {{name}}.txt\n```\nnew\n```\n")
+        .unwrap();
+    let output_dir = temp_dir.child("out");
+    output_dir.create_dir_all().unwrap();
+    output_dir.child("cli.txt").write_str("old\n").unwrap();
+
+    let mut cmd = get_cmd();
+    cmd.current_dir(temp_dir.path())
+        .arg(md_file.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--no-force")
+        .arg("--line-endings")
+        .arg("keep")
+        .arg("--var")
+        .arg("name=cli");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Force:            no (command line)",
+    ));
+
+    output_dir.child("cli.txt").assert("old\n");
+    temp_dir.child("ignored").assert(predicate::path::missing());
+}
+
+#[test]
+fn test_cli_allowed_actions_reject_other_types() {
+    let temp_dir = setup_temp_dir();
+    let md_file = temp_dir.child("doc.md");
+    md_file
+        .write_str("---\nallowed_actions: [create]\n---\n## File: a.txt\n```\nA\n```\n\n## Deleted File: b.txt\n")
+        .unwrap();
+    let output_dir = temp_dir.child("out");
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_file.path()).arg("-o").arg(output_dir.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Action 'delete' for 'b.txt'"));
+    output_dir.assert(predicate::path::missing());
+
+    // The command line can widen (or narrow) the list
+    let mut cmd = get_cmd();
+    cmd.arg(md_file.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--allow-actions")
+        .arg("create,delete");
+    cmd.assert().success();
+    output_dir.child("a.txt").assert("A\n");
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_file.path())
+        .arg("--allow-actions")
+        .arg("create,explode");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("expected one of: create, delete"));
+}
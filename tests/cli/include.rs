@@ -0,0 +1,75 @@
+//! CLI tests for `## Include:` documents.
+
+use super::common::get_cmd;
+use crate::test_common::setup_temp_dir;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn test_cli_include_composes_documents() {
+    let temp_dir = setup_temp_dir();
+    let main = temp_dir.child("docs/main.md");
+    main.write_str("## File: a.txt\n```\nA\n```\n\n## Include: parts/api.md\n\n## Append File: a.txt\n```\nend\n```\n")
+        .unwrap();
+    temp_dir
+        .child("docs/parts/api.md")
+        .write_str(
+            "## File: {{name}}.txt\n```\nAPI\n```\n\n## Append File: a.txt\n```\nfrom api\n```\n",
+        )
+        .unwrap();
+    let output_dir = temp_dir.child("out");
+
+    let mut cmd = get_cmd();
+    cmd.arg(main.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--var")
+        .arg("name=api");
+    cmd.assert().success();
+
+    output_dir.child("api.txt").assert("API\n");
+    // The included append runs at the directive's position
+    output_dir.child("a.txt").assert("A\nfrom api\nend\n");
+
+    let mut check = get_cmd();
+    check
+        .arg("check")
+        .arg(main.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--var")
+        .arg("name=api");
+    check.assert().success();
+}
+
+#[test]
+fn test_cli_include_errors_name_the_included_file() {
+    let temp_dir = setup_temp_dir();
+    let main = temp_dir.child("main.md");
+    main.write_str("## Include: part.md\n").unwrap();
+    temp_dir
+        .child("part.md")
+        .write_str("## File: a.txt\n```\n{{missing}}\n```\n")
+        .unwrap();
+    let output_dir = temp_dir.child("out");
+
+    let mut cmd = get_cmd();
+    cmd.arg(main.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--var")
+        .arg("other=x");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("part.md'"))
+        .stderr(predicate::str::contains("Unknown variable '{{missing}}'"));
+    output_dir.assert(predicate::path::missing());
+
+    main.write_str("# Doc\n\n## Include: nowhere.md\n").unwrap();
+    let mut cmd = get_cmd();
+    cmd.arg(main.path()).arg("-o").arg(output_dir.path());
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "nowhere.md', included at byte 7 of",
+    ));
+}
@@ -0,0 +1,55 @@
+//! CLI tests for the 'Insert After' and 'Insert Before' actions.
+
+use super::common::get_cmd;
+use crate::test_common::setup_temp_dir;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn test_cli_insert_after_and_force_fallback() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path
+        .write_str("\n## Insert After: src/lib.rs @ /^mod /\n```rust\nmod foo;\n```\n")
+        .unwrap();
+    let output_dir = temp_dir.child("generated");
+    output_dir
+        .child("src/lib.rs")
+        .write_str("mod a;\n\npub fn f() {}\n")
+        .unwrap();
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path()).arg("-o").arg(output_dir.path());
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Files inserted into:                1",
+    ));
+    output_dir
+        .child("src/lib.rs")
+        .assert("mod a;\nmod foo;\n\npub fn f() {}\n");
+
+    // Without a matching line the action fails, unless --force falls back to the end of file
+    output_dir
+        .child("src/lib.rs")
+        .write_str("fn g() {}\n")
+        .unwrap();
+    let mut strict = get_cmd();
+    strict.arg(md_path.path()).arg("-o").arg(output_dir.path());
+    strict.assert().success().stderr(predicate::str::contains(
+        "no line matches the anchor '/^mod /'",
+    ));
+    output_dir.child("src/lib.rs").assert("fn g() {}\n");
+
+    let mut forced = get_cmd();
+    forced
+        .arg(md_path.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--force");
+    forced.assert().success().stdout(predicate::str::contains(
+        "Files inserted into (at end):       1",
+    ));
+    output_dir
+        .child("src/lib.rs")
+        .assert("fn g() {}\nmod foo;\n");
+}
@@ -0,0 +1,104 @@
+//! CLI tests for the machine-readable report (--format json).
+
+use super::common::get_cmd;
+use crate::test_common::setup_temp_dir;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use serde_json::Value;
+
+/// Parses stdout, which must consist of the JSON report alone.
+fn report_from_stdout(stdout: &[u8]) -> Value {
+    serde_json::from_slice(stdout).expect("stdout is not a single JSON document")
+}
+
+#[test]
+fn test_cli_json_report_lists_actions_and_summary() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path
+        .write_str("## File: a.txt\n```\nA\n```\n\n## File: ../escape.txt\n```\nX\n```\n\n## Moved File: a.txt to b.txt\n")
+        .unwrap();
+    let output_dir = temp_dir.child("generated");
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--format")
+        .arg("json");
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let report = report_from_stdout(&output);
+
+    assert_eq!(report["version"], 1);
+    assert_eq!(report["dry_run"], false);
+    let actions = report["actions"].as_array().unwrap();
+    assert_eq!(actions.len(), 3);
+
+    assert_eq!(actions[0]["index"], 0);
+    assert_eq!(actions[0]["action_type"], "create");
+    assert_eq!(actions[0]["path"], "a.txt");
+    assert_eq!(actions[0]["dest_path"], Value::Null);
+    assert_eq!(actions[0]["original_pos"], 0);
+    assert_eq!(actions[0]["outcome"], "created");
+    assert_eq!(actions[0]["error"], Value::Null);
+
+    assert_eq!(actions[1]["outcome"], "failed_unsafe");
+    assert!(actions[1]["error"]
+        .as_str()
+        .unwrap()
+        .contains("Unsafe path"));
+
+    assert_eq!(actions[2]["action_type"], "move");
+    assert_eq!(actions[2]["dest_path"], "b.txt");
+    assert_eq!(actions[2]["outcome"], "moved");
+
+    assert_eq!(report["summary"]["created"], 1);
+    assert_eq!(report["summary"]["moved"], 1);
+    assert_eq!(report["summary"]["failed_unsafe"], 1);
+    assert_eq!(report["summary"]["error_other"], 0);
+    output_dir.child("b.txt").assert("A\n");
+}
+
+#[test]
+fn test_cli_json_report_dry_run_omits_human_summary() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path.write_str("## File: a.txt\n```\nA\n```\n").unwrap();
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path())
+        .arg("-o")
+        .arg(temp_dir.path())
+        .arg("--dry-run")
+        .arg("--format")
+        .arg("json");
+
+    let assert = cmd
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Processing Summary:").not())
+        .stdout(predicate::str::contains("Planned Actions (dry run):").not());
+    let report = report_from_stdout(&assert.get_output().stdout);
+    assert_eq!(report["dry_run"], true);
+    assert_eq!(report["actions"][0]["outcome"], "created");
+    temp_dir.child("a.txt").assert(predicate::path::missing());
+}
+
+#[test]
+fn test_cli_json_conflicts_with_diff() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path.write_str("## File: a.txt\n```\nA\n```\n").unwrap();
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path())
+        .arg("--diff")
+        .arg("--format")
+        .arg("json");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
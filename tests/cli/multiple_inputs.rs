@@ -0,0 +1,119 @@
+//! CLI tests for reading markdown from standard input and from several files.
+
+use super::common::get_cmd;
+use crate::test_common::setup_temp_dir;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+// `std::process::Command` cannot feed stdin; wrap it in assert_cmd's Command
+fn get_stdin_cmd() -> assert_cmd::Command {
+    assert_cmd::Command::from_std(get_cmd())
+}
+
+#[test]
+fn test_cli_reads_markdown_from_stdin() {
+    let temp_dir = setup_temp_dir();
+    let output_dir = temp_dir.child("generated");
+
+    let mut cmd = get_stdin_cmd();
+    cmd.arg("-")
+        .arg("-o")
+        .arg(output_dir.path())
+        .write_stdin("\n## File: hello.txt\n```\nHello\n```\n");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Reading markdown from standard input",
+        ))
+        .stdout(predicate::str::contains(
+            "Files created:                      1",
+        ));
+    output_dir.child("hello.txt").assert("Hello\n");
+}
+
+#[test]
+fn test_cli_merges_multiple_files_in_order() {
+    let temp_dir = setup_temp_dir();
+    let first = temp_dir.child("first.md");
+    first
+        .write_str("\n## File: notes.txt\n```\nfirst\n```\n")
+        .unwrap();
+    let second = temp_dir.child("second.md");
+    second
+        .write_str(
+            "\n## Append File: notes.txt\n```\nsecond\n```\n\n## File: other.txt\n```\nx\n```\n",
+        )
+        .unwrap();
+    let output_dir = temp_dir.child("generated");
+
+    let mut cmd = get_cmd();
+    cmd.arg(first.path())
+        .arg(second.path())
+        .arg("-o")
+        .arg(output_dir.path());
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Found 3 actions to process"))
+        .stdout(predicate::str::contains(
+            "Files created:                      2",
+        ))
+        .stdout(predicate::str::contains(
+            "Files appended:                     1",
+        ));
+    output_dir.child("notes.txt").assert("first\nsecond\n");
+    output_dir.child("other.txt").assert("x\n");
+}
+
+#[test]
+fn test_cli_errors_name_the_source_file() {
+    let temp_dir = setup_temp_dir();
+    let first = temp_dir.child("first.md");
+    first
+        .write_str("\n## File: ok.txt\n```\nok\n```\n")
+        .unwrap();
+    let output_dir = temp_dir.child("generated");
+    output_dir.create_dir_all().unwrap();
+    output_dir.child("blocker").write_str("a file").unwrap();
+
+    let mut cmd = get_stdin_cmd();
+    cmd.arg(first.path())
+        .arg("-")
+        .arg("-o")
+        .arg(output_dir.path())
+        .write_stdin("\n## File: blocker/file.txt\n```\nx\n```\n");
+
+    cmd.assert().success().stderr(predicate::str::contains(
+        "Could not process action for 'blocker/file.txt' (from <stdin>)",
+    ));
+}
+
+#[test]
+fn test_cli_stdin_only_once() {
+    let mut cmd = get_stdin_cmd();
+    cmd.arg("-").arg("-").write_stdin("");
+
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Standard input ('-') can only be given once",
+    ));
+}
+
+#[test]
+fn test_cli_json_report_includes_source_file() {
+    let temp_dir = setup_temp_dir();
+    let output_dir = temp_dir.child("generated");
+
+    let mut cmd = get_stdin_cmd();
+    cmd.arg("-")
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--format")
+        .arg("json")
+        .write_stdin("\n## File: hello.txt\n```\nHello\n```\n");
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).expect("valid JSON");
+    assert_eq!(report["actions"][0]["source_file"], "<stdin>");
+}
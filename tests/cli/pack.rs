@@ -0,0 +1,201 @@
+//! CLI tests for the `pack` subcommand, including a byte-exact round trip.
+
+use super::common::get_cmd;
+use crate::test_common::setup_temp_dir;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Reads every file below `root` as raw bytes, keyed by '/'-separated relative path.
+fn read_tree(root: &Path) -> BTreeMap<String, Vec<u8>> {
+    fn walk(dir: &Path, prefix: &str, files: &mut BTreeMap<String, Vec<u8>>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let entry = entry.unwrap();
+            let name = entry.file_name().into_string().unwrap();
+            let relative = format!("{}{}", prefix, name);
+            if entry.file_type().unwrap().is_dir() {
+                walk(&entry.path(), &format!("{}/", relative), files);
+            } else {
+                files.insert(relative, fs::read(entry.path()).unwrap());
+            }
+        }
+    }
+    let mut files = BTreeMap::new();
+    walk(root, "", &mut files);
+    files
+}
+
+fn pack(dir: &Path, extra_args: &[&str]) -> String {
+    let mut cmd = get_cmd();
+    cmd.arg("pack").arg(dir).args(extra_args);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    String::from_utf8(output).expect("pack output is UTF-8")
+}
+
+#[test]
+fn test_cli_pack_round_trip_is_byte_exact() {
+    let temp_dir = setup_temp_dir();
+    let source = temp_dir.child("source");
+    source
+        .child("src/main.rs")
+        .write_str("fn main() {\n    println!(\"hi\");\n}\n")
+        .unwrap();
+    source
+        .child("README.md")
+        .write_str("# Demo\n\n```bash\ncargo run\n```\n\n````text\n```\n````\n")
+        .unwrap();
+    source
+        .child("no_newline.txt")
+        .write_str("last line")
+        .unwrap();
+    source.child("crlf.txt").write_str("a\r\nb\r\n").unwrap();
+    source.child("empty.txt").write_str("").unwrap();
+    source
+        .child("docs/my notes.md")
+        .write_str("## File: not/a/header.txt\n")
+        .unwrap();
+    source
+        .child("unicode/ünïcödé.txt")
+        .write_str("héllo ✓\n")
+        .unwrap();
+    source
+        .child("blank_edges.txt")
+        .write_str("\n\n  indented\n\n")
+        .unwrap();
+    // Placeholder braces come back as written, with or without variables
+    source
+        .child("templates/{{name}}.hbs")
+        .write_str("<h1>{{ title }}</h1>\n\\{{ raw }}\n{{{html}}}\nX: ${{ github.ref }}\n")
+        .unwrap();
+    // Documented conditional markers are not resolved when the document is applied
+    source
+        .child("docs/conditionals.md")
+        .write_str("# Usage\n<!-- strux:if docker -->\n\\<!-- strux:endif -->\nend\n")
+        .unwrap();
+    // Undo journals are never packed
+    source
+        .child(".strux/journal/1/journal.json")
+        .write_str("{}")
+        .unwrap();
+
+    let document = pack(source.path(), &[]);
+    // README.md holds a four-backtick fence, so every block uses five
+    assert!(document.contains("## File: src/main.rs\n`````rust\n"));
+    assert!(document.contains("## File: README.md\n`````markdown\n# Demo"));
+    assert!(document.contains("## File: no_newline.txt\n`````no-eol\n"));
+    assert!(!document.contains(".strux"));
+
+    let md_path = temp_dir.child("packed.md");
+    md_path.write_str(&document).unwrap();
+    let output_dir = temp_dir.child("recreated");
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--no-journal");
+    cmd.assert().success();
+
+    let mut expected = read_tree(source.path());
+    expected.retain(|path, _| !path.starts_with(".strux/"));
+    assert_eq!(read_tree(output_dir.path()), expected);
+
+    let with_vars_dir = temp_dir.child("recreated_with_vars");
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path())
+        .arg("-o")
+        .arg(with_vars_dir.path())
+        .arg("--no-journal")
+        .arg("--var")
+        .arg("title=ignored");
+    cmd.assert().success();
+    assert_eq!(read_tree(with_vars_dir.path()), expected);
+}
+
+#[test]
+fn test_cli_pack_include_and_exclude() {
+    let temp_dir = setup_temp_dir();
+    temp_dir.child("src/lib.rs").write_str("// lib\n").unwrap();
+    temp_dir
+        .child("src/gen/out.rs")
+        .write_str("// gen\n")
+        .unwrap();
+    temp_dir.child("notes.txt").write_str("notes\n").unwrap();
+
+    let document = pack(
+        temp_dir.path(),
+        &["--include", "**/*.rs", "--exclude", "src/gen"],
+    );
+
+    assert!(document.contains("## File: src/lib.rs"));
+    assert!(!document.contains("out.rs"));
+    assert!(!document.contains("notes.txt"));
+}
+
+#[test]
+fn test_cli_pack_rejects_missing_directory() {
+    let temp_dir = setup_temp_dir();
+    let mut cmd = get_cmd();
+    cmd.arg("pack").arg(temp_dir.child("missing").path());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("not a directory"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_cli_pack_records_executable_mode() {
+    use std::os::unix::fs::PermissionsExt;
+    let temp_dir = setup_temp_dir();
+    let source = temp_dir.child("src");
+    source.child("run.sh").write_str("#!/bin/sh\n").unwrap();
+    source.child("data.txt").write_str("x\n").unwrap();
+    std::fs::set_permissions(
+        source.child("run.sh").path(),
+        std::fs::Permissions::from_mode(0o755),
+    )
+    .unwrap();
+
+    let document = pack(source.path(), &[]);
+
+    assert!(document.contains("```bash mode=755\n"), "{}", document);
+    assert!(
+        document.contains("## File: data.txt\n```\n"),
+        "{}",
+        document
+    );
+}
+
+#[test]
+fn test_cli_pack_emits_binary_files_as_base64() {
+    let temp_dir = setup_temp_dir();
+    let source = temp_dir.child("source");
+    let binary: Vec<u8> = (0..=255u8).rev().collect();
+    source
+        .child("assets/blob.bin")
+        .write_binary(&binary)
+        .unwrap();
+    source.child("text.txt").write_str("plain\n").unwrap();
+
+    let document = pack(source.path(), &[]);
+    assert!(
+        document.contains("## File: assets/blob.bin\n```base64\n"),
+        "{}",
+        document
+    );
+    assert!(document.lines().all(|line| line.len() <= 76));
+
+    let md_path = temp_dir.child("packed.md");
+    md_path.write_str(&document).unwrap();
+    let output_dir = temp_dir.child("recreated");
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--no-journal");
+    cmd.assert().success();
+
+    assert_eq!(read_tree(output_dir.path()), read_tree(source.path()));
+}
@@ -0,0 +1,72 @@
+//! CLI tests for the 'Patch File' action and header-less diff blocks.
+
+use super::common::get_cmd;
+use crate::test_common::setup_temp_dir;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn test_cli_patch_file() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path
+        .write_str("\n```diff\n--- a/app.cfg\n+++ b/app.cfg\n@@ -1,2 +1,2 @@\n host = a\n-port = 80\n+port = 8080\n```\n")
+        .unwrap();
+    let output_dir = temp_dir.child("generated");
+    output_dir
+        .child("app.cfg")
+        .write_str("host = a\nport = 80\n")
+        .unwrap();
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path()).arg("-o").arg(output_dir.path());
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Files patched:                      1",
+    ));
+    output_dir
+        .child("app.cfg")
+        .assert("host = a\nport = 8080\n");
+
+    // The patch is already applied: its removed line is gone, so the hunk is rejected
+    let mut again = get_cmd();
+    again.arg(md_path.path()).arg("-o").arg(output_dir.path());
+    again
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Hunk #1 of 'app.cfg' FAILED"));
+}
+
+#[test]
+fn test_cli_fuzz_flag() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    // The leading context line does not match the file
+    md_path
+        .write_str("\n## Patch File: list.txt\n```\n@@ -1,3 +1,3 @@\n zero\n-b\n+B\n c\n```\n")
+        .unwrap();
+    let output_dir = temp_dir.child("generated");
+    output_dir.child("list.txt").write_str("a\nb\nc\n").unwrap();
+
+    let mut strict = get_cmd();
+    strict
+        .arg(md_path.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--fuzz")
+        .arg("0");
+    strict.assert().success().stdout(predicate::str::contains(
+        "Failed (patch, hunks rejected):     1",
+    ));
+    output_dir.child("list.txt").assert("a\nb\nc\n");
+
+    let mut fuzzy = get_cmd();
+    fuzzy
+        .arg(md_path.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--fuzz")
+        .arg("1");
+    fuzzy.assert().success();
+    output_dir.child("list.txt").assert("a\nB\nc\n");
+}
@@ -0,0 +1,62 @@
+//! CLI tests for the 'Symlink' action.
+#![cfg(unix)]
+
+use super::common::get_cmd;
+use crate::test_common::setup_temp_dir;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use std::path::Path;
+
+#[test]
+fn test_cli_symlink_and_force_replace() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path
+        .write_str("## Symlink: current to releases/v2\n")
+        .unwrap();
+    let output_dir = temp_dir.child("generated");
+    output_dir.create_dir_all().unwrap();
+    std::os::unix::fs::symlink("releases/v1", output_dir.child("current").path()).unwrap();
+
+    let mut strict = get_cmd();
+    strict.arg(md_path.path()).arg("-o").arg(output_dir.path());
+    strict
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "already exists and points to 'releases/v1' (use --force to replace it)",
+        ))
+        .stdout(predicate::str::contains(
+            "Failed (symlink, path exists):      1",
+        ));
+
+    let mut forced = get_cmd();
+    forced
+        .arg(md_path.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--force");
+    forced.assert().success().stdout(predicate::str::contains(
+        "Symlinks replaced (--force):        1",
+    ));
+    assert_eq!(
+        std::fs::read_link(output_dir.child("current").path()).unwrap(),
+        Path::new("releases/v2")
+    );
+}
+
+#[test]
+fn test_cli_symlink_escape_is_rejected() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path.write_str("## Symlink: leak to ../../..\n").unwrap();
+    let output_dir = temp_dir.child("generated");
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path()).arg("-o").arg(output_dir.path());
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Unsafe path detected!"));
+    assert!(std::fs::symlink_metadata(output_dir.child("leak").path()).is_err());
+}
@@ -0,0 +1,60 @@
+//! CLI tests for the undo journal and the `undo` subcommand.
+
+use super::common::get_cmd;
+use crate::test_common::setup_temp_dir;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn test_cli_undo_reverts_last_run() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path
+        .write_str("\n## File: notes.txt\n```\nnew\n```\n")
+        .unwrap();
+    let output_dir = temp_dir.child("generated");
+    output_dir.create_dir_all().unwrap();
+    output_dir.child("notes.txt").write_str("old\n").unwrap();
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--force");
+    cmd.assert().success();
+    output_dir.child("notes.txt").assert("new\n");
+
+    let mut undo = get_cmd();
+    undo.arg("undo").arg("-o").arg(output_dir.path());
+    undo.assert().success().stdout(predicate::str::contains(
+        "Undo complete: 1 path(s) restored",
+    ));
+    output_dir.child("notes.txt").assert("old\n");
+}
+
+#[test]
+fn test_cli_no_journal_leaves_nothing_to_undo() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path
+        .write_str("\n## File: a.txt\n```\nA\n```\n")
+        .unwrap();
+    let output_dir = temp_dir.child("generated");
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--no-journal");
+    cmd.assert().success();
+    output_dir
+        .child(".strux")
+        .assert(predicate::path::missing());
+
+    let mut undo = get_cmd();
+    undo.arg("undo").arg("-o").arg(output_dir.path());
+    undo.assert()
+        .failure()
+        .stderr(predicate::str::contains("No undo journal found"));
+}
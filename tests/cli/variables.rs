@@ -0,0 +1,170 @@
+//! CLI tests for template variables (`--var`, `--vars-file`).
+
+use super::common::get_cmd;
+use crate::test_common::setup_temp_dir;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+const TEMPLATE_MD: &str =
+    "## File: {{crate}}/Cargo.toml\n```toml\n[package]\nname = \"{{crate}}\"\nauthors = [\"{{author}}\"]\n```\n";
+
+#[test]
+fn test_cli_var_overrides_vars_file() {
+    let temp_dir = setup_temp_dir();
+    let md_file = temp_dir.child("template.md");
+    md_file.write_str(TEMPLATE_MD).unwrap();
+    let vars_file = temp_dir.child("vars.env");
+    vars_file
+        .write_str("# Project settings\ncrate = from_file\n\nauthor=Ada\n")
+        .unwrap();
+    let output_dir = temp_dir.child("out");
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_file.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--vars-file")
+        .arg(vars_file.path())
+        .arg("--var")
+        .arg("crate=demo");
+    cmd.assert().success();
+
+    output_dir
+        .child("demo/Cargo.toml")
+        .assert("[package]\nname = \"demo\"\nauthors = [\"Ada\"]\n");
+    output_dir
+        .child("from_file")
+        .assert(predicate::path::missing());
+
+    // `check` substitutes the same way
+    let mut check = get_cmd();
+    check
+        .arg("check")
+        .arg(md_file.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--vars-file")
+        .arg(vars_file.path())
+        .arg("--var")
+        .arg("crate=demo");
+    check.assert().success();
+}
+
+#[test]
+fn test_cli_unknown_variable_fails_before_writing() {
+    let temp_dir = setup_temp_dir();
+    let md_file = temp_dir.child("template.md");
+    md_file.write_str(TEMPLATE_MD).unwrap();
+    let output_dir = temp_dir.child("out");
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_file.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--var")
+        .arg("crate=demo");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Unknown variable '{{author}}' in the action at byte 0",
+    ));
+    output_dir.assert(predicate::path::missing());
+}
+
+#[test]
+fn test_cli_without_variables_leaves_braces_alone() {
+    let temp_dir = setup_temp_dir();
+    let md_file = temp_dir.child("workflow.md");
+    md_file
+        .write_str("## File: ci.yml\n```yaml\ntoken: ${{ secrets.TOKEN }}\nraw: $\\{{ x }}\n```\n")
+        .unwrap();
+    let output_dir = temp_dir.child("out");
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_file.path()).arg("-o").arg(output_dir.path());
+    cmd.assert().success();
+
+    // Placeholders stay as written; only escapes are processed
+    output_dir
+        .child("ci.yml")
+        .assert("token: ${{ secrets.TOKEN }}\nraw: ${{ x }}\n");
+}
+
+#[test]
+fn test_cli_packed_workflow_round_trips_without_variables() {
+    let temp_dir = setup_temp_dir();
+    let workflow = "on: push\njobs:\n  build:\n    env:\n      X: ${{ github.ref }}\n";
+    temp_dir
+        .child("source/.github/workflows/ci.yml")
+        .write_str(workflow)
+        .unwrap();
+
+    let mut cmd = get_cmd();
+    cmd.arg("pack").arg(temp_dir.child("source").path());
+    let doc = cmd.assert().success().get_output().stdout.clone();
+    let md_file = temp_dir.child("doc.md");
+    md_file.write_binary(&doc).unwrap();
+
+    let output_dir = temp_dir.child("out");
+    let mut cmd = get_cmd();
+    cmd.arg(md_file.path()).arg("-o").arg(output_dir.path());
+    cmd.assert().success();
+
+    output_dir
+        .child(".github/workflows/ci.yml")
+        .assert(workflow);
+}
+
+#[test]
+fn test_cli_no_vars_leaves_braces_alone() {
+    let temp_dir = setup_temp_dir();
+    let md_file = temp_dir.child("workflow.md");
+    md_file
+        .write_str("## File: ci.yml\n```yaml\ntoken: ${{ secrets.TOKEN }}\nraw: \\{{\n```\n")
+        .unwrap();
+    let output_dir = temp_dir.child("out");
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_file.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("--no-vars");
+    cmd.assert().success();
+
+    output_dir
+        .child("ci.yml")
+        .assert("token: ${{ secrets.TOKEN }}\nraw: \\{{\n");
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_file.path())
+        .arg("--no-vars")
+        .arg("--var")
+        .arg("crate=demo");
+    cmd.assert().failure();
+}
+
+#[test]
+fn test_cli_rejects_invalid_assignments() {
+    let temp_dir = setup_temp_dir();
+    let md_file = temp_dir.child("template.md");
+    md_file.write_str(TEMPLATE_MD).unwrap();
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_file.path()).arg("--var").arg("no_value");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("expected KEY=VALUE"));
+
+    let vars_file = temp_dir.child("vars.env");
+    vars_file
+        .write_str("crate=demo\nnot an assignment\n")
+        .unwrap();
+    let mut cmd = get_cmd();
+    cmd.arg(md_file.path())
+        .arg("-o")
+        .arg(temp_dir.child("out").path())
+        .arg("--vars-file")
+        .arg(vars_file.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid line 2 in vars file"));
+}
@@ -0,0 +1,98 @@
+//! CLI tests for the --quiet, --verbose and --debug verbosity levels.
+
+use super::common::get_cmd;
+use crate::test_common::setup_temp_dir;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+const MARKDOWN: &str = "\n## File: src/main.rs\n```\nfn main() {}\n```\n";
+
+fn run_with(flag: Option<&str>) -> assert_cmd::assert::Assert {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path.write_str(MARKDOWN).unwrap();
+    let output_dir = temp_dir.child("generated");
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path()).arg("-o").arg(output_dir.path());
+    if let Some(flag) = flag {
+        cmd.arg(flag);
+    }
+    cmd.assert().success()
+}
+
+#[test]
+fn test_cli_default_prints_one_line_per_action() {
+    run_with(None)
+        .stdout(predicate::str::contains(
+            "[1/1] Create 'src/main.rs': Created",
+        ))
+        .stdout(predicate::str::contains("Processing Summary:"))
+        .stdout(predicate::str::contains("Adding Create action").not())
+        .stdout(predicate::str::contains("Step 1:").not());
+}
+
+#[test]
+fn test_cli_quiet_prints_only_summary() {
+    run_with(Some("--quiet"))
+        .stdout(predicate::str::contains("Processing Summary:"))
+        .stdout(predicate::str::contains(
+            "Files created:                      1",
+        ))
+        .stdout(predicate::str::contains("Reading markdown file").not())
+        .stdout(predicate::str::contains("[1/1]").not())
+        .stdout(predicate::str::contains("completed successfully").not());
+}
+
+#[test]
+fn test_cli_quiet_still_reports_errors() {
+    let temp_dir = setup_temp_dir();
+    let md_path = temp_dir.child("input.md");
+    md_path
+        .write_str("\n## File: blocker/file.txt\n```\nx\n```\n")
+        .unwrap();
+    let output_dir = temp_dir.child("generated");
+    output_dir.create_dir_all().unwrap();
+    output_dir.child("blocker").write_str("a file").unwrap();
+
+    let mut cmd = get_cmd();
+    cmd.arg(md_path.path())
+        .arg("-o")
+        .arg(output_dir.path())
+        .arg("-q");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Error: Could not process action"))
+        .stdout(predicate::str::contains("Processing Summary:"));
+}
+
+#[test]
+fn test_cli_verbose_prints_association_decisions() {
+    run_with(Some("--verbose"))
+        .stdout(predicate::str::contains(
+            "Adding Create action for 'src/main.rs' from source 'external'",
+        ))
+        .stdout(predicate::str::contains(
+            "[1/1] Action: Create, Path: 'src/main.rs'",
+        ))
+        .stdout(predicate::str::contains("Step 1:").not());
+}
+
+#[test]
+fn test_cli_debug_prints_pass_banners() {
+    run_with(Some("--debug"))
+        .stdout(predicate::str::contains("Step 1:"))
+        .stdout(predicate::str::contains(
+            "Adding Create action for 'src/main.rs'",
+        ));
+}
+
+#[test]
+fn test_cli_verbosity_flags_conflict() {
+    let mut cmd = get_cmd();
+    cmd.arg("input.md").arg("--quiet").arg("--verbose");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
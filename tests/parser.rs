@@ -7,6 +7,12 @@ mod common;
 // Declare the specific test modules (tests/parser/*.rs)
 #[path = "parser/append_prepend.rs"]
 mod append_prepend;
+#[path = "parser/binary.rs"]
+mod binary;
+#[path = "parser/conditionals.rs"]
+mod conditionals;
+#[path = "parser/copy_file.rs"]
+mod copy_file;
 #[path = "parser/create_distant.rs"]
 mod create_distant;
 #[path = "parser/create_external.rs"]
@@ -17,12 +23,26 @@ mod create_internal;
 mod create_wrapped;
 #[path = "parser/delete.rs"]
 mod delete;
+#[path = "parser/directory.rs"]
+mod directory;
 #[path = "parser/edge_cases.rs"]
 mod edge_cases;
+#[path = "parser/edit_file.rs"]
+mod edit_file;
+#[path = "parser/file_mode.rs"]
+mod file_mode;
 #[path = "parser/flexible_headers.rs"] // ADDED
 mod flexible_headers;
+#[path = "parser/front_matter.rs"]
+mod front_matter;
 #[path = "parser/heuristics.rs"]
 mod heuristics;
+#[path = "parser/include.rs"]
+mod include;
+#[path = "parser/indented_blocks.rs"]
+mod indented_blocks;
+#[path = "parser/insert.rs"]
+mod insert;
 #[path = "parser/invalid_paths.rs"]
 mod invalid_paths;
 #[path = "parser/move_file.rs"]
@@ -31,6 +51,16 @@ mod move_file;
 mod nested_content;
 #[path = "parser/ordering.rs"]
 mod ordering;
+#[path = "parser/patch_file.rs"]
+mod patch_file;
+#[path = "parser/reporter.rs"]
+mod reporter;
+#[path = "parser/symlink.rs"]
+mod symlink;
+#[path = "parser/tilde_fences.rs"]
+mod tilde_fences;
+#[path = "parser/variables.rs"]
+mod variables;
 
 // Declare the top-level common module (tests/test_common.rs)
 // This isn't strictly needed by parser tests currently, but good practice
@@ -0,0 +1,52 @@
+//! Tests for `base64` blocks, which carry binary file content.
+
+use strux::{parse_markdown, ActionType, Content, ParseError};
+
+const PNG_HEADER: [u8; 10] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0xFF];
+
+#[test]
+fn test_parse_base64_block_decodes_to_bytes() {
+    let md = "## File: assets/icon.png\n```base64\niVBORw0K\nGgoA/w==\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0].action_type, ActionType::Create);
+    assert_eq!(
+        actions[0].content,
+        Some(Content::Binary(PNG_HEADER.to_vec()))
+    );
+}
+
+#[test]
+fn test_parse_base64_block_with_mode_and_wrapped_header() {
+    let md = "\n```markdown\n## Append File: data.bin\n```\n\n```base64 mode=600\nAAEC\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0].action_type, ActionType::Append);
+    assert_eq!(actions[0].content, Some(Content::Binary(vec![0, 1, 2])));
+    assert_eq!(actions[0].mode, Some(0o600));
+}
+
+#[test]
+fn test_parse_base64_on_edit_stays_text() {
+    let md = "## Edit File: notes.txt\n```base64\n<<<<<<< SEARCH\nold\n=======\nnew\n>>>>>>> REPLACE\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert!(actions[0]
+        .content
+        .as_ref()
+        .and_then(Content::as_text)
+        .is_some_and(|text| text.starts_with("<<<<<<< SEARCH")));
+}
+
+#[test]
+fn test_parse_invalid_base64_is_an_error() {
+    let md = "Intro\n\n## File: broken.bin\n```base64\nnot base64!\n```\n";
+    let result = parse_markdown(md);
+    match result {
+        Err(ParseError::InvalidBase64 { pos, path, .. }) => {
+            assert_eq!(path, "broken.bin");
+            assert_eq!(pos, md.find("```base64").unwrap());
+        }
+        other => panic!("Expected InvalidBase64, got {:?}", other),
+    }
+}
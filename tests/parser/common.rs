@@ -1,6 +1,6 @@
 //! Common helpers for parser integration tests.
 
-use strux::core_types::{Action, ActionType};
+use strux::core_types::{Action, ActionType, Content};
 
 // --- Test Helpers ---
 
@@ -29,7 +29,7 @@ pub fn assert_action(
         action.path
     );
     assert_eq!(
-        action.content.as_deref(),
+        action.content.as_ref().and_then(Content::as_text),
         expected_content,
         "Action content mismatch for path: {}",
         action.path
@@ -0,0 +1,147 @@
+//! Tests for `<!-- strux:if flag -->` conditional regions.
+
+use std::collections::BTreeSet;
+use strux::{parse_document_with, Action, Content, NullReporter, ParseError, ParseOptions};
+
+fn parse_with(md: &str, flags: &[&str]) -> Result<Vec<Action>, ParseError> {
+    let options = ParseOptions {
+        enabled_flags: flags
+            .iter()
+            .map(|flag| flag.to_string())
+            .collect::<BTreeSet<_>>(),
+        ..ParseOptions::default()
+    };
+    parse_document_with(md, &options, &NullReporter).map(|(actions, _)| actions)
+}
+
+fn paths(actions: &[Action]) -> Vec<&str> {
+    actions.iter().map(|action| action.path.as_str()).collect()
+}
+
+const DOC: &str = "## File: a.txt\n```\nA\n```\n\n<!-- strux:if docker -->\n## File: Dockerfile\n```\nFROM scratch\n```\n<!-- strux:else -->\n## File: run.sh\n```\n./app\n```\n<!-- strux:endif -->\n\n## File: z.txt\n```\nZ\n```\n";
+
+#[test]
+fn test_conditional_region_enabled_and_disabled() {
+    let actions = parse_with(DOC, &[]).unwrap();
+    assert_eq!(paths(&actions), ["a.txt", "run.sh", "z.txt"]);
+
+    let actions = parse_with(DOC, &["docker"]).unwrap();
+    assert_eq!(paths(&actions), ["a.txt", "Dockerfile", "z.txt"]);
+    assert_eq!(
+        actions[1].content.as_ref().and_then(Content::as_text),
+        Some("FROM scratch\n")
+    );
+}
+
+#[test]
+fn test_conditional_positions_point_into_the_original_document() {
+    let actions = parse_with(DOC, &[]).unwrap();
+    assert_eq!(
+        actions[1].original_pos,
+        DOC.find("## File: run.sh").unwrap()
+    );
+    assert_eq!(actions[2].original_pos, DOC.find("## File: z.txt").unwrap());
+
+    let actions = parse_with(DOC, &["docker"]).unwrap();
+    assert_eq!(
+        actions[1].original_pos,
+        DOC.find("## File: Dockerfile").unwrap()
+    );
+}
+
+#[test]
+fn test_conditional_regions_nest() {
+    let md = "<!-- strux:if ci -->\n## File: ci.yml\n```\nci\n```\n<!-- strux:if docker -->\n## File: docker.yml\n```\ndocker\n```\n<!-- strux:endif -->\n<!-- strux:else -->\n<!-- strux:if docker -->\n## File: local-docker.txt\n```\nx\n```\n<!-- strux:endif -->\n<!-- strux:endif -->\n";
+    assert!(parse_with(md, &[]).unwrap().is_empty());
+    assert_eq!(paths(&parse_with(md, &["ci"]).unwrap()), ["ci.yml"]);
+    assert_eq!(
+        paths(&parse_with(md, &["ci", "docker"]).unwrap()),
+        ["ci.yml", "docker.yml"]
+    );
+    assert_eq!(
+        paths(&parse_with(md, &["docker"]).unwrap()),
+        ["local-docker.txt"]
+    );
+}
+
+#[test]
+fn test_disabled_headers_and_fences_do_not_leak() {
+    // A disabled header must not claim the next block, and a disabled unbalanced fence must
+    // not swallow the rest of the document.
+    let md = "<!-- strux:if docs -->\n## File: README.md\n<!-- strux:endif -->\n\n## File: main.rs\n```rust\nfn main() {}\n```\n\n<!-- strux:if docs -->\n```\nunclosed\n<!-- strux:endif -->\n\n## Deleted File: old.txt\n";
+    let actions = parse_with(md, &[]).unwrap();
+    assert_eq!(paths(&actions), ["main.rs", "old.txt"]);
+    assert_eq!(
+        actions[0].content.as_ref().and_then(Content::as_text),
+        Some("fn main() {}\n")
+    );
+}
+
+#[test]
+fn test_conditional_lines_inside_a_code_block() {
+    let md = "## File: config.toml\n```toml\nname = \"app\"\n<!-- strux:if docker -->\nimage = \"app:latest\"\n<!-- strux:endif -->\n```\n";
+    let actions = parse_with(md, &[]).unwrap();
+    assert_eq!(
+        actions[0].content.as_ref().and_then(Content::as_text),
+        Some("name = \"app\"\n")
+    );
+    let actions = parse_with(md, &["docker"]).unwrap();
+    assert_eq!(
+        actions[0].content.as_ref().and_then(Content::as_text),
+        Some("name = \"app\"\nimage = \"app:latest\"\n")
+    );
+}
+
+#[test]
+fn test_unclosed_conditional_regions_report_positions() {
+    let md = "# Doc\n<!-- strux:if a -->\n<!-- strux:if b -->\n<!-- strux:endif -->\n<!-- strux:if c -->\n";
+    match parse_with(md, &[]) {
+        Err(ParseError::UnclosedConditional { positions }) => {
+            assert_eq!(
+                positions,
+                [
+                    md.find("<!-- strux:if a").unwrap(),
+                    md.find("<!-- strux:if c").unwrap()
+                ]
+            );
+        }
+        other => panic!("Expected UnclosedConditional, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_invalid_conditional_markers() {
+    for (md, details) in [
+        ("<!-- strux:endif -->\n", "without 'strux:if'"),
+        ("<!-- strux:else -->\n", "without 'strux:if'"),
+        ("<!-- strux:if -->\n", "needs a flag name"),
+        ("<!-- strux:iff docker -->\n", "unrecognized marker"),
+        (
+            "<!-- strux:if a -->\n<!-- strux:endif a -->\n",
+            "does not take a flag",
+        ),
+        (
+            "<!-- strux:if a -->\n<!-- strux:else -->\n<!-- strux:else -->\n<!-- strux:endif -->\n",
+            "second 'strux:else'",
+        ),
+    ] {
+        match parse_with(md, &[]) {
+            Err(ParseError::InvalidConditional { details: found, .. }) => {
+                assert!(found.contains(details), "{:?}: {}", md, found)
+            }
+            other => panic!("Expected InvalidConditional for {:?}, got {:?}", md, other),
+        }
+    }
+}
+
+#[test]
+fn test_escaped_markers_are_kept_as_text() {
+    let md = "## File: README.md\n```markdown\n# Usage\n\\<!-- strux:if docker -->\n  \\\\<!-- strux:bogus -->\nend\n```\n\n## File: z.txt\n```\nZ\n```\n";
+    let actions = parse_with(md, &[]).unwrap();
+    assert_eq!(paths(&actions), ["README.md", "z.txt"]);
+    assert_eq!(
+        actions[0].content.as_ref().and_then(Content::as_text),
+        Some("# Usage\n<!-- strux:if docker -->\n  \\<!-- strux:bogus -->\nend\n")
+    );
+    assert_eq!(actions[1].original_pos, md.find("## File: z.txt").unwrap());
+}
@@ -0,0 +1,55 @@
+//! Tests for parsing 'Copied File' headers.
+
+use super::common::*; // Use helper from common.rs
+use strux::core_types::ActionType;
+use strux::parse_markdown;
+
+#[test]
+fn test_parse_hash_copied_file_header() {
+    let md = "\n## Copied File: templates/base.html to site/index.html\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_action(
+        actions.first(),
+        ActionType::Copy,
+        "templates/base.html",
+        Some("site/index.html"),
+        None,
+    );
+}
+
+#[test]
+fn test_parse_bold_copied_file_header_with_backticks() {
+    let md = "\n**Copied File: `my config.toml` to `backup/my config.toml`**\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_action(
+        actions.first(),
+        ActionType::Copy,
+        "my config.toml",
+        Some("backup/my config.toml"),
+        None,
+    );
+}
+
+#[test]
+fn test_parse_copied_file_header_is_standalone() {
+    // A code block after the header is not its content; it stays an unrelated block.
+    let md = "\n## Copied File: a.txt to b.txt\n```\nnot content\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_action(
+        actions.first(),
+        ActionType::Copy,
+        "a.txt",
+        Some("b.txt"),
+        None,
+    );
+}
+
+#[test]
+fn test_parse_copied_file_header_without_destination_is_ignored() {
+    let md = "\n## Copied File: lonely.txt\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert!(actions.is_empty());
+}
@@ -0,0 +1,111 @@
+//! Tests for parsing 'Directory' and 'Deleted Directory' headers.
+
+use super::common::*; // Use helper from common.rs
+use strux::core_types::ActionType;
+use strux::parse_markdown;
+
+#[test]
+fn test_parse_hash_directory_header() {
+    let md = "\n## Directory: logs\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_action(
+        actions.first(),
+        ActionType::CreateDirectory,
+        "logs",
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_parse_bold_deleted_directory_header() {
+    let md = "\n**Deleted Directory: `build/tmp`**\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_action(
+        actions.first(),
+        ActionType::DeleteDirectory,
+        "build/tmp",
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_parse_wrapped_directory_header() {
+    let md = "\n```markdown\n## Directory: tmp/cache\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_action(
+        actions.first(),
+        ActionType::CreateDirectory,
+        "tmp/cache",
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_parse_directory_word_in_heading_is_not_an_action() {
+    // Without a colon, "Directory" is just part of a heading
+    let md = "\n## Project Directory Layout\n\n## Deleted Directory handling\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert!(actions.is_empty(), "Unexpected actions: {:?}", actions);
+}
+
+#[test]
+fn test_parse_directory_header_keeps_document_order() {
+    let md = "\n## Directory: logs\n\n## File: logs/README.md\n```\nLogs go here.\n```\n\n## Deleted Directory: old\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 3);
+    assert_action(
+        actions.first(),
+        ActionType::CreateDirectory,
+        "logs",
+        None,
+        None,
+    );
+    assert_action(
+        actions.get(1),
+        ActionType::Create,
+        "logs/README.md",
+        None,
+        Some("Logs go here.\n"),
+    );
+    assert_action(
+        actions.get(2),
+        ActionType::DeleteDirectory,
+        "old",
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_parse_moved_directory_header() {
+    let md = "\n## Moved Directory: src/old_mod to `src/new mod`\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_action(
+        actions.first(),
+        ActionType::MoveDirectory,
+        "src/old_mod",
+        Some("src/new mod"),
+        None,
+    );
+}
+
+#[test]
+fn test_parse_wrapped_moved_directory_header() {
+    let md = "\n```markdown\n**Moved Directory: a to b/c**\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_action(
+        actions.first(),
+        ActionType::MoveDirectory,
+        "a",
+        Some("b/c"),
+        None,
+    );
+}
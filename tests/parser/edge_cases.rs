@@ -1,7 +1,7 @@
 //! Tests for parser edge cases and invalid formats.
 
 // Use helper from common.rs
-use strux::parse_markdown;
+use strux::{parse_markdown, Content};
 
 #[test]
 fn test_parse_empty_input() {
@@ -83,4 +83,31 @@ fn test_parse_only_markdown_wrapper() {
     assert!(actions.is_empty());
 }
 
+#[test]
+fn test_parse_no_eol_attribute_strips_final_newline() {
+    let md =
+        "## File: a.txt\n```text no-eol\nlast line\n```\n\n## File: b.txt\n```text\nkept\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 2);
+    assert_eq!(
+        actions[0].content.as_ref().and_then(Content::as_text),
+        Some("last line")
+    );
+    assert_eq!(
+        actions[1].content.as_ref().and_then(Content::as_text),
+        Some("kept\n")
+    );
+}
+
+#[test]
+fn test_parse_blank_lines_before_closing_fence() {
+    let md = "## File: a.txt\n```\n\n\n  indented\n\n\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_eq!(
+        actions[0].content.as_ref().and_then(Content::as_text),
+        Some("\n\n  indented\n\n\n")
+    );
+}
+
 // test_parse_invalid_path_format_skipped MOVED to invalid_paths.rs
@@ -0,0 +1,45 @@
+//! Tests for parsing 'Edit File' headers.
+
+use super::common::*; // Use helper from common.rs
+use strux::core_types::ActionType;
+use strux::parse_markdown;
+
+const HUNK: &str = "<<<<<<< SEARCH\nold\n=======\nnew\n>>>>>>> REPLACE\n";
+
+#[test]
+fn test_parse_hash_edit_file_header() {
+    let md = format!("\n## Edit File: src/lib.rs\n```rust\n{}```\n", HUNK);
+    let actions = parse_markdown(&md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_action(
+        actions.first(),
+        ActionType::Edit,
+        "src/lib.rs",
+        None,
+        Some(HUNK),
+    );
+}
+
+#[test]
+fn test_parse_wrapped_edit_file_header() {
+    let md = format!(
+        "\n```markdown\n**Edit File: `config.toml`**\n```\n```toml\n{}```\n",
+        HUNK
+    );
+    let actions = parse_markdown(&md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_action(
+        actions.first(),
+        ActionType::Edit,
+        "config.toml",
+        None,
+        Some(HUNK),
+    );
+}
+
+#[test]
+fn test_parse_edit_file_header_without_block_is_ignored() {
+    let md = "\n## Edit File: src/lib.rs\n\nSome prose.\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert!(actions.is_empty());
+}
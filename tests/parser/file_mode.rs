@@ -0,0 +1,49 @@
+//! Tests for the `mode=` fence attribute on content blocks.
+
+use strux::{parse_markdown, ActionType, Content};
+
+#[test]
+fn test_parse_mode_attribute_external_header() {
+    let md = "## File: scripts/run.sh\n```bash mode=755\n#!/bin/sh\n```\n\n## File: plain.txt\n```text\nx\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 2);
+    assert_eq!(actions[0].mode, Some(0o755));
+    assert_eq!(
+        actions[0].content.as_ref().and_then(Content::as_text),
+        Some("#!/bin/sh\n")
+    );
+    assert_eq!(actions[1].mode, None);
+}
+
+#[test]
+fn test_parse_mode_attribute_with_leading_zero_and_no_eol() {
+    let md = "## Append File: bin/tool\n```mode=0700 no-eol\necho done\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0].action_type, ActionType::Append);
+    assert_eq!(actions[0].mode, Some(0o700));
+    assert_eq!(
+        actions[0].content.as_ref().and_then(Content::as_text),
+        Some("echo done")
+    );
+}
+
+#[test]
+fn test_parse_mode_attribute_wrapped_and_distant_headers() {
+    let md = "\n```markdown\n## File: wrapped.sh\n```\n\n```sh mode=750\necho wrapped\n```\n\n## File: distant.sh\n\nSome text.\n\n```sh mode=711\necho distant\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 2);
+    assert_eq!(actions[0].path, "wrapped.sh");
+    assert_eq!(actions[0].mode, Some(0o750));
+    assert_eq!(actions[1].path, "distant.sh");
+    assert_eq!(actions[1].mode, Some(0o711));
+}
+
+#[test]
+fn test_parse_invalid_or_misplaced_mode_is_ignored() {
+    let md = "## File: a.sh\n```sh mode=789\necho a\n```\n\n## Edit File: b.txt\n```text mode=755\n<<<<<<< SEARCH\nold\n=======\nnew\n>>>>>>> REPLACE\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 2);
+    assert_eq!(actions[0].mode, None);
+    assert_eq!(actions[1].mode, None);
+}
@@ -0,0 +1,181 @@
+//! Tests for front-matter blocks (`parse_document_with`).
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use strux::{
+    apply_line_endings, check_allowed_actions, parse_document_with, parse_markdown, Action,
+    ActionType, Content, Event, FrontMatter, LineEndings, NullReporter, ParseError, ParseOptions,
+    Reporter,
+};
+
+fn parse(md: &str) -> Result<(Vec<Action>, FrontMatter), ParseError> {
+    parse_document_with(md, &ParseOptions::default(), &NullReporter)
+}
+
+/// Collects the warnings it receives.
+#[derive(Default)]
+struct WarningReporter {
+    warnings: RefCell<Vec<String>>,
+}
+
+impl Reporter for WarningReporter {
+    fn report(&self, event: &Event) {
+        if let Event::Warning(message) = event {
+            self.warnings.borrow_mut().push(message.clone());
+        }
+    }
+}
+
+#[test]
+fn test_yaml_front_matter_settings() {
+    let md = "---\noutput_dir: \"build/site\"\nforce: true\nline_endings: crlf\nallowed_actions: [create, append]\nvariables:\n  name: demo\n  version: '1.0'\n---\n## File: {{name}}.txt\n```\nv{{version}}\n```\n";
+    let (actions, front_matter) = parse(md).unwrap();
+    assert_eq!(front_matter.output_dir.as_deref(), Some("build/site"));
+    assert_eq!(front_matter.force, Some(true));
+    assert_eq!(front_matter.line_endings, Some(LineEndings::Crlf));
+    assert_eq!(
+        front_matter.allowed_actions,
+        Some(vec![ActionType::Create, ActionType::Append])
+    );
+    let expected: BTreeMap<_, _> = [("name", "demo"), ("version", "1.0")]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    assert_eq!(front_matter.variables, expected);
+
+    // The body keeps positions relative to the whole document
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0].path, "{{name}}.txt");
+    assert_eq!(actions[0].original_pos, md.find("## File").unwrap());
+}
+
+#[test]
+fn test_yaml_block_lists_and_toml_front_matter() {
+    let yaml = "---\nallowed_actions:\n  - create\n  - delete\n---\n";
+    let (_, front_matter) = parse(yaml).unwrap();
+    assert_eq!(
+        front_matter.allowed_actions,
+        Some(vec![ActionType::Create, ActionType::Delete])
+    );
+
+    let toml = "+++\n# Settings\noutput_dir = \"out\"\nforce = false\nallowed_actions = [\"create\"]\n\n[variables]\nname = \"demo\"\n+++\n## File: a.txt\n```\nA\n```\n";
+    let (actions, front_matter) = parse(toml).unwrap();
+    assert_eq!(front_matter.output_dir.as_deref(), Some("out"));
+    assert_eq!(front_matter.force, Some(false));
+    assert_eq!(front_matter.allowed_actions, Some(vec![ActionType::Create]));
+    assert_eq!(
+        front_matter.variables.get("name").map(String::as_str),
+        Some("demo")
+    );
+    assert_eq!(actions.len(), 1);
+}
+
+#[test]
+fn test_inline_comments_are_ignored() {
+    let yaml = "---\nforce: true # overwrite\noutput_dir: \"out #1\" # quoted\nallowed_actions: [create] # only\nvariables: # defaults\n  tag: v#2 # no space before the first '#'\n---\n";
+    let (_, front_matter) = parse(yaml).unwrap();
+    assert_eq!(front_matter.force, Some(true));
+    assert_eq!(front_matter.output_dir.as_deref(), Some("out #1"));
+    assert_eq!(front_matter.allowed_actions, Some(vec![ActionType::Create]));
+    assert_eq!(
+        front_matter.variables.get("tag").map(String::as_str),
+        Some("v#2")
+    );
+
+    let toml =
+        "+++\nforce = false # keep files\n[variables] # table\nname = 'a # b' # quoted\n+++\n";
+    let (_, front_matter) = parse(toml).unwrap();
+    assert_eq!(front_matter.force, Some(false));
+    assert_eq!(
+        front_matter.variables.get("name").map(String::as_str),
+        Some("a # b")
+    );
+}
+
+#[test]
+fn test_front_matter_before_markdown_wrapper() {
+    let md = "---\nforce: true\n---\n```markdown\n## File: a.txt\n```\nA\n```\n```\n";
+    let (actions, front_matter) = parse(md).unwrap();
+    assert_eq!(front_matter.force, Some(true));
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0].path, "a.txt");
+}
+
+#[test]
+fn test_unclosed_rule_is_not_front_matter() {
+    let md = "---\n## File: a.txt\n```\nA\n```\n";
+    let (actions, front_matter) = parse(md).unwrap();
+    assert_eq!(front_matter, FrontMatter::default());
+    assert_eq!(actions.len(), 1);
+}
+
+#[test]
+fn test_rules_around_text_are_not_front_matter() {
+    let md = "---\nSome introduction.\n---\n\n## File: a.txt\n```\nA\n```\n";
+    let reporter = WarningReporter::default();
+    let (actions, front_matter) =
+        parse_document_with(md, &ParseOptions::default(), &reporter).unwrap();
+    assert_eq!(front_matter, FrontMatter::default());
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0].path, "a.txt");
+    assert!(reporter
+        .warnings
+        .borrow()
+        .iter()
+        .any(|warning| warning.contains("Not reading the leading '---' block")));
+}
+
+#[test]
+fn test_invalid_front_matter_reports_line() {
+    let cases = [
+        ("---\nforce: maybe\n---\n", 2),
+        ("---\nname: x\nline_endings: cr\n---\n", 3),
+        ("---\nallowed_actions: [create, explode]\n---\n", 2),
+        ("+++\n[server]\n+++\n", 2),
+    ];
+    for (md, expected_line) in cases {
+        match parse(md) {
+            Err(ParseError::InvalidFrontMatter { line, .. }) => {
+                assert_eq!(line, expected_line, "for {:?}", md)
+            }
+            other => panic!("Expected InvalidFrontMatter for {:?}, got {:?}", md, other),
+        }
+    }
+}
+
+#[test]
+fn test_merge_prefers_later_settings() {
+    let (_, mut merged) =
+        parse("---\nforce: true\noutput_dir: a\nvariables:\n  x: 1\n---\n").unwrap();
+    let (_, later) = parse("---\noutput_dir: b\nvariables:\n  y: 2\n---\n").unwrap();
+    merged.merge(later);
+    assert_eq!(merged.output_dir.as_deref(), Some("b"));
+    assert_eq!(merged.force, Some(true));
+    assert_eq!(merged.variables.len(), 2);
+}
+
+#[test]
+fn test_line_endings_and_allowed_actions() {
+    let md = "## File: a.txt\n```\none\r\ntwo\n```\n\n## Deleted File: old.txt\n";
+    let mut actions = parse_markdown(md).unwrap();
+    apply_line_endings(&mut actions, LineEndings::Crlf);
+    assert_eq!(
+        actions[0].content.as_ref().and_then(Content::as_text),
+        Some("one\r\ntwo\r\n")
+    );
+    apply_line_endings(&mut actions, LineEndings::Lf);
+    assert_eq!(
+        actions[0].content.as_ref().and_then(Content::as_text),
+        Some("one\ntwo\n")
+    );
+
+    assert!(check_allowed_actions(&actions, &[ActionType::Create, ActionType::Delete]).is_ok());
+    match check_allowed_actions(&actions, &[ActionType::Create]) {
+        Err(ParseError::ActionNotAllowed { action, path, pos }) => {
+            assert_eq!(action, "delete");
+            assert_eq!(path, "old.txt");
+            assert_eq!(pos, md.find("## Deleted").unwrap());
+        }
+        other => panic!("Expected ActionNotAllowed, got {:?}", other),
+    }
+}
@@ -0,0 +1,150 @@
+//! Tests for `## Include:` headers and their expansion (`resolve_includes`).
+
+use crate::test_common::setup_temp_dir;
+use assert_fs::prelude::*;
+use strux::{
+    parse_markdown, resolve_includes, Action, ActionType, NullReporter, ParseError, ParseOptions,
+    MAX_INCLUDE_DEPTH,
+};
+
+fn paths(actions: &[Action]) -> Vec<&str> {
+    actions.iter().map(|action| action.path.as_str()).collect()
+}
+
+#[test]
+fn test_parse_include_header() {
+    let md =
+        "## File: a.txt\n```\nA\n```\n\n## Include: parts/api.md\n\n## Included files\n\nText.\n";
+    let actions = parse_markdown(md).unwrap();
+    assert_eq!(actions.len(), 2);
+    assert_eq!(actions[1].action_type, ActionType::Include);
+    assert_eq!(actions[1].path, "parts/api.md");
+    assert_eq!(actions[1].original_pos, md.find("## Include:").unwrap());
+}
+
+#[test]
+fn test_resolve_includes_splices_actions_in_document_order() {
+    let temp_dir = setup_temp_dir();
+    let main = temp_dir.child("main.md");
+    main.write_str(
+        "## File: a.txt\n```\nA\n```\n\n## Include: parts/api.md\n\n## File: z.txt\n```\nZ\n```\n",
+    )
+    .unwrap();
+    let api_md = "## File: api.txt\n```\nAPI\n```\n\n## Include: deep/more.md\n";
+    temp_dir.child("parts/api.md").write_str(api_md).unwrap();
+    temp_dir
+        .child("parts/deep/more.md")
+        .write_str("Intro\n\n## Deleted File: old.txt\n")
+        .unwrap();
+
+    let actions = parse_markdown(&std::fs::read_to_string(main.path()).unwrap()).unwrap();
+    let actions = resolve_includes(
+        actions,
+        Some(main.path()),
+        &ParseOptions::default(),
+        &NullReporter,
+    )
+    .unwrap();
+    assert_eq!(paths(&actions), ["a.txt", "api.txt", "old.txt", "z.txt"]);
+
+    // Included actions remember their own file and position in it
+    let api_file = temp_dir.child("parts/api.md").path().display().to_string();
+    assert_eq!(actions[1].source_file.as_deref(), Some(api_file.as_str()));
+    assert_eq!(actions[1].original_pos, 0);
+    assert!(actions[2]
+        .source_file
+        .as_deref()
+        .unwrap()
+        .ends_with("more.md"));
+    assert_eq!(actions[2].original_pos, "Intro\n\n".len());
+    assert_eq!(actions[0].source_file, None); // Top-level actions are left to the caller
+}
+
+#[test]
+fn test_resolve_includes_detects_cycles() {
+    let temp_dir = setup_temp_dir();
+    let main = temp_dir.child("main.md");
+    main.write_str("## Include: other.md\n").unwrap();
+    temp_dir
+        .child("other.md")
+        .write_str("## Include: main.md\n")
+        .unwrap();
+
+    let actions = parse_markdown("## Include: other.md\n").unwrap();
+    match resolve_includes(
+        actions,
+        Some(main.path()),
+        &ParseOptions::default(),
+        &NullReporter,
+    ) {
+        Err(ParseError::IncludeCycle { cycle }) => {
+            assert!(cycle.contains("other.md -> "), "cycle: {}", cycle);
+            assert!(cycle.ends_with("main.md"), "cycle: {}", cycle);
+        }
+        other => panic!("Expected IncludeCycle, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_resolve_includes_depth_limit() {
+    let temp_dir = setup_temp_dir();
+    // level0.md includes level1.md, which includes level2.md, ...
+    for level in 0..=MAX_INCLUDE_DEPTH + 1 {
+        temp_dir
+            .child(format!("level{}.md", level))
+            .write_str(&format!("## Include: level{}.md\n", level + 1))
+            .unwrap();
+    }
+    let main = temp_dir.child("level0.md");
+    let actions = parse_markdown("## Include: level1.md\n").unwrap();
+    match resolve_includes(
+        actions,
+        Some(main.path()),
+        &ParseOptions::default(),
+        &NullReporter,
+    ) {
+        Err(ParseError::IncludeTooDeep { limit, .. }) => assert_eq!(limit, MAX_INCLUDE_DEPTH),
+        other => panic!("Expected IncludeTooDeep, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_resolve_includes_reports_missing_and_invalid_files() {
+    let temp_dir = setup_temp_dir();
+    let main = temp_dir.child("main.md");
+    let md = "# Doc\n\n## Include: missing.md\n";
+    let actions = parse_markdown(md).unwrap();
+    match resolve_includes(
+        actions,
+        Some(main.path()),
+        &ParseOptions::default(),
+        &NullReporter,
+    ) {
+        Err(ParseError::IncludeNotReadable { path, pos, .. }) => {
+            assert!(path.ends_with("missing.md"));
+            assert_eq!(pos, md.find("## Include").unwrap());
+        }
+        other => panic!("Expected IncludeNotReadable, got {:?}", other),
+    }
+
+    temp_dir
+        .child("broken.md")
+        .write_str("---\nforce: maybe\n---\n## File: a.txt\n```\nA\n```\n")
+        .unwrap();
+    let actions = parse_markdown("## Include: broken.md\n").unwrap();
+    match resolve_includes(
+        actions,
+        Some(main.path()),
+        &ParseOptions::default(),
+        &NullReporter,
+    ) {
+        Err(ParseError::InIncludedFile { file, source }) => {
+            assert!(file.ends_with("broken.md"));
+            assert!(matches!(
+                *source,
+                ParseError::InvalidFrontMatter { line: 2, .. }
+            ));
+        }
+        other => panic!("Expected InIncludedFile, got {:?}", other),
+    }
+}
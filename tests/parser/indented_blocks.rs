@@ -0,0 +1,85 @@
+//! Tests for 4-space indented code blocks (`ParseOptions::indented_code_blocks`).
+
+use super::common::*;
+use strux::core_types::ActionType;
+use strux::{parse_document_with, parse_markdown, Action, NullReporter, ParseOptions};
+
+fn parse_indented(md: &str) -> Vec<Action> {
+    let options = ParseOptions {
+        indented_code_blocks: true,
+        ..ParseOptions::default()
+    };
+    parse_document_with(md, &options, &NullReporter)
+        .expect("Parsing failed")
+        .0
+}
+
+#[test]
+fn test_parse_indented_block_after_header() {
+    let md = "## File: src/main.rs\n\n    fn main() {\n\n        println!(\"hi\");\n    }\n\n\nText after.\n\n## Append File: notes.txt\n\tline\n";
+    let actions = parse_indented(md);
+    assert_eq!(actions.len(), 2);
+    assert_action(
+        actions.first(),
+        ActionType::Create,
+        "src/main.rs",
+        None,
+        Some("fn main() {\n\n    println!(\"hi\");\n}\n"),
+    );
+    assert_eq!(actions[0].original_pos, 0);
+    assert_action(
+        actions.get(1),
+        ActionType::Append,
+        "notes.txt",
+        None,
+        Some("line\n"),
+    );
+}
+
+#[test]
+fn test_parse_indented_blocks_are_opt_in() {
+    let md = "## File: a.txt\n\n    indented\n\n## File: b.txt\n```\nB\n```\n";
+    // Without the option the indented lines are plain text and `a.txt` has no block
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_action(
+        actions.first(),
+        ActionType::Create,
+        "b.txt",
+        None,
+        Some("B\n"),
+    );
+
+    let actions = parse_indented(md);
+    assert_eq!(actions.len(), 2);
+    assert_action(
+        actions.first(),
+        ActionType::Create,
+        "a.txt",
+        None,
+        Some("indented\n"),
+    );
+    assert_action(
+        actions.get(1),
+        ActionType::Create,
+        "b.txt",
+        None,
+        Some("B\n"),
+    );
+}
+
+#[test]
+fn test_parse_fenced_blocks_win_over_indented_lines() {
+    // A header directly above a fence keeps it, and headers or indented lines inside fenced
+    // blocks are not indented blocks
+    let md = "## File: a.md\n```markdown\n## File: inner.txt\n\n    not a block\n```\n\n    stray indented text\n";
+    let actions = parse_indented(md);
+    assert_eq!(actions.len(), 1);
+    assert_action(
+        actions.first(),
+        ActionType::Create,
+        "a.md",
+        None,
+        Some("## File: inner.txt\n\n    not a block\n"),
+    );
+}
@@ -0,0 +1,75 @@
+//! Tests for parsing 'Insert After' and 'Insert Before' headers.
+
+use super::common::*; // Use helper from common.rs
+use strux::core_types::ActionType;
+use strux::parse_markdown;
+
+#[test]
+fn test_parse_hash_insert_after_header_with_regex_anchor() {
+    let md = "\n## Insert After: src/lib.rs @ /^mod /\n```rust\nmod foo;\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_action(
+        actions.first(),
+        ActionType::InsertAfter,
+        "src/lib.rs",
+        None,
+        Some("mod foo;\n"),
+    );
+    assert_eq!(actions[0].anchor.as_deref(), Some("/^mod /"));
+}
+
+#[test]
+fn test_parse_insert_before_anchor_keeps_trailing_text() {
+    // Unlike a path, the anchor is not cut at " #" or " ("
+    let md = "\n## Insert Before: routes.py @ # ROUTES END (keep)\n```python\nroute('/x')\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_action(
+        actions.first(),
+        ActionType::InsertBefore,
+        "routes.py",
+        None,
+        Some("route('/x')\n"),
+    );
+    assert_eq!(actions[0].anchor.as_deref(), Some("# ROUTES END (keep)"));
+}
+
+#[test]
+fn test_parse_insert_header_with_backticked_path_and_anchor() {
+    let md = "\n**Insert After: `my dir/a @ b.txt` @ `  indented line`**\n```\nnew\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_action(
+        actions.first(),
+        ActionType::InsertAfter,
+        "my dir/a @ b.txt",
+        None,
+        Some("new\n"),
+    );
+    assert_eq!(actions[0].anchor.as_deref(), Some("  indented line"));
+}
+
+#[test]
+fn test_parse_wrapped_insert_header() {
+    let md = "\n```markdown\n**Insert Before: main.rs @ fn main() {**\n```\n```rust\nuse std::io;\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_action(
+        actions.first(),
+        ActionType::InsertBefore,
+        "main.rs",
+        None,
+        Some("use std::io;\n"),
+    );
+    assert_eq!(actions[0].anchor.as_deref(), Some("fn main() {"));
+}
+
+#[test]
+fn test_parse_insert_header_without_anchor_is_ignored() {
+    let md = "\n## Insert After: src/lib.rs\n```rust\nmod foo;\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert!(actions
+        .iter()
+        .all(|action| action.action_type != ActionType::InsertAfter));
+}
@@ -0,0 +1,85 @@
+//! Tests for parsing 'Patch File' headers and header-less diff blocks.
+
+use super::common::*; // Use helper from common.rs
+use strux::core_types::ActionType;
+use strux::parse_markdown;
+
+const HUNK: &str = "@@ -1,2 +1,2 @@\n a\n-b\n+B\n";
+
+#[test]
+fn test_parse_hash_patch_file_header() {
+    let md = format!("\n## Patch File: src/lib.rs\n```diff\n{}```\n", HUNK);
+    let actions = parse_markdown(&md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_action(
+        actions.first(),
+        ActionType::Patch,
+        "src/lib.rs",
+        None,
+        Some(HUNK),
+    );
+}
+
+#[test]
+fn test_parse_headerless_diff_block_uses_plus_plus_plus_path() {
+    let diff = format!("--- a/src/old.rs\n+++ b/src/main.rs\n{}", HUNK);
+    let md = format!("Some prose.\n\n```diff\n{}```\n", diff);
+    let actions = parse_markdown(&md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_action(
+        actions.first(),
+        ActionType::Patch,
+        "src/main.rs",
+        None,
+        Some(&diff),
+    );
+}
+
+#[test]
+fn test_parse_multi_file_diff_expands_into_one_action_per_file() {
+    let first = format!("--- a.txt\t2024-01-01\n+++ a.txt\t2024-01-02\n{}", HUNK);
+    let second = format!("--- dir/b.txt\n+++ dir/b.txt\n{}", HUNK);
+    let md = format!(
+        "\n```patch\ndiff --git a/a.txt b/a.txt\n{}{}```\n",
+        first, second
+    );
+    let actions = parse_markdown(&md).expect("Parsing failed");
+    assert_eq!(actions.len(), 2);
+    assert_action(
+        actions.first(),
+        ActionType::Patch,
+        "a.txt",
+        None,
+        Some(&first),
+    );
+    assert_action(
+        actions.get(1),
+        ActionType::Patch,
+        "dir/b.txt",
+        None,
+        Some(&second),
+    );
+    assert!(actions[0].original_pos < actions[1].original_pos);
+}
+
+#[test]
+fn test_parse_diff_to_dev_null_is_a_delete() {
+    let md = "\n```diff\n--- a/old.txt\n+++ /dev/null\n@@ -1 +0,0 @@\n-gone\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_action(actions.first(), ActionType::Delete, "old.txt", None, None);
+}
+
+#[test]
+fn test_parse_diff_block_without_file_headers_is_ignored() {
+    let md = format!("\n```diff\n{}```\n", HUNK);
+    let actions = parse_markdown(&md).expect("Parsing failed");
+    assert!(actions.is_empty());
+}
+
+#[test]
+fn test_parse_diff_block_with_malformed_path_is_skipped() {
+    let md = format!("\n```diff\n--- a//x.txt\n+++ a//x.txt\n{}```\n", HUNK);
+    let actions = parse_markdown(&md).expect("Parsing failed");
+    assert!(actions.is_empty());
+}
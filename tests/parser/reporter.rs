@@ -0,0 +1,66 @@
+//! Tests for the events the parser sends to a Reporter.
+
+use std::cell::RefCell;
+use strux::{parse_markdown_with_reporter, Event, NullReporter, Reporter};
+
+/// Records a short description of every event it receives.
+#[derive(Default)]
+struct RecordingReporter {
+    events: RefCell<Vec<String>>,
+}
+
+impl Reporter for RecordingReporter {
+    fn report(&self, event: &Event) {
+        let description = match event {
+            Event::Stage(_) => "stage".to_string(),
+            Event::BlockFound { lang, start, .. } => format!("block {} {}", lang, start),
+            Event::HeaderAssociated {
+                source,
+                action,
+                original_pos,
+            } => format!("header {} {} {}", source, action.path, original_pos),
+            Event::Warning(message) => format!("warning {}", message),
+            Event::Trace(_) => "trace".to_string(),
+            _ => "other".to_string(),
+        };
+        self.events.borrow_mut().push(description);
+    }
+}
+
+#[test]
+fn test_reporter_receives_block_and_header_events() {
+    let md = "## File: src/main.rs\n```rust\nfn main() {}\n```\n";
+    let reporter = RecordingReporter::default();
+
+    let actions = parse_markdown_with_reporter(md, &reporter).unwrap();
+
+    assert_eq!(actions.len(), 1);
+    let events = reporter.events.borrow();
+    assert!(events.contains(&"block rust 21".to_string()));
+    assert!(events.contains(&"header external src/main.rs 0".to_string()));
+    assert!(events.contains(&"stage".to_string()));
+    assert!(events.contains(&"trace".to_string()));
+}
+
+#[test]
+fn test_reporter_receives_warnings() {
+    let md = "## File: orphan.txt\n\nNo code block follows.\n";
+    let reporter = RecordingReporter::default();
+
+    let actions = parse_markdown_with_reporter(md, &reporter).unwrap();
+
+    assert!(actions.is_empty());
+    let events = reporter.events.borrow();
+    assert!(events
+        .iter()
+        .any(|e| e.starts_with("warning") && e.contains("orphan.txt")));
+}
+
+#[test]
+fn test_null_reporter_parses_identically() {
+    let md = "## File: a.txt\n```\nA\n```\n\n## Deleted File: b.txt\n";
+    let actions = parse_markdown_with_reporter(md, &NullReporter).unwrap();
+    assert_eq!(actions.len(), 2);
+    assert_eq!(actions[0].path, "a.txt");
+    assert_eq!(actions[1].path, "b.txt");
+}
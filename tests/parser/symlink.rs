@@ -0,0 +1,56 @@
+//! Tests for parsing 'Symlink' headers.
+
+use super::common::*; // Use helper from common.rs
+use strux::core_types::ActionType;
+use strux::parse_markdown;
+
+#[test]
+fn test_parse_hash_symlink_header() {
+    let md = "\n## Symlink: current to releases/v1\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_action(
+        actions.first(),
+        ActionType::Symlink,
+        "current",
+        Some("releases/v1"),
+        None,
+    );
+}
+
+#[test]
+fn test_parse_bold_symlink_header_with_backticks() {
+    let md = "\n**Symlink: `bin/my tool` to `../libexec/my tool`**\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_action(
+        actions.first(),
+        ActionType::Symlink,
+        "bin/my tool",
+        Some("../libexec/my tool"),
+        None,
+    );
+}
+
+#[test]
+fn test_parse_symlink_header_is_standalone() {
+    // A code block after the header is not its content; it stays an unrelated block.
+    let md = "\n## Symlink: latest to v2\n```\nnot content\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 1);
+    assert_action(
+        actions.first(),
+        ActionType::Symlink,
+        "latest",
+        Some("v2"),
+        None,
+    );
+}
+
+#[test]
+fn test_parse_symlink_word_in_heading_is_not_an_action() {
+    // Without a colon, "Symlink" is just a word in a heading
+    let md = "\n## Symlink support to be added later\n\nSome prose.\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert!(actions.is_empty());
+}
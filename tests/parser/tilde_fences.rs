@@ -0,0 +1,113 @@
+//! Tests for `~~~` fences, mixed fence characters and longer closing fences.
+
+use super::common::*;
+use strux::core_types::ActionType;
+use strux::parse_markdown;
+
+#[test]
+fn test_parse_tilde_fences() {
+    let md = "## File: src/main.py\n~~~python\nprint(\"hi\")\n~~~\n\n## Append File: notes.txt\n\n~~~~\nmore\n~~~~\n\n## Deleted File: old.txt\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 3);
+    assert_action(
+        actions.first(),
+        ActionType::Create,
+        "src/main.py",
+        None,
+        Some("print(\"hi\")\n"),
+    );
+    assert_action(
+        actions.get(1),
+        ActionType::Append,
+        "notes.txt",
+        None,
+        Some("more\n"),
+    );
+    assert_action(actions.get(2), ActionType::Delete, "old.txt", None, None);
+}
+
+#[test]
+fn test_parse_tilde_fence_attributes_and_internal_header() {
+    let md = "~~~bash mode=755\n// File: run.sh\necho hi\n~~~\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_action(
+        actions.first(),
+        ActionType::Create,
+        "run.sh",
+        None,
+        Some("echo hi\n"),
+    );
+    assert_eq!(actions[0].mode, Some(0o755));
+}
+
+#[test]
+fn test_parse_mixed_tilde_and_backtick_nesting() {
+    // Backtick blocks inside a tilde block are content, and vice versa
+    let md = "## File: docs/guide.md\n~~~markdown\n# Guide\n```rust\nfn main() {}\n```\n~~~\n\n## File: docs/other.md\n```markdown\n~~~\nnot a closer for the backtick block\n~~~\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 2);
+    assert_action(
+        actions.first(),
+        ActionType::Create,
+        "docs/guide.md",
+        None,
+        Some("# Guide\n```rust\nfn main() {}\n```\n"),
+    );
+    assert_action(
+        actions.get(1),
+        ActionType::Create,
+        "docs/other.md",
+        None,
+        Some("~~~\nnot a closer for the backtick block\n~~~\n"),
+    );
+}
+
+#[test]
+fn test_parse_closing_fence_longer_than_opener() {
+    let md = "## File: a.txt\n~~~\nA\n~~~~~\n\n## File: b.txt\n```\nB\n`````\n\n## File: c.txt\n```\nC\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 3);
+    assert_action(
+        actions.first(),
+        ActionType::Create,
+        "a.txt",
+        None,
+        Some("A\n"),
+    );
+    assert_action(
+        actions.get(1),
+        ActionType::Create,
+        "b.txt",
+        None,
+        Some("B\n"),
+    );
+    assert_action(
+        actions.get(2),
+        ActionType::Create,
+        "c.txt",
+        None,
+        Some("C\n"),
+    );
+}
+
+#[test]
+fn test_parse_stray_fence_lines_stay_inside_their_block() {
+    // Lone ``` and ~~~ lines in a ```` block must not pair up across blocks
+    let md = "## File: a.md\n````\n```\n~~~\n````\n\n## File: b.md\n````\n```\n~~~\n````\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+    assert_eq!(actions.len(), 2);
+    assert_action(
+        actions.first(),
+        ActionType::Create,
+        "a.md",
+        None,
+        Some("```\n~~~\n"),
+    );
+    assert_action(
+        actions.get(1),
+        ActionType::Create,
+        "b.md",
+        None,
+        Some("```\n~~~\n"),
+    );
+}
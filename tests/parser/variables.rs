@@ -0,0 +1,75 @@
+//! Tests for template variable substitution (`substitute_variables`).
+
+use std::collections::BTreeMap;
+use strux::{
+    parse_markdown, parse_variable_assignment, substitute_variables, Action, Content, ParseError,
+};
+
+fn vars(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+fn substituted(md: &str, pairs: &[(&str, &str)]) -> Result<Vec<Action>, ParseError> {
+    let mut actions = parse_markdown(md).expect("Parsing failed");
+    substitute_variables(&mut actions, &vars(pairs))?;
+    Ok(actions)
+}
+
+#[test]
+fn test_substitute_paths_and_content() {
+    let md = "## File: {{crate}}/src/lib.rs\n```rust\n//! {{ crate }} by {{author}}\n```\n\n## Moved File: old.txt to {{crate}}/new.txt\n";
+    let actions = substituted(md, &[("crate", "demo"), ("author", "Ada")]).unwrap();
+    assert_eq!(actions[0].path, "demo/src/lib.rs");
+    assert_eq!(
+        actions[0].content.as_ref().and_then(Content::as_text),
+        Some("//! demo by Ada\n")
+    );
+    assert_eq!(actions[1].dest_path.as_deref(), Some("demo/new.txt"));
+}
+
+#[test]
+fn test_substitute_escaped_braces_are_literal() {
+    let md = "## File: ci.yml\n```yaml\ntoken: $\\{{ secrets.TOKEN }}\nname: {{name}}\nfmt: \"{{}}\"\n```\n";
+    let actions = substituted(md, &[("name", "demo")]).unwrap();
+    assert_eq!(
+        actions[0].content.as_ref().and_then(Content::as_text),
+        Some("token: ${{ secrets.TOKEN }}\nname: demo\nfmt: \"{{}}\"\n")
+    );
+}
+
+#[test]
+fn test_substitute_unknown_variable_reports_action_position() {
+    let md = "## File: a.txt\n```\nfine\n```\n\n## File: b.txt\n```\n{{missing}}\n```\n";
+    match substituted(md, &[("other", "x")]) {
+        Err(ParseError::UnknownVariable { name, pos }) => {
+            assert_eq!(name, "missing");
+            assert_eq!(pos, md.find("## File: b.txt").unwrap());
+        }
+        other => panic!("Expected UnknownVariable, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_substitute_leaves_binary_content_alone() {
+    let md = "## File: {{name}}.bin\n```base64\nAAEC\n```\n";
+    let actions = substituted(md, &[("name", "data")]).unwrap();
+    assert_eq!(actions[0].path, "data.bin");
+    assert_eq!(actions[0].content, Some(Content::Binary(vec![0, 1, 2])));
+}
+
+#[test]
+fn test_parse_variable_assignment() {
+    assert_eq!(
+        parse_variable_assignment(" project.name = My App "),
+        Ok(("project.name".to_string(), "My App".to_string()))
+    );
+    assert_eq!(
+        parse_variable_assignment("url=a=b"),
+        Ok(("url".to_string(), "a=b".to_string()))
+    );
+    assert!(parse_variable_assignment("no_equals").is_err());
+    assert!(parse_variable_assignment("1st=x").is_err());
+}
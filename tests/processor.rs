@@ -7,16 +7,34 @@ mod common;
 // Declare the specific test modules (tests/processor/*.rs)
 #[path = "processor/append_prepend.rs"] // ADDED
 mod append_prepend;
+#[path = "processor/atomic.rs"]
+mod atomic;
 #[path = "processor/base_dir.rs"]
 mod base_dir;
+#[path = "processor/binary.rs"]
+mod binary;
+#[path = "processor/check.rs"]
+mod check;
+#[path = "processor/copy_file.rs"]
+mod copy_file;
 #[path = "processor/create.rs"]
 mod create;
 #[path = "processor/delete.rs"]
 mod delete;
+#[path = "processor/directory.rs"]
+mod directory;
+#[path = "processor/dry_run.rs"]
+mod dry_run;
 #[path = "processor/duplicates.rs"]
 mod duplicates;
+#[path = "processor/edit_file.rs"]
+mod edit_file;
 #[path = "processor/errors.rs"]
 mod errors;
+#[path = "processor/file_mode.rs"]
+mod file_mode;
+#[path = "processor/insert.rs"]
+mod insert;
 #[path = "processor/interactions.rs"]
 mod interactions;
 #[path = "processor/meta_readme.rs"]
@@ -31,6 +49,16 @@ mod multi_create;
 mod multi_delete;
 #[path = "processor/overwrite_skip.rs"]
 mod overwrite_skip;
+#[path = "processor/patch_file.rs"]
+mod patch_file;
+#[path = "processor/preview.rs"]
+mod preview;
+#[path = "processor/reporter.rs"]
+mod reporter;
+#[path = "processor/symlink.rs"]
+mod symlink;
+#[path = "processor/undo.rs"]
+mod undo;
 // REMOVED: safety tests are now unit tests
 // #[path = "processor/safety.rs"]
 // mod safety;
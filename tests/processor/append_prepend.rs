@@ -2,6 +2,10 @@
 
 use assert_fs::prelude::*;
 use predicates::prelude::*;
+use strux::{
+    parse_markdown, process_actions_with, ActionOutcome, AppendStatus, NullReporter, PrependStatus,
+    ProcessOptions,
+};
 // Use helpers from the top-level test_common module
 use crate::test_common::{run_processor, setup_temp_dir, setup_temp_dir_with_files};
 // Use the helper from this module's common
@@ -154,3 +158,77 @@ fn test_process_multiple_appends_and_prepends() {
         &summary, 0, 0, 0, 0, 0, 2, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
     );
 }
+
+fn idempotent() -> ProcessOptions {
+    ProcessOptions {
+        idempotent: true,
+        ..ProcessOptions::default()
+    }
+}
+
+#[test]
+fn test_process_idempotent_append_and_prepend_skip_present_content() {
+    let temp_dir = setup_temp_dir_with_files(&[("log.txt", "header\nbody\nfooter\n")]);
+    let md = "\n## Prepend File: log.txt\n```\nheader\n```\n\n## Append File: log.txt\n```\nfooter\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+
+    let report = process_actions_with(temp_dir.path(), actions, &idempotent(), &NullReporter)
+        .expect("Processing failed");
+
+    temp_dir.child("log.txt").assert("header\nbody\nfooter\n");
+    assert!(matches!(
+        report.outcomes[0],
+        ActionOutcome::Prepend(PrependStatus::SkippedAlreadyPresent)
+    ));
+    assert!(matches!(
+        report.outcomes[1],
+        ActionOutcome::Append(AppendStatus::SkippedAlreadyPresent)
+    ));
+    assert_eq!(report.summary.skipped_prepend_present, 1);
+    assert_eq!(report.summary.skipped_append_present, 1);
+}
+
+#[test]
+fn test_process_idempotent_append_is_applied_once() {
+    let temp_dir = setup_temp_dir();
+    let md = "\n## Append File: notes.md\n```\n- item\n```\n";
+
+    // The first run creates the file, the second finds the block already there
+    for _ in 0..2 {
+        let actions = parse_markdown(md).expect("Parsing failed");
+        process_actions_with(temp_dir.path(), actions, &idempotent(), &NullReporter)
+            .expect("Processing failed");
+    }
+
+    temp_dir.child("notes.md").assert("- item\n");
+}
+
+#[test]
+fn test_process_idempotent_several_blocks_are_applied_once() {
+    let temp_dir = setup_temp_dir_with_files(&[("log.txt", "base\n")]);
+    let md = "\n## Append File: log.txt\n```\nX\n```\n\n## Append File: log.txt\n```\nY\n```\n\n\
+              ## Prepend File: log.txt\n```\nP\n```\n\n## Prepend File: log.txt\n```\nQ\n```\n";
+
+    // On the rerun, X is found followed by Y, and P preceded by Q
+    for _ in 0..2 {
+        let actions = parse_markdown(md).expect("Parsing failed");
+        process_actions_with(temp_dir.path(), actions, &idempotent(), &NullReporter)
+            .expect("Processing failed");
+    }
+
+    temp_dir.child("log.txt").assert("Q\nP\nbase\nX\nY\n");
+}
+
+#[test]
+fn test_process_idempotent_append_needs_exact_block_at_end() {
+    // The block is in the file, but not at its end: it is appended again
+    let temp_dir = setup_temp_dir_with_files(&[("a.txt", "x\nmore\n")]);
+    let md = "\n## Append File: a.txt\n```\nx\n```\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+
+    let report = process_actions_with(temp_dir.path(), actions, &idempotent(), &NullReporter)
+        .expect("Processing failed");
+
+    temp_dir.child("a.txt").assert("x\nmore\nx\n");
+    assert_eq!(report.summary.appended, 1);
+}
@@ -0,0 +1,102 @@
+//! Tests for atomic processing (`ProcessOptions::atomic`) and rollback.
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use strux::{parse_markdown, process_actions_with, ActionOutcome, NullReporter, ProcessOptions};
+// Use helpers from the top-level test_common module
+use crate::test_common::setup_temp_dir_with_files;
+
+fn atomic() -> ProcessOptions {
+    ProcessOptions {
+        overwrite: true,
+        atomic: true,
+        ..ProcessOptions::default()
+    }
+}
+
+/// Ends with an action that fails because 'blocker' is a file, not a directory.
+const FAILING_RUN: &str = "\
+## File: existing.txt\n```\nnew content\n```\n\n\
+## Append File: log.txt\n```\nappended\n```\n\n\
+## Deleted File: doomed.txt\n\n\
+## Moved File: old/name.txt to renamed/name.txt\n\n\
+## File: brand/new/dir/file.txt\n```\nfresh\n```\n\n\
+## File: blocker/file.txt\n```\nnever\n```\n\n\
+## File: after_failure.txt\n```\nnot reached\n```\n";
+
+#[test]
+fn test_atomic_failure_restores_pre_run_state() {
+    let temp_dir = setup_temp_dir_with_files(&[
+        ("existing.txt", "old content\n"),
+        ("log.txt", "line 1\n"),
+        ("doomed.txt", "keep me\n"),
+        ("old/name.txt", "moving\n"),
+        ("blocker", "a file"),
+    ]);
+    let actions = parse_markdown(FAILING_RUN).unwrap();
+
+    let report = process_actions_with(temp_dir.path(), actions, &atomic(), &NullReporter)
+        .expect("Processing should not fail overall");
+
+    assert!(report.rolled_back);
+    assert_eq!(report.summary.failed_parent_isdir, 1);
+    // Processing stopped at the failing action, but every action is still listed
+    assert_eq!(report.outcomes.len(), 7);
+    assert!(matches!(report.outcomes[0], ActionOutcome::RolledBack(_)));
+    assert_eq!(report.outcomes[5].label(), "failed_parent_isdir");
+    assert!(matches!(report.outcomes[6], ActionOutcome::NotRun));
+
+    temp_dir.child("existing.txt").assert("old content\n");
+    temp_dir.child("log.txt").assert("line 1\n");
+    temp_dir.child("doomed.txt").assert("keep me\n");
+    temp_dir.child("old/name.txt").assert("moving\n");
+    temp_dir.child("blocker").assert("a file");
+    temp_dir.child("renamed").assert(predicate::path::missing());
+    temp_dir.child("brand").assert(predicate::path::missing());
+    temp_dir
+        .child("after_failure.txt")
+        .assert(predicate::path::missing());
+}
+
+#[test]
+fn test_atomic_success_keeps_changes() {
+    let temp_dir = setup_temp_dir_with_files(&[("log.txt", "line 1\n")]);
+    let md = "## Append File: log.txt\n```\nline 2\n```\n\n## File: dir/a.txt\n```\nA\n```\n";
+    let actions = parse_markdown(md).unwrap();
+
+    let report = process_actions_with(temp_dir.path(), actions, &atomic(), &NullReporter)
+        .expect("Processing should not fail overall");
+
+    assert!(!report.rolled_back);
+    temp_dir.child("log.txt").assert("line 1\nline 2\n");
+    temp_dir.child("dir/a.txt").assert("A\n");
+}
+
+#[test]
+fn test_atomic_rollback_removes_created_base_directory() {
+    let temp_dir = setup_temp_dir_with_files(&[]);
+    let base = temp_dir.child("out/nested");
+    let md = "## File: a.txt\n```\nA\n```\n\n## File: ../escape.txt\n```\nX\n```\n";
+    let actions = parse_markdown(md).unwrap();
+
+    let report = process_actions_with(base.path(), actions, &atomic(), &NullReporter)
+        .expect("Processing should not fail overall");
+
+    assert!(report.rolled_back);
+    assert_eq!(report.summary.failed_unsafe, 1);
+    temp_dir.child("out").assert(predicate::path::missing());
+}
+
+#[test]
+fn test_non_atomic_failure_keeps_earlier_changes() {
+    let temp_dir = setup_temp_dir_with_files(&[("blocker", "a file")]);
+    let md = "## File: a.txt\n```\nA\n```\n\n## File: blocker/b.txt\n```\nB\n```\n";
+    let actions = parse_markdown(md).unwrap();
+    let options = ProcessOptions::default();
+
+    let report = process_actions_with(temp_dir.path(), actions, &options, &NullReporter)
+        .expect("Processing should not fail overall");
+
+    assert!(!report.rolled_back);
+    temp_dir.child("a.txt").assert("A\n");
+}
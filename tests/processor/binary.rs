@@ -0,0 +1,67 @@
+//! Tests for writing, checking and previewing binary content from `base64` blocks.
+
+use assert_fs::prelude::*;
+use strux::{check_actions, parse_markdown, preview_actions, NullReporter, DEFAULT_PATCH_FUZZ};
+// Use helpers from the top-level test_common module
+use crate::test_common::{run_processor, setup_temp_dir, setup_temp_dir_with_files};
+
+const PNG_HEADER: [u8; 10] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0xFF];
+const PNG_MD: &str = "## File: assets/icon.png\n```base64\niVBORw0KGgoA/w==\n```\n";
+
+#[test]
+fn test_process_create_writes_decoded_bytes() {
+    let temp_dir = setup_temp_dir();
+
+    let (summary, _) = run_processor(PNG_MD, &temp_dir, false).expect("Processing failed");
+
+    assert_eq!(summary.created, 1);
+    let written = std::fs::read(temp_dir.child("assets/icon.png").path()).unwrap();
+    assert_eq!(written, PNG_HEADER);
+}
+
+#[test]
+fn test_process_append_and_prepend_binary_bytes() {
+    let temp_dir = setup_temp_dir_with_files(&[("data.bin", "mid")]);
+    let md = "## Append File: data.bin\n```base64\nAAEC\n```\n\n## Prepend File: data.bin\n```base64\n/w==\n```\n";
+
+    let (summary, _) = run_processor(md, &temp_dir, false).expect("Processing failed");
+
+    assert_eq!(summary.appended, 1);
+    assert_eq!(summary.prepended, 1);
+    let written = std::fs::read(temp_dir.child("data.bin").path()).unwrap();
+    assert_eq!(written, b"\xFFmid\x00\x01\x02");
+}
+
+#[test]
+fn test_check_compares_binary_content() {
+    let temp_dir = setup_temp_dir();
+    let actions = parse_markdown(PNG_MD).unwrap();
+    run_processor(PNG_MD, &temp_dir, false).expect("Processing failed");
+
+    let report =
+        check_actions(temp_dir.path(), &actions, DEFAULT_PATCH_FUZZ, &NullReporter).unwrap();
+    assert!(report.is_clean());
+
+    temp_dir
+        .child("assets/icon.png")
+        .write_binary(&PNG_HEADER[..9])
+        .unwrap();
+    let report =
+        check_actions(temp_dir.path(), &actions, DEFAULT_PATCH_FUZZ, &NullReporter).unwrap();
+    assert_eq!(report.mismatches(), 1);
+}
+
+#[test]
+fn test_preview_reports_binary_files_without_a_text_diff() {
+    let temp_dir = setup_temp_dir();
+    let actions = parse_markdown(PNG_MD).unwrap();
+
+    let preview =
+        preview_actions(temp_dir.path(), actions, false, &NullReporter).expect("Preview failed");
+
+    assert_eq!(preview.changes.len(), 1);
+    assert_eq!(
+        preview.changes[0].unified_diff(),
+        "Binary files /dev/null and b/assets/icon.png differ\n"
+    );
+}
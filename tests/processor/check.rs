@@ -0,0 +1,271 @@
+//! Tests for `check_actions` (the library side of `strux check`).
+
+use assert_fs::prelude::*;
+use strux::{
+    check_actions, parse_markdown, CheckStatus, Expectation, NullReporter, DEFAULT_PATCH_FUZZ,
+};
+// Use helpers from the top-level test_common module
+use crate::test_common::{setup_temp_dir, setup_temp_dir_with_files};
+
+fn statuses(base: &std::path::Path, md: &str) -> Vec<(String, CheckStatus)> {
+    let actions = parse_markdown(md).unwrap();
+    let report =
+        check_actions(base, &actions, DEFAULT_PATCH_FUZZ, &NullReporter).expect("Check should run");
+    report
+        .entries
+        .into_iter()
+        .map(|entry| (entry.path, entry.status))
+        .collect()
+}
+
+#[test]
+fn test_check_matching_tree_is_clean() {
+    let temp_dir = setup_temp_dir_with_files(&[("src/main.rs", "fn main() {}\n")]);
+    let actions = parse_markdown("## File: src/main.rs\n```rust\nfn main() {}\n```\n").unwrap();
+
+    let report =
+        check_actions(temp_dir.path(), &actions, DEFAULT_PATCH_FUZZ, &NullReporter).unwrap();
+
+    assert!(report.is_clean());
+    assert_eq!(report.entries.len(), 1);
+    assert_eq!(
+        report.entries[0].expected,
+        Expectation::Content(b"fn main() {}\n".to_vec())
+    );
+}
+
+#[test]
+fn test_check_reports_drift_and_missing_files() {
+    let temp_dir = setup_temp_dir_with_files(&[("a.txt", "edited\n")]);
+
+    let result = statuses(
+        temp_dir.path(),
+        "## File: a.txt\n```\noriginal\n```\n\n## File: b.txt\n```\nB\n```\n",
+    );
+
+    assert_eq!(
+        result,
+        vec![
+            ("a.txt".to_string(), CheckStatus::ContentDiffers),
+            ("b.txt".to_string(), CheckStatus::Missing),
+        ]
+    );
+}
+
+#[test]
+fn test_check_delete_expects_absence() {
+    let temp_dir = setup_temp_dir_with_files(&[("still-here.txt", "x\n")]);
+
+    let result = statuses(
+        temp_dir.path(),
+        "## Deleted File: still-here.txt\n\n## Deleted File: gone.txt\n",
+    );
+
+    assert_eq!(
+        result,
+        vec![
+            (
+                "still-here.txt".to_string(),
+                CheckStatus::UnexpectedlyExists
+            ),
+            ("gone.txt".to_string(), CheckStatus::Match),
+        ]
+    );
+}
+
+#[test]
+fn test_check_move_expects_destination_only() {
+    let temp_dir = setup_temp_dir_with_files(&[("new/name.txt", "anything\n")]);
+
+    let result = statuses(
+        temp_dir.path(),
+        "## Moved File: old/name.txt to new/name.txt\n",
+    );
+
+    assert_eq!(
+        result,
+        vec![
+            ("old/name.txt".to_string(), CheckStatus::Match),
+            ("new/name.txt".to_string(), CheckStatus::Match),
+        ]
+    );
+}
+
+#[test]
+fn test_check_folds_create_then_append() {
+    let temp_dir =
+        setup_temp_dir_with_files(&[("log.txt", "one\ntwo\n"), ("tail.txt", "x\nend\n")]);
+
+    let result = statuses(
+        temp_dir.path(),
+        "## File: log.txt\n```\none\n```\n\n## Append File: log.txt\n```\ntwo\n```\n\n\
+         ## Append File: tail.txt\n```\nend\n```\n",
+    );
+
+    assert_eq!(
+        result,
+        vec![
+            ("log.txt".to_string(), CheckStatus::Match),
+            ("tail.txt".to_string(), CheckStatus::Match),
+        ]
+    );
+}
+
+#[test]
+fn test_check_missing_base_dir_is_an_error() {
+    let temp_dir = setup_temp_dir();
+    let actions = parse_markdown("## File: a.txt\n```\nA\n```\n").unwrap();
+
+    let result = check_actions(
+        &temp_dir.path().join("nope"),
+        &actions,
+        DEFAULT_PATCH_FUZZ,
+        &NullReporter,
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_check_directory_actions() {
+    let temp_dir = setup_temp_dir_with_files(&[("new/mod.rs", "mod a;\n")]);
+    temp_dir.child("logs").create_dir_all().unwrap();
+
+    let result = statuses(
+        temp_dir.path(),
+        "## File: old/mod.rs\n```\nmod a;\n```\n\n## Moved Directory: old to new\n\n\
+         ## Directory: logs\n\n## Deleted Directory: build\n",
+    );
+
+    assert_eq!(
+        result,
+        vec![
+            ("old/mod.rs".to_string(), CheckStatus::Match),
+            ("old".to_string(), CheckStatus::Match),
+            ("new/mod.rs".to_string(), CheckStatus::Match),
+            ("new".to_string(), CheckStatus::Match),
+            ("logs".to_string(), CheckStatus::Match),
+            ("build".to_string(), CheckStatus::Match),
+        ]
+    );
+}
+
+#[test]
+fn test_check_copied_file_expects_source_and_destination() {
+    let temp_dir = setup_temp_dir_with_files(&[("LICENSE", "MIT\n"), ("a/LICENSE", "GPL\n")]);
+
+    let result = statuses(
+        temp_dir.path(),
+        "## File: LICENSE\n```\nMIT\n```\n\n## Copied File: LICENSE to a/LICENSE\n\n\
+         ## Copied File: LICENSE to b/LICENSE\n",
+    );
+
+    assert_eq!(
+        result,
+        vec![
+            ("LICENSE".to_string(), CheckStatus::Match),
+            ("a/LICENSE".to_string(), CheckStatus::ContentDiffers),
+            ("b/LICENSE".to_string(), CheckStatus::Missing),
+        ]
+    );
+}
+
+#[test]
+fn test_check_edit_file_applies_hunks_to_expected_content() {
+    let temp_dir = setup_temp_dir_with_files(&[
+        ("app.cfg", "port = 8080\n"),
+        ("other.cfg", "a\ny\n"),
+        ("stale.cfg", "a\nx\n"),
+    ]);
+
+    let result = statuses(
+        temp_dir.path(),
+        "## File: app.cfg\n```\nport = 80\n```\n\n\
+         ## Edit File: app.cfg\n```\n<<<<<<< SEARCH\n80\n=======\n8080\n>>>>>>> REPLACE\n```\n\n\
+         ## Edit File: other.cfg\n```\n<<<<<<< SEARCH\nx\n=======\ny\n>>>>>>> REPLACE\n```\n\n\
+         ## Edit File: stale.cfg\n```\n<<<<<<< SEARCH\nx\n=======\ny\n>>>>>>> REPLACE\n```\n",
+    );
+
+    // Undescribed files must hold the REPLACE text and no longer hold the SEARCH text
+    assert_eq!(
+        result,
+        vec![
+            ("app.cfg".to_string(), CheckStatus::Match),
+            ("other.cfg".to_string(), CheckStatus::Match),
+            ("stale.cfg".to_string(), CheckStatus::ContentDiffers),
+        ]
+    );
+}
+
+#[test]
+fn test_check_patch_file_applies_hunks_to_expected_content() {
+    let temp_dir = setup_temp_dir_with_files(&[("app.cfg", "host = a\nport = 8080\n")]);
+
+    let result = statuses(
+        temp_dir.path(),
+        "## File: app.cfg\n```\nhost = a\nport = 80\n```\n\n\
+         ```diff\n--- a/app.cfg\n+++ b/app.cfg\n@@ -1,2 +1,2 @@\n host = a\n-port = 80\n+port = 8080\n```\n\n\
+         ```diff\n--- /dev/null\n+++ b/new.txt\n@@ -0,0 +1 @@\n+new\n```\n",
+    );
+
+    assert_eq!(
+        result,
+        vec![
+            ("app.cfg".to_string(), CheckStatus::Match),
+            ("new.txt".to_string(), CheckStatus::Missing),
+        ]
+    );
+}
+
+#[test]
+fn test_check_patch_uses_the_given_fuzz() {
+    let temp_dir = setup_temp_dir_with_files(&[("list.txt", "a\nB\nc\n")]);
+    // The leading context line does not match, so the hunk only applies with fuzz
+    let md = "## File: list.txt\n```\na\nb\nc\n```\n\n\
+              ## Patch File: list.txt\n```\n@@ -1,3 +1,3 @@\n zero\n-b\n+B\n c\n```\n";
+    let actions = parse_markdown(md).unwrap();
+
+    let strict = check_actions(temp_dir.path(), &actions, 0, &NullReporter).unwrap();
+    assert_eq!(
+        strict.entries[0].expected,
+        Expectation::Content(b"a\nb\nc\n".to_vec())
+    );
+    assert_eq!(strict.entries[0].status, CheckStatus::ContentDiffers);
+
+    let fuzzy = check_actions(temp_dir.path(), &actions, 1, &NullReporter).unwrap();
+    assert_eq!(fuzzy.entries[0].status, CheckStatus::Match);
+}
+
+#[test]
+fn test_check_patch_and_insert_on_undescribed_files() {
+    let temp_dir = setup_temp_dir_with_files(&[
+        ("patched.txt", "a\nB\nc\n"),
+        ("unpatched.txt", "a\nb\nc\n"),
+        ("inserted.rs", "mod a;\nmod b;\n"),
+        ("crlf.rs", "mod a;\r\nmod b;\r\n"),
+        ("missing_insert.rs", "mod a;\n"),
+    ]);
+    let patch = "```\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n```\n\n";
+    let insert = "```rust\nmod b;\n```\n\n";
+
+    let result = statuses(
+        temp_dir.path(),
+        &format!(
+            "## Patch File: patched.txt\n{patch}## Patch File: unpatched.txt\n{patch}\
+             ## Insert After: inserted.rs @ mod a;\n{insert}\
+             ## Insert After: crlf.rs @ mod a;\n{insert}\
+             ## Insert After: missing_insert.rs @ mod a;\n{insert}"
+        ),
+    );
+
+    assert_eq!(
+        result,
+        vec![
+            ("patched.txt".to_string(), CheckStatus::Match),
+            ("unpatched.txt".to_string(), CheckStatus::ContentDiffers),
+            ("inserted.rs".to_string(), CheckStatus::Match),
+            ("crlf.rs".to_string(), CheckStatus::Match),
+            ("missing_insert.rs".to_string(), CheckStatus::ContentDiffers),
+        ]
+    );
+}
@@ -0,0 +1,92 @@
+//! Tests for the 'Copied File' action in the processor.
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use strux::{
+    parse_markdown, process_actions_with, undo_last_run, ActionOutcome, CopyStatus, NullReporter,
+    ProcessOptions,
+};
+// Use helpers from the top-level test_common module
+use crate::test_common::{run_processor, setup_temp_dir_with_files};
+
+#[test]
+fn test_process_copy_file_keeps_source() {
+    let temp_dir = setup_temp_dir_with_files(&[("templates/base.html", "<html></html>\n")]);
+    let md = "\n## Copied File: templates/base.html to site/pages/index.html\n";
+
+    let (summary, _) = run_processor(md, &temp_dir, false).expect("Processing failed");
+
+    temp_dir
+        .child("templates/base.html")
+        .assert("<html></html>\n");
+    temp_dir
+        .child("site/pages/index.html")
+        .assert("<html></html>\n");
+    assert_eq!(summary.copied, 1);
+    assert_eq!(summary.copied_overwritten, 0);
+}
+
+#[test]
+fn test_process_copy_file_existing_destination_needs_force() {
+    let temp_dir = setup_temp_dir_with_files(&[("a.txt", "new\n"), ("b.txt", "old\n")]);
+    let md = "\n## Copied File: a.txt to b.txt\n";
+
+    let (summary, _) = run_processor(md, &temp_dir, false).expect("Processing failed");
+    temp_dir.child("b.txt").assert("old\n");
+    assert_eq!(summary.skipped_copy_dst_exists, 1);
+
+    let (forced, _) = run_processor(md, &temp_dir, true).expect("Processing failed");
+    temp_dir.child("a.txt").assert("new\n");
+    temp_dir.child("b.txt").assert("new\n");
+    assert_eq!(forced.copied_overwritten, 1);
+}
+
+#[test]
+fn test_process_copy_file_skips() {
+    let temp_dir = setup_temp_dir_with_files(&[("a.txt", "a\n"), ("dest_dir/keep.txt", "k\n")]);
+    temp_dir.child("src_dir").create_dir_all().unwrap();
+    let md = "\n## Copied File: missing.txt to x.txt\n\n\
+              ## Copied File: src_dir to y.txt\n\n\
+              ## Copied File: a.txt to dest_dir\n";
+
+    let (summary, _) = run_processor(md, &temp_dir, true).expect("Processing failed");
+
+    temp_dir.child("x.txt").assert(predicate::path::missing());
+    temp_dir.child("y.txt").assert(predicate::path::missing());
+    temp_dir.child("dest_dir").assert(predicate::path::is_dir());
+    assert_eq!(summary.skipped_copy_src_not_found, 1);
+    assert_eq!(summary.skipped_copy_src_is_dir, 1);
+    assert_eq!(summary.skipped_copy_dst_isdir, 1);
+    assert_eq!(summary.copied, 0);
+}
+
+#[test]
+fn test_process_copy_file_dry_run_and_undo() {
+    let temp_dir = setup_temp_dir_with_files(&[("a.txt", "a\n")]);
+    let md = "\n## Copied File: a.txt to nested/b.txt\n";
+    let actions = parse_markdown(md).expect("Parsing failed");
+
+    let dry_run = ProcessOptions {
+        dry_run: true,
+        ..ProcessOptions::default()
+    };
+    let report = process_actions_with(temp_dir.path(), actions.clone(), &dry_run, &NullReporter)
+        .expect("Processing failed");
+    assert!(matches!(
+        report.outcomes[0],
+        ActionOutcome::Copy(CopyStatus::Copied)
+    ));
+    temp_dir.child("nested").assert(predicate::path::missing());
+
+    let journaled = ProcessOptions {
+        journal: true,
+        ..ProcessOptions::default()
+    };
+    process_actions_with(temp_dir.path(), actions, &journaled, &NullReporter)
+        .expect("Processing failed");
+    temp_dir.child("nested/b.txt").assert("a\n");
+
+    undo_last_run(temp_dir.path(), &NullReporter).expect("Undo should succeed");
+    temp_dir.child("a.txt").assert("a\n");
+    temp_dir.child("nested").assert(predicate::path::missing());
+}
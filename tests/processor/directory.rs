@@ -0,0 +1,267 @@
+//! Tests for the 'Directory' and 'Deleted Directory' actions in the processor.
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use strux::{
+    undo_last_run, ActionOutcome, DeleteDirectoryStatus, DirectoryStatus, NullReporter,
+    ProcessOptions,
+};
+// Use helpers from the top-level test_common module
+use crate::test_common::{run_processor, run_with, setup_temp_dir, setup_temp_dir_with_files};
+
+#[test]
+fn test_process_create_directory() {
+    let temp_dir = setup_temp_dir();
+    let md = "\n## Directory: logs\n\n## Directory: tmp/cache/nested\n";
+
+    let (summary, _) = run_processor(md, &temp_dir, false).expect("Processing failed");
+
+    temp_dir.child("logs").assert(predicate::path::is_dir());
+    temp_dir
+        .child("tmp/cache/nested")
+        .assert(predicate::path::is_dir());
+    assert_eq!(summary.dirs_created, 2);
+}
+
+#[test]
+fn test_process_create_directory_skips_existing_and_fails_on_file() {
+    let temp_dir = setup_temp_dir_with_files(&[("logs/app.log", "x\n"), ("tmp", "a file\n")]);
+    let md = "\n## Directory: logs\n\n## Directory: tmp\n";
+
+    let report = run_with(temp_dir.path(), md, &ProcessOptions::default());
+
+    assert!(matches!(
+        report.outcomes[0],
+        ActionOutcome::CreateDirectory(DirectoryStatus::SkippedExists)
+    ));
+    assert!(matches!(report.outcomes[1], ActionOutcome::Failed(_)));
+    assert_eq!(report.summary.skipped_dir_exists, 1);
+    assert_eq!(report.summary.failed_dir_target_is_file, 1);
+    temp_dir.child("logs/app.log").assert("x\n");
+    temp_dir.child("tmp").assert("a file\n");
+}
+
+#[test]
+fn test_process_delete_empty_directory() {
+    let temp_dir = setup_temp_dir();
+    temp_dir.child("empty").create_dir_all().unwrap();
+    let md = "\n## Deleted Directory: empty\n\n## Deleted Directory: missing\n";
+
+    let (summary, _) = run_processor(md, &temp_dir, false).expect("Processing failed");
+
+    temp_dir.child("empty").assert(predicate::path::missing());
+    assert_eq!(summary.dirs_deleted, 1);
+    assert_eq!(summary.skipped_dir_not_found, 1);
+}
+
+#[test]
+fn test_process_delete_non_empty_directory_requires_force() {
+    let temp_dir = setup_temp_dir_with_files(&[("build/out/app.bin", "bin\n")]);
+    let md = "\n## Deleted Directory: build\n";
+
+    let (summary, _) = run_processor(md, &temp_dir, false).expect("Processing failed");
+    assert_eq!(summary.skipped_dir_not_empty, 1);
+    temp_dir.child("build/out/app.bin").assert("bin\n");
+
+    let (summary, _) = run_processor(md, &temp_dir, true).expect("Processing failed");
+    assert_eq!(summary.dirs_deleted, 1);
+    temp_dir.child("build").assert(predicate::path::missing());
+}
+
+#[test]
+fn test_process_delete_directory_skips_files_and_base() {
+    let temp_dir = setup_temp_dir_with_files(&[("notes.txt", "keep\n")]);
+    let md = "\n## Deleted Directory: notes.txt\n\n## Deleted Directory: .\n";
+    let options = ProcessOptions {
+        overwrite: true,
+        ..ProcessOptions::default()
+    };
+
+    let report = run_with(temp_dir.path(), md, &options);
+
+    assert!(matches!(
+        report.outcomes[0],
+        ActionOutcome::DeleteDirectory(DeleteDirectoryStatus::SkippedNotADirectory)
+    ));
+    assert!(matches!(report.outcomes[1], ActionOutcome::Failed(_)));
+    assert_eq!(report.summary.failed_unsafe, 1);
+    temp_dir.child("notes.txt").assert("keep\n");
+}
+
+#[test]
+fn test_process_directory_dry_run_changes_nothing() {
+    let temp_dir = setup_temp_dir_with_files(&[("old/a.txt", "a\n")]);
+    let md = "\n## Directory: logs\n\n## Deleted Directory: old\n";
+    let options = ProcessOptions {
+        overwrite: true,
+        dry_run: true,
+        ..ProcessOptions::default()
+    };
+
+    let report = run_with(temp_dir.path(), md, &options);
+
+    assert_eq!(report.summary.dirs_created, 1);
+    assert_eq!(report.summary.dirs_deleted, 1);
+    temp_dir.child("logs").assert(predicate::path::missing());
+    temp_dir.child("old/a.txt").assert("a\n");
+}
+
+#[test]
+fn test_undo_restores_deleted_directory_tree() {
+    let temp_dir = setup_temp_dir_with_files(&[("old/a.txt", "a\n"), ("old/sub/b.txt", "b\n")]);
+    temp_dir.child("old/empty").create_dir_all().unwrap();
+    let md = "\n## Deleted Directory: old\n\n## Directory: logs\n";
+    let options = ProcessOptions {
+        overwrite: true,
+        journal: true,
+        ..ProcessOptions::default()
+    };
+
+    run_with(temp_dir.path(), md, &options);
+    temp_dir.child("old").assert(predicate::path::missing());
+
+    let report = undo_last_run(temp_dir.path(), &NullReporter).expect("Undo should succeed");
+
+    assert_eq!(report.failed, 0);
+    temp_dir.child("old/a.txt").assert("a\n");
+    temp_dir.child("old/sub/b.txt").assert("b\n");
+    temp_dir
+        .child("old/empty")
+        .assert(predicate::path::is_dir());
+    temp_dir.child("logs").assert(predicate::path::missing());
+}
+
+#[test]
+fn test_atomic_rollback_restores_deleted_directory_tree() {
+    let temp_dir = setup_temp_dir_with_files(&[("old/a.txt", "a\n"), ("blocker", "file\n")]);
+    let md = "\n## Deleted Directory: old\n\n## Directory: blocker\n";
+    let options = ProcessOptions {
+        overwrite: true,
+        atomic: true,
+        ..ProcessOptions::default()
+    };
+
+    let report = run_with(temp_dir.path(), md, &options);
+
+    assert!(report.rolled_back);
+    temp_dir.child("old/a.txt").assert("a\n");
+}
+
+#[test]
+fn test_process_move_directory() {
+    let temp_dir =
+        setup_temp_dir_with_files(&[("src/old_mod/mod.rs", "mod a;\n"), ("src/old_mod/a.rs", "")]);
+    let md = "\n## Moved Directory: src/old_mod to src/new_mod\n";
+
+    let (summary, _) = run_processor(md, &temp_dir, false).expect("Processing failed");
+
+    assert_eq!(summary.dirs_moved, 1);
+    temp_dir
+        .child("src/old_mod")
+        .assert(predicate::path::missing());
+    temp_dir.child("src/new_mod/mod.rs").assert("mod a;\n");
+    temp_dir.child("src/new_mod/a.rs").assert("");
+}
+
+#[test]
+fn test_process_move_directory_into_existing_requires_force() {
+    let temp_dir = setup_temp_dir_with_files(&[
+        ("from/a.txt", "new a\n"),
+        ("from/sub/b.txt", "b\n"),
+        ("to/a.txt", "old a\n"),
+        ("to/keep.txt", "keep\n"),
+    ]);
+    let md = "\n## Moved Directory: from to to\n";
+
+    let (summary, _) = run_processor(md, &temp_dir, false).expect("Processing failed");
+    assert_eq!(summary.skipped_dir_move_dst_exists, 1);
+    temp_dir.child("from/a.txt").assert("new a\n");
+
+    let (summary, _) = run_processor(md, &temp_dir, true).expect("Processing failed");
+    assert_eq!(summary.dirs_merged, 1);
+    temp_dir.child("from").assert(predicate::path::missing());
+    temp_dir.child("to/a.txt").assert("new a\n");
+    temp_dir.child("to/sub/b.txt").assert("b\n");
+    temp_dir.child("to/keep.txt").assert("keep\n");
+}
+
+#[test]
+fn test_process_move_directory_merge_conflict_changes_nothing() {
+    let temp_dir = setup_temp_dir_with_files(&[
+        ("from/a.txt", "a\n"),
+        ("from/sub/b.txt", "b\n"),
+        ("to/sub", "a file where a directory would go\n"),
+    ]);
+    let md = "\n## Moved Directory: from to to\n";
+
+    let (summary, _) = run_processor(md, &temp_dir, true).expect("Processing failed");
+
+    assert_eq!(summary.skipped_dir_merge_conflict, 1);
+    temp_dir.child("from/a.txt").assert("a\n");
+    temp_dir
+        .child("to/a.txt")
+        .assert(predicate::path::missing());
+}
+
+#[test]
+fn test_process_move_directory_skips() {
+    let temp_dir = setup_temp_dir_with_files(&[("dir/a.txt", "a\n"), ("file.txt", "f\n")]);
+    let md = "\n## Moved Directory: missing to x\n\n## Moved Directory: file.txt to y\n\n\
+              ## Moved Directory: dir to dir/inner\n\n## Moved Directory: dir to file.txt\n\n\
+              ## Moved Directory: dir to ../outside\n";
+    let options = ProcessOptions {
+        overwrite: true,
+        ..ProcessOptions::default()
+    };
+
+    let report = run_with(temp_dir.path(), md, &options);
+
+    assert_eq!(report.summary.skipped_dir_move_src_not_found, 1);
+    assert_eq!(report.summary.skipped_dir_move_src_not_dir, 1);
+    assert_eq!(report.summary.skipped_dir_move_into_self, 1);
+    assert_eq!(report.summary.skipped_dir_move_dst_is_file, 1);
+    assert_eq!(report.summary.failed_unsafe, 1);
+    temp_dir.child("dir/a.txt").assert("a\n");
+}
+
+#[test]
+fn test_undo_restores_merged_directory() {
+    let temp_dir = setup_temp_dir_with_files(&[
+        ("from/a.txt", "new a\n"),
+        ("from/sub/b.txt", "b\n"),
+        ("to/a.txt", "old a\n"),
+    ]);
+    let md = "\n## Moved Directory: from to to\n";
+    let options = ProcessOptions {
+        overwrite: true,
+        journal: true,
+        ..ProcessOptions::default()
+    };
+
+    run_with(temp_dir.path(), md, &options);
+    temp_dir.child("to/sub/b.txt").assert("b\n");
+
+    let report = undo_last_run(temp_dir.path(), &NullReporter).expect("Undo should succeed");
+
+    assert_eq!(report.failed, 0);
+    temp_dir.child("from/a.txt").assert("new a\n");
+    temp_dir.child("from/sub/b.txt").assert("b\n");
+    temp_dir.child("to/a.txt").assert("old a\n");
+    temp_dir.child("to/sub").assert(predicate::path::missing());
+}
+
+#[test]
+fn test_atomic_rollback_restores_moved_directory() {
+    let temp_dir = setup_temp_dir_with_files(&[("old/a/b.txt", "b\n"), ("blocker", "file\n")]);
+    let md = "\n## Moved Directory: old to new/place\n\n## Directory: blocker\n";
+    let options = ProcessOptions {
+        atomic: true,
+        ..ProcessOptions::default()
+    };
+
+    let report = run_with(temp_dir.path(), md, &options);
+
+    assert!(report.rolled_back);
+    temp_dir.child("old/a/b.txt").assert("b\n");
+    temp_dir.child("new").assert(predicate::path::missing());
+}
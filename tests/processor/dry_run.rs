@@ -0,0 +1,139 @@
+//! Tests for dry-run planning (no filesystem changes).
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use strux::{
+    parse_markdown, plan_actions, ActionOutcome, AppendStatus, CreateStatus, DeleteStatus,
+    MoveStatus,
+};
+// Use helpers from the top-level test_common module
+use crate::test_common::setup_temp_dir;
+// Use the helper from this module's common
+use super::common::*;
+
+#[test]
+fn test_plan_reports_statuses_without_writing() {
+    let temp_dir = setup_temp_dir();
+    temp_dir
+        .child("existing.txt")
+        .write_str("Original")
+        .unwrap();
+    temp_dir.child("old.txt").write_str("move me").unwrap();
+    temp_dir.child("doomed.txt").write_str("bye").unwrap();
+    let md = "\n## File: new/nested.txt\n```\nnew\n```\n\n## File: existing.txt\n```\nreplacement\n```\n\n## Moved File: old.txt to moved/old.txt\n\n## Deleted File: doomed.txt\n";
+    let actions = parse_markdown(md).unwrap();
+
+    let report = plan_actions(temp_dir.path(), actions, false).expect("Planning failed");
+
+    assert!(matches!(
+        report.outcomes[0],
+        ActionOutcome::Create(CreateStatus::Created)
+    ));
+    assert!(matches!(
+        report.outcomes[1],
+        ActionOutcome::Create(CreateStatus::SkippedExists)
+    ));
+    assert!(matches!(
+        report.outcomes[2],
+        ActionOutcome::Move(MoveStatus::Moved)
+    ));
+    assert!(matches!(
+        report.outcomes[3],
+        ActionOutcome::Delete(DeleteStatus::Deleted)
+    ));
+    let summary = &report.summary;
+    assert_summary(
+        summary, 1, 0, 1, 1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    );
+
+    // Nothing on disk changed
+    temp_dir.child("new").assert(predicate::path::missing());
+    temp_dir.child("moved").assert(predicate::path::missing());
+    temp_dir.child("existing.txt").assert("Original");
+    temp_dir.child("old.txt").assert("move me");
+    temp_dir.child("doomed.txt").assert("bye");
+}
+
+#[test]
+fn test_plan_force_reports_overwrite() {
+    let temp_dir = setup_temp_dir();
+    temp_dir
+        .child("existing.txt")
+        .write_str("Original")
+        .unwrap();
+    let md = "\n## File: existing.txt\n```\nreplacement\n```\n";
+    let actions = parse_markdown(md).unwrap();
+
+    let report = plan_actions(temp_dir.path(), actions, true).expect("Planning failed");
+
+    assert!(matches!(
+        report.outcomes[0],
+        ActionOutcome::Create(CreateStatus::Overwritten)
+    ));
+    temp_dir.child("existing.txt").assert("Original");
+}
+
+#[test]
+fn test_plan_does_not_create_base_directory() {
+    let temp_dir = setup_temp_dir();
+    let base = temp_dir.child("not_yet_created");
+    let md = "\n## File: a/b.txt\n```\ncontent\n```\n\n## File: ../escape.txt\n```\nnope\n```\n";
+    let actions = parse_markdown(md).unwrap();
+
+    let report = plan_actions(base.path(), actions, false).expect("Planning failed");
+
+    assert!(matches!(
+        report.outcomes[0],
+        ActionOutcome::Create(CreateStatus::Created)
+    ));
+    assert!(matches!(report.outcomes[1], ActionOutcome::Failed(_)));
+    assert_eq!(report.summary.failed_unsafe, 1);
+    base.assert(predicate::path::missing());
+}
+
+#[test]
+fn test_plan_detects_directory_conflicts() {
+    let temp_dir = setup_temp_dir();
+    temp_dir.child("target_dir").create_dir_all().unwrap();
+    temp_dir.child("parent_file").write_str("file").unwrap();
+    let md = "\n## File: target_dir\n```\nx\n```\n\n## Append File: parent_file/child.txt\n```\ny\n```\n";
+    let actions = parse_markdown(md).unwrap();
+
+    let report = plan_actions(temp_dir.path(), actions, false).expect("Planning failed");
+
+    assert_eq!(report.summary.failed_isdir_create, 1);
+    assert_eq!(report.summary.failed_parent_isdir, 1);
+    temp_dir
+        .child("parent_file")
+        .assert(predicate::path::is_file());
+}
+
+#[test]
+fn test_plan_sees_changes_of_earlier_actions() {
+    let temp_dir = setup_temp_dir();
+    let md = "## File: a.txt\n```\nA\n```\n\n## Moved File: a.txt to b.txt\n\n## Append File: b.txt\n```\nB\n```\n\n## Deleted File: b.txt\n";
+    let actions = parse_markdown(md).unwrap();
+
+    let report = plan_actions(temp_dir.path(), actions, false).expect("Planning failed");
+
+    assert!(matches!(
+        report.outcomes[0],
+        ActionOutcome::Create(CreateStatus::Created)
+    ));
+    assert!(matches!(
+        report.outcomes[1],
+        ActionOutcome::Move(MoveStatus::Moved)
+    ));
+    assert!(matches!(
+        report.outcomes[2],
+        ActionOutcome::Append(AppendStatus::Appended)
+    ));
+    assert!(matches!(
+        report.outcomes[3],
+        ActionOutcome::Delete(DeleteStatus::Deleted)
+    ));
+    assert!(!report.summary.has_failures());
+
+    temp_dir.child("a.txt").assert(predicate::path::missing());
+    temp_dir.child("b.txt").assert(predicate::path::missing());
+}
@@ -0,0 +1,140 @@
+//! Tests for the 'Edit File' (SEARCH/REPLACE) action in the processor.
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use strux::{ActionOutcome, EditStatus, ProcessError, ProcessOptions};
+// Use helpers from the top-level test_common module
+use crate::test_common::{run_processor, run_with, setup_temp_dir, setup_temp_dir_with_files};
+
+fn edit_md(path: &str, hunks: &[(&str, &str)]) -> String {
+    let mut block = String::new();
+    for (search, replace) in hunks {
+        block.push_str(&format!(
+            "<<<<<<< SEARCH\n{}=======\n{}>>>>>>> REPLACE\n",
+            search, replace
+        ));
+    }
+    format!("\n## Edit File: {}\n```\n{}```\n", path, block)
+}
+
+#[test]
+fn test_process_edit_applies_hunks_in_order() {
+    let temp_dir =
+        setup_temp_dir_with_files(&[("main.rs", "fn main() {\n    a();\n    b();\n}\n")]);
+    let md = edit_md(
+        "main.rs",
+        &[
+            ("    a();\n", "    a2();\n"),
+            ("    a2();\n    b();\n", "    c();\n"),
+        ],
+    );
+
+    let (summary, _) = run_processor(&md, &temp_dir, false).expect("Processing failed");
+
+    temp_dir
+        .child("main.rs")
+        .assert("fn main() {\n    c();\n}\n");
+    assert_eq!(summary.edited, 1);
+}
+
+#[test]
+fn test_process_edit_empty_replace_deletes_lines() {
+    let temp_dir = setup_temp_dir_with_files(&[("list.txt", "a\nb\nc\n")]);
+    let md = edit_md("list.txt", &[("b\n", "")]);
+
+    run_processor(&md, &temp_dir, false).expect("Processing failed");
+
+    temp_dir.child("list.txt").assert("a\nc\n");
+}
+
+#[test]
+fn test_process_edit_matches_crlf_files() {
+    let temp_dir = setup_temp_dir_with_files(&[("win.txt", "one\r\ntwo\r\n")]);
+    let md = edit_md("win.txt", &[("two\n", "2\n")]);
+
+    run_processor(&md, &temp_dir, false).expect("Processing failed");
+
+    temp_dir.child("win.txt").assert("one\r\n2\r\n");
+}
+
+#[test]
+fn test_process_edit_missing_search_fails_without_writing() {
+    let temp_dir = setup_temp_dir_with_files(&[("a.txt", "x\ny\n")]);
+    // The first hunk matches, the second does not: nothing may be written
+    let md = edit_md("a.txt", &[("x\n", "X\n"), ("nope\n", "z\n")]);
+
+    let report = run_with(temp_dir.path(), &md, &ProcessOptions::default());
+
+    temp_dir.child("a.txt").assert("x\ny\n");
+    assert!(matches!(
+        report.outcomes[0],
+        ActionOutcome::Failed(ProcessError::EditSearchMismatch {
+            hunk: 2,
+            matches: 0,
+            ..
+        })
+    ));
+    assert_eq!(report.summary.failed_edit_no_match, 1);
+    assert!(report.summary.has_failures());
+}
+
+#[test]
+fn test_process_edit_ambiguous_search_fails() {
+    let temp_dir = setup_temp_dir_with_files(&[("a.txt", "dup\nother\ndup\n")]);
+    let md = edit_md("a.txt", &[("dup\n", "once\n")]);
+
+    let report = run_with(temp_dir.path(), &md, &ProcessOptions::default());
+
+    temp_dir.child("a.txt").assert("dup\nother\ndup\n");
+    assert!(matches!(
+        report.outcomes[0],
+        ActionOutcome::Failed(ProcessError::EditSearchMismatch { matches: 2, .. })
+    ));
+    assert_eq!(report.summary.failed_edit_ambiguous, 1);
+}
+
+#[test]
+fn test_process_edit_invalid_block_fails() {
+    let temp_dir = setup_temp_dir_with_files(&[("a.txt", "x\n")]);
+    let md = "\n## Edit File: a.txt\n```\n<<<<<<< SEARCH\nx\n=======\ny\n```\n";
+
+    let report = run_with(temp_dir.path(), md, &ProcessOptions::default());
+
+    temp_dir.child("a.txt").assert("x\n");
+    assert!(matches!(
+        report.outcomes[0],
+        ActionOutcome::Failed(ProcessError::InvalidEditBlock { .. })
+    ));
+    assert_eq!(report.summary.failed_edit_invalid, 1);
+}
+
+#[test]
+fn test_process_edit_missing_file_fails() {
+    let temp_dir = setup_temp_dir();
+    let md = edit_md("missing.txt", &[("x\n", "y\n")]);
+
+    let report = run_with(temp_dir.path(), &md, &ProcessOptions::default());
+
+    assert_eq!(report.summary.failed_io, 1);
+    temp_dir
+        .child("missing.txt")
+        .assert(predicate::path::missing());
+}
+
+#[test]
+fn test_process_edit_dry_run_reports_outcome_without_writing() {
+    let temp_dir = setup_temp_dir_with_files(&[("a.txt", "x\n")]);
+    let md = edit_md("a.txt", &[("x\n", "y\n")]);
+    let options = ProcessOptions {
+        dry_run: true,
+        ..ProcessOptions::default()
+    };
+
+    let report = run_with(temp_dir.path(), &md, &options);
+
+    assert!(matches!(
+        report.outcomes[0],
+        ActionOutcome::Edit(EditStatus::Edited)
+    ));
+    temp_dir.child("a.txt").assert("x\n");
+}
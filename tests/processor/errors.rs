@@ -2,6 +2,9 @@
 
 use assert_fs::prelude::*;
 use predicates::prelude::*;
+use strux::{
+    parse_markdown, process_actions_with, ActionOutcome, NullReporter, ProcessError, ProcessOptions,
+};
 // Use helpers from the top-level test_common module
 use crate::test_common::{read_file_content, run_processor, setup_temp_dir};
 // Use the helper from this module's common
@@ -77,8 +80,11 @@ fn test_process_invalid_path_format_in_action() {
         action_type: strux::ActionType::Create,
         path: "bad//path.txt".to_string(),
         dest_path: None,
-        content: Some("content".to_string()),
+        content: Some(strux::Content::Text("content".to_string())),
+        anchor: None,
+        mode: None,
         original_pos: 0,
+        source_file: None,
     }];
 
     let summary = strux::process_actions(temp_dir.path(), actions, false)
@@ -101,8 +107,11 @@ fn test_process_empty_path_string() {
         action_type: strux::ActionType::Create,
         path: "".to_string(),
         dest_path: None,
-        content: Some("content".to_string()),
+        content: Some(strux::Content::Text("content".to_string())),
+        anchor: None,
+        mode: None,
         original_pos: 0,
+        source_file: None,
     }];
 
     let summary = strux::process_actions(temp_dir.path(), actions, false)
@@ -114,3 +123,25 @@ fn test_process_empty_path_string() {
         &summary, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0,
     );
 }
+
+#[test]
+fn test_process_unresolved_include_fails_without_touching_files() {
+    let temp_dir = setup_temp_dir();
+    let md = "## Include: ../outside.md\n\n## File: a.txt\n```\nA\n```\n";
+    let actions = parse_markdown(md).unwrap();
+    let report = process_actions_with(
+        temp_dir.path(),
+        actions,
+        &ProcessOptions::default(),
+        &NullReporter,
+    )
+    .expect("Processing should not fail overall");
+
+    assert!(matches!(
+        &report.outcomes[0],
+        ActionOutcome::Failed(ProcessError::UnresolvedInclude { path })
+            if path == "../outside.md"
+    ));
+    assert_eq!(report.summary.error_other, 1);
+    temp_dir.child("a.txt").assert("A\n");
+}
@@ -0,0 +1,119 @@
+//! Tests for file modes (`mode=` fence attribute) and `auto_exec` in the processor.
+#![cfg(unix)]
+
+use assert_fs::prelude::*;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use strux::{parse_markdown, process_actions_with, undo_last_run, NullReporter, ProcessOptions};
+// Use helpers from the top-level test_common module
+use crate::test_common::{run_processor, run_with, setup_temp_dir, setup_temp_dir_with_files};
+
+fn mode_of(path: &Path) -> u32 {
+    std::fs::metadata(path).unwrap().permissions().mode() & 0o777
+}
+
+#[test]
+fn test_process_create_sets_mode() {
+    let temp_dir = setup_temp_dir();
+    let md = "## File: scripts/run.sh\n```bash mode=750\n#!/bin/sh\necho hi\n```\n";
+
+    run_processor(md, &temp_dir, false).expect("Processing failed");
+
+    let script = temp_dir.child("scripts/run.sh");
+    script.assert("#!/bin/sh\necho hi\n");
+    assert_eq!(mode_of(script.path()), 0o750);
+}
+
+#[test]
+fn test_process_append_and_prepend_set_mode() {
+    let temp_dir = setup_temp_dir_with_files(&[("tool", "echo body\n"), ("log.txt", "a\n")]);
+    std::fs::set_permissions(
+        temp_dir.child("log.txt").path(),
+        std::fs::Permissions::from_mode(0o644),
+    )
+    .unwrap();
+    let md = "## Prepend File: tool\n```sh mode=755\n#!/bin/sh\n```\n\n## Append File: log.txt\n```text mode=600\nb\n```\n";
+
+    run_processor(md, &temp_dir, false).expect("Processing failed");
+
+    temp_dir.child("tool").assert("#!/bin/sh\necho body\n");
+    assert_eq!(mode_of(temp_dir.child("tool").path()), 0o755);
+    temp_dir.child("log.txt").assert("a\nb\n");
+    assert_eq!(mode_of(temp_dir.child("log.txt").path()), 0o600);
+}
+
+#[test]
+fn test_process_auto_exec_marks_shebang_files() {
+    let temp_dir = setup_temp_dir();
+    let md = "## File: run.sh\n```sh\n#!/bin/sh\n```\n\n## File: notes.txt\n```text\n# not a script\n```\n\n## File: locked.sh\n```sh mode=600\n#!/bin/sh\n```\n";
+    let options = ProcessOptions {
+        auto_exec: true,
+        ..Default::default()
+    };
+
+    run_with(temp_dir.path(), md, &options);
+
+    let run = mode_of(temp_dir.child("run.sh").path());
+    assert_eq!(run & 0o111, (run & 0o444) >> 2, "read bits gain exec bits");
+    assert_ne!(run & 0o100, 0);
+    assert_eq!(mode_of(temp_dir.child("notes.txt").path()) & 0o111, 0);
+    // An explicit mode wins over --auto-exec
+    assert_eq!(mode_of(temp_dir.child("locked.sh").path()), 0o600);
+}
+
+#[test]
+fn test_process_auto_exec_off_by_default_and_dry_run_untouched() {
+    let temp_dir = setup_temp_dir_with_files(&[("run.sh", "#!/bin/sh\n")]);
+    std::fs::set_permissions(
+        temp_dir.child("run.sh").path(),
+        std::fs::Permissions::from_mode(0o644),
+    )
+    .unwrap();
+    let md = "## Append File: run.sh\n```sh\necho hi\n```\n";
+
+    run_processor(md, &temp_dir, false).expect("Processing failed");
+    assert_eq!(mode_of(temp_dir.child("run.sh").path()), 0o644);
+
+    let options = ProcessOptions {
+        auto_exec: true,
+        dry_run: true,
+        ..Default::default()
+    };
+    run_with(temp_dir.path(), md, &options);
+    assert_eq!(mode_of(temp_dir.child("run.sh").path()), 0o644);
+}
+
+#[test]
+fn test_undo_and_atomic_rollback_restore_modes() {
+    let temp_dir = setup_temp_dir_with_files(&[("run.sh", "#!/bin/sh\n")]);
+    temp_dir.child("a_dir").create_dir_all().unwrap();
+    let script = temp_dir.child("run.sh");
+    std::fs::set_permissions(script.path(), std::fs::Permissions::from_mode(0o644)).unwrap();
+    let md = "## Append File: run.sh\n```sh\necho hi\n```\n";
+
+    // A mode change is undone even though the content is restored byte for byte
+    let journaled = ProcessOptions {
+        auto_exec: true,
+        journal: true,
+        ..Default::default()
+    };
+    run_with(temp_dir.path(), md, &journaled);
+    assert_eq!(mode_of(script.path()), 0o755);
+    undo_last_run(temp_dir.path(), &NullReporter).expect("Undo failed");
+    script.assert("#!/bin/sh\n");
+    assert_eq!(mode_of(script.path()), 0o644);
+
+    // A later failure rolls the mode back too
+    let failing = format!("{}\n## File: a_dir\n```\nx\n```\n", md);
+    let atomic = ProcessOptions {
+        auto_exec: true,
+        atomic: true,
+        ..Default::default()
+    };
+    let actions = parse_markdown(&failing).expect("Parsing failed");
+    let report = process_actions_with(temp_dir.path(), actions, &atomic, &NullReporter)
+        .expect("Processing failed");
+    assert!(report.rolled_back);
+    script.assert("#!/bin/sh\n");
+    assert_eq!(mode_of(script.path()), 0o644);
+}
@@ -0,0 +1,140 @@
+//! Tests for the 'Insert After' and 'Insert Before' actions in the processor.
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use strux::{ActionOutcome, InsertStatus, ProcessError, ProcessOptions};
+// Use helpers from the top-level test_common module
+use crate::test_common::{run_processor, run_with, setup_temp_dir, setup_temp_dir_with_files};
+
+const LIB: &str = "mod a;\nmod b;\n\nfn main() {}\n// ROUTES END\n";
+
+fn insert_md(action: &str, path: &str, anchor: &str, content: &str) -> String {
+    format!(
+        "\n## {}: {} @ {}\n```\n{}```\n",
+        action, path, anchor, content
+    )
+}
+
+#[test]
+fn test_process_insert_after_last_regex_match() {
+    let temp_dir = setup_temp_dir_with_files(&[("lib.rs", LIB)]);
+    let md = insert_md("Insert After", "lib.rs", "/^mod /", "mod c;\n");
+
+    let (summary, _) = run_processor(&md, &temp_dir, false).expect("Processing failed");
+
+    temp_dir
+        .child("lib.rs")
+        .assert("mod a;\nmod b;\nmod c;\n\nfn main() {}\n// ROUTES END\n");
+    assert_eq!(summary.inserted, 1);
+}
+
+#[test]
+fn test_process_insert_before_first_literal_match() {
+    let temp_dir = setup_temp_dir_with_files(&[("lib.rs", LIB)]);
+    // Literal anchors ignore surrounding whitespace
+    let md = insert_md("Insert Before", "lib.rs", "`mod b;  `", "mod a2;\n");
+
+    run_processor(&md, &temp_dir, false).expect("Processing failed");
+
+    temp_dir
+        .child("lib.rs")
+        .assert("mod a;\nmod a2;\nmod b;\n\nfn main() {}\n// ROUTES END\n");
+}
+
+#[test]
+fn test_process_insert_before_marker_comment() {
+    let temp_dir = setup_temp_dir_with_files(&[("lib.rs", LIB)]);
+    let md = insert_md(
+        "Insert Before",
+        "lib.rs",
+        "// ROUTES END",
+        "route(\"/x\");\n",
+    );
+
+    run_processor(&md, &temp_dir, false).expect("Processing failed");
+
+    temp_dir
+        .child("lib.rs")
+        .assert("mod a;\nmod b;\n\nfn main() {}\nroute(\"/x\");\n// ROUTES END\n");
+}
+
+#[test]
+fn test_process_insert_after_last_line_without_newline() {
+    let temp_dir = setup_temp_dir_with_files(&[("a.txt", "x\r\ny")]);
+    let md = insert_md("Insert After", "a.txt", "y", "z\n");
+
+    run_processor(&md, &temp_dir, false).expect("Processing failed");
+
+    // Line endings follow the file, and the missing final newline stays missing
+    temp_dir.child("a.txt").assert("x\r\ny\r\nz");
+}
+
+#[test]
+fn test_process_insert_missing_anchor_fails_without_writing() {
+    let temp_dir = setup_temp_dir_with_files(&[("lib.rs", LIB)]);
+    let md = insert_md("Insert After", "lib.rs", "/^use /", "use std::io;\n");
+
+    let report = run_with(temp_dir.path(), &md, &ProcessOptions::default());
+
+    temp_dir.child("lib.rs").assert(LIB);
+    assert!(matches!(
+        report.outcomes[0],
+        ActionOutcome::Failed(ProcessError::AnchorNotFound { .. })
+    ));
+    assert_eq!(report.summary.failed_insert_no_anchor, 1);
+    assert!(report.summary.has_failures());
+}
+
+#[test]
+fn test_process_insert_missing_anchor_with_force_appends() {
+    let temp_dir = setup_temp_dir_with_files(&[("a.txt", "x\ny")]);
+    let md = insert_md("Insert Before", "a.txt", "nothing", "z\n");
+
+    let (summary, _) = run_processor(&md, &temp_dir, true).expect("Processing failed");
+
+    temp_dir.child("a.txt").assert("x\ny\nz");
+    assert_eq!(summary.inserted_at_end, 1);
+    assert!(!summary.has_failures());
+}
+
+#[test]
+fn test_process_insert_invalid_regex_fails() {
+    let temp_dir = setup_temp_dir_with_files(&[("a.txt", "x\n")]);
+    let md = insert_md("Insert After", "a.txt", "/(unclosed/", "y\n");
+
+    let report = run_with(temp_dir.path(), &md, &ProcessOptions::default());
+
+    temp_dir.child("a.txt").assert("x\n");
+    assert_eq!(report.summary.failed_insert_invalid_anchor, 1);
+}
+
+#[test]
+fn test_process_insert_missing_file_fails() {
+    let temp_dir = setup_temp_dir();
+    let md = insert_md("Insert After", "missing.txt", "x", "y\n");
+
+    let report = run_with(temp_dir.path(), &md, &ProcessOptions::default());
+
+    assert_eq!(report.summary.failed_io, 1);
+    temp_dir
+        .child("missing.txt")
+        .assert(predicate::path::missing());
+}
+
+#[test]
+fn test_process_insert_dry_run_reports_outcome_without_writing() {
+    let temp_dir = setup_temp_dir_with_files(&[("a.txt", "x\n")]);
+    let md = insert_md("Insert After", "a.txt", "x", "y\n");
+    let options = ProcessOptions {
+        dry_run: true,
+        ..ProcessOptions::default()
+    };
+
+    let report = run_with(temp_dir.path(), &md, &options);
+
+    assert!(matches!(
+        report.outcomes[0],
+        ActionOutcome::Insert(InsertStatus::Inserted)
+    ));
+    temp_dir.child("a.txt").assert("x\n");
+}
@@ -0,0 +1,173 @@
+//! Tests for the 'Patch File' (unified diff) action in the processor.
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use strux::{
+    parse_markdown, preview_actions_with, ActionOutcome, NullReporter, PatchStatus, ProcessError,
+    ProcessOptions,
+};
+// Use helpers from the top-level test_common module
+use crate::test_common::{run_processor, run_with, setup_temp_dir, setup_temp_dir_with_files};
+
+const NUMBERS: &str = "one\ntwo\nthree\nfour\nfive\nsix\nseven\n";
+
+fn patch_md(path: &str, diff: &str) -> String {
+    format!("\n## Patch File: {}\n```diff\n{}```\n", path, diff)
+}
+
+#[test]
+fn test_process_patch_applies_hunks() {
+    let temp_dir = setup_temp_dir_with_files(&[("n.txt", NUMBERS)]);
+    let md = patch_md(
+        "n.txt",
+        "@@ -2,3 +2,3 @@\n two\n-three\n+THREE\n four\n@@ -6,2 +6,3 @@\n six\n seven\n+eight\n",
+    );
+
+    let (summary, _) = run_processor(&md, &temp_dir, false).expect("Processing failed");
+
+    temp_dir
+        .child("n.txt")
+        .assert("one\ntwo\nTHREE\nfour\nfive\nsix\nseven\neight\n");
+    assert_eq!(summary.patched, 1);
+}
+
+#[test]
+fn test_process_patch_finds_hunk_at_an_offset() {
+    let temp_dir = setup_temp_dir_with_files(&[("n.txt", NUMBERS)]);
+    // The line numbers are wrong by four lines; the context still matches
+    let md = patch_md("n.txt", "@@ -1,3 +1,3 @@\n four\n-five\n+FIVE\n six\n");
+
+    run_processor(&md, &temp_dir, false).expect("Processing failed");
+
+    temp_dir
+        .child("n.txt")
+        .assert("one\ntwo\nthree\nfour\nFIVE\nsix\nseven\n");
+}
+
+#[test]
+fn test_process_patch_fuzz_ignores_outer_context() {
+    let temp_dir = setup_temp_dir_with_files(&[("n.txt", NUMBERS)]);
+    // The first context line no longer matches the file
+    let md = patch_md("n.txt", "@@ -2,3 +2,3 @@\n TWO\n-three\n+3\n four\n");
+
+    let strict = ProcessOptions {
+        fuzz: 0,
+        ..ProcessOptions::default()
+    };
+    let report = run_with(temp_dir.path(), &md, &strict);
+    assert_eq!(report.summary.failed_patch, 1);
+    temp_dir.child("n.txt").assert(NUMBERS);
+
+    let report = run_with(temp_dir.path(), &md, &ProcessOptions::default());
+    assert_eq!(report.summary.patched, 1);
+    temp_dir
+        .child("n.txt")
+        .assert("one\ntwo\n3\nfour\nfive\nsix\nseven\n");
+}
+
+#[test]
+fn test_process_patch_failed_hunk_writes_nothing() {
+    let temp_dir = setup_temp_dir_with_files(&[("n.txt", NUMBERS)]);
+    // The first hunk applies, the second does not: the file must stay untouched
+    let md = patch_md(
+        "n.txt",
+        "@@ -1,2 +1,2 @@\n-one\n+ONE\n two\n@@ -5,1 +5,1 @@\n-nine\n+NINE\n",
+    );
+
+    let report = run_with(temp_dir.path(), &md, &ProcessOptions::default());
+
+    temp_dir.child("n.txt").assert(NUMBERS);
+    assert!(matches!(
+        &report.outcomes[0],
+        ActionOutcome::Failed(ProcessError::PatchFailed {
+            failed_hunks,
+            total_hunks: 2,
+            ..
+        }) if failed_hunks == &vec![2]
+    ));
+    assert_eq!(report.summary.failed_patch, 1);
+    assert!(report.summary.has_failures());
+}
+
+#[test]
+fn test_process_patch_invalid_diff_fails() {
+    let temp_dir = setup_temp_dir_with_files(&[("n.txt", NUMBERS)]);
+    let md = patch_md("n.txt", "this is not a diff\n");
+
+    let report = run_with(temp_dir.path(), &md, &ProcessOptions::default());
+
+    temp_dir.child("n.txt").assert(NUMBERS);
+    assert!(matches!(
+        report.outcomes[0],
+        ActionOutcome::Failed(ProcessError::InvalidPatch { .. })
+    ));
+    assert_eq!(report.summary.failed_patch_invalid, 1);
+}
+
+#[test]
+fn test_process_patch_from_dev_null_creates_file() {
+    let temp_dir = setup_temp_dir();
+    let md =
+        "\n```diff\n--- /dev/null\n+++ b/new/hello.txt\n@@ -0,0 +1,2 @@\n+hello\n+world\n```\n";
+
+    let report = run_with(temp_dir.path(), md, &ProcessOptions::default());
+
+    assert!(matches!(
+        report.outcomes[0],
+        ActionOutcome::Patch(PatchStatus::Created)
+    ));
+    temp_dir.child("new/hello.txt").assert("hello\nworld\n");
+}
+
+#[test]
+fn test_process_patch_missing_file_with_context_fails() {
+    let temp_dir = setup_temp_dir();
+    let md = patch_md("missing.txt", "@@ -1,2 +1,2 @@\n a\n-b\n+c\n");
+
+    let report = run_with(temp_dir.path(), &md, &ProcessOptions::default());
+
+    assert!(report.summary.has_failures());
+    temp_dir
+        .child("missing.txt")
+        .assert(predicate::path::missing());
+}
+
+#[test]
+fn test_process_patch_keeps_crlf_line_endings() {
+    let temp_dir = setup_temp_dir_with_files(&[("win.txt", "a\r\nb\r\nc\r\n")]);
+    let md = patch_md("win.txt", "@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n");
+
+    run_processor(&md, &temp_dir, false).expect("Processing failed");
+
+    temp_dir.child("win.txt").assert("a\r\nB\r\nc\r\n");
+}
+
+#[test]
+fn test_process_multi_file_diff_patches_each_file() {
+    let temp_dir = setup_temp_dir_with_files(&[("a.txt", "x\ny\n"), ("b.txt", "p\nq\n")]);
+    let md = "\n```diff\n--- a/a.txt\n+++ b/a.txt\n@@ -1,2 +1,2 @@\n x\n-y\n+Y\n--- a/b.txt\n+++ b/b.txt\n@@ -1,2 +1,2 @@\n-p\n+P\n q\n```\n";
+
+    let (summary, _) = run_processor(md, &temp_dir, false).expect("Processing failed");
+
+    assert_eq!(summary.patched, 2);
+    temp_dir.child("a.txt").assert("x\nY\n");
+    temp_dir.child("b.txt").assert("P\nq\n");
+}
+
+#[test]
+fn test_preview_shows_patched_content() {
+    let temp_dir = setup_temp_dir_with_files(&[("a.txt", "x\ny\n")]);
+    let actions = parse_markdown(&patch_md("a.txt", "@@ -1,2 +1,2 @@\n x\n-y\n+Y\n")).unwrap();
+
+    let preview = preview_actions_with(
+        temp_dir.path(),
+        actions,
+        &ProcessOptions::default(),
+        &NullReporter,
+    )
+    .expect("Preview failed");
+
+    assert_eq!(preview.changes.len(), 1);
+    assert_eq!(preview.changes[0].after.as_deref(), Some("x\nY\n"));
+    temp_dir.child("a.txt").assert("x\ny\n");
+}
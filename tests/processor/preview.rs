@@ -0,0 +1,216 @@
+//! Tests for content-level previews (unified diffs) of pending actions.
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use strux::{parse_markdown, preview_actions, ActionOutcome, ChangeKind, MoveStatus, NullReporter};
+// Use helpers from the top-level test_common module
+use crate::test_common::{setup_temp_dir, setup_temp_dir_with_files};
+
+#[test]
+fn test_preview_new_file_diffs_against_dev_null() {
+    let temp_dir = setup_temp_dir();
+    let md = "## File: src/lib.rs\n```\npub fn a() {}\n```\n";
+    let actions = parse_markdown(md).unwrap();
+
+    let preview =
+        preview_actions(temp_dir.path(), actions, false, &NullReporter).expect("Preview failed");
+
+    assert_eq!(preview.changes.len(), 1);
+    let diff = preview.changes[0].unified_diff();
+    assert!(diff.contains("--- /dev/null"));
+    assert!(diff.contains("+++ b/src/lib.rs"));
+    assert!(diff.contains("+pub fn a() {}"));
+    temp_dir.child("src").assert(predicate::path::missing());
+}
+
+#[test]
+fn test_preview_later_actions_see_earlier_changes() {
+    let temp_dir = setup_temp_dir();
+    let md = "## File: log.txt\n```\nfirst\n```\n\n## Append File: log.txt\n```\nsecond\n```\n";
+    let actions = parse_markdown(md).unwrap();
+
+    let preview =
+        preview_actions(temp_dir.path(), actions, false, &NullReporter).expect("Preview failed");
+
+    assert_eq!(preview.changes.len(), 2);
+    assert_eq!(preview.changes[1].before.as_deref(), Some("first\n"));
+    assert_eq!(preview.changes[1].after.as_deref(), Some("first\nsecond\n"));
+    let diff = preview.changes[1].unified_diff();
+    assert!(diff.contains("--- a/log.txt"));
+    assert!(diff.contains(" first"));
+    assert!(diff.contains("+second"));
+    temp_dir.child("log.txt").assert(predicate::path::missing());
+}
+
+#[test]
+fn test_preview_existing_file_skipped_without_force() {
+    let temp_dir = setup_temp_dir();
+    temp_dir.child("a.txt").write_str("old\n").unwrap();
+    let md = "## File: a.txt\n```\nnew\n```\n";
+
+    let skipped = preview_actions(
+        temp_dir.path(),
+        parse_markdown(md).unwrap(),
+        false,
+        &NullReporter,
+    )
+    .expect("Preview");
+    assert!(skipped.changes.is_empty());
+    assert_eq!(skipped.report.summary.skipped_exists, 1);
+
+    let forced = preview_actions(
+        temp_dir.path(),
+        parse_markdown(md).unwrap(),
+        true,
+        &NullReporter,
+    )
+    .expect("Preview");
+    let diff = forced.changes[0].unified_diff();
+    assert!(diff.contains("-old"));
+    assert!(diff.contains("+new"));
+    temp_dir.child("a.txt").assert("old\n");
+}
+
+#[test]
+fn test_preview_delete_and_move() {
+    let temp_dir = setup_temp_dir();
+    temp_dir.child("doomed.txt").write_str("bye\n").unwrap();
+    temp_dir.child("old.txt").write_str("move me\n").unwrap();
+    let md = "## Deleted File: doomed.txt\n\n## Moved File: old.txt to new/old.txt\n";
+    let actions = parse_markdown(md).unwrap();
+
+    let preview =
+        preview_actions(temp_dir.path(), actions, false, &NullReporter).expect("Preview failed");
+
+    assert_eq!(preview.changes.len(), 2);
+    assert_eq!(preview.changes[0].kind, ChangeKind::Delete);
+    let delete_diff = preview.changes[0].unified_diff();
+    assert!(delete_diff.contains("+++ /dev/null"));
+    assert!(delete_diff.contains("-bye"));
+
+    assert_eq!(
+        preview.changes[1].kind,
+        ChangeKind::Rename {
+            to: "new/old.txt".to_string()
+        }
+    );
+    let move_diff = preview.changes[1].unified_diff();
+    assert!(move_diff.contains("rename from old.txt"));
+    assert!(move_diff.contains("rename to new/old.txt"));
+
+    temp_dir.child("doomed.txt").assert("bye\n");
+    temp_dir.child("old.txt").assert("move me\n");
+}
+
+#[test]
+fn test_preview_skips_failed_actions() {
+    let temp_dir = setup_temp_dir();
+    temp_dir.child("a_dir").create_dir_all().unwrap();
+    let md = "## File: a_dir\n```\ncontent\n```\n";
+    let actions = parse_markdown(md).unwrap();
+
+    let preview =
+        preview_actions(temp_dir.path(), actions, false, &NullReporter).expect("Preview failed");
+
+    assert!(preview.changes.is_empty());
+    assert_eq!(preview.report.summary.failed_isdir_create, 1);
+}
+
+#[test]
+fn test_preview_deleted_directory_lists_every_file() {
+    let temp_dir = setup_temp_dir();
+    temp_dir.child("old/a.txt").write_str("a\n").unwrap();
+    temp_dir.child("old/sub/b.txt").write_str("b\n").unwrap();
+    let md = "## File: old/c.txt\n```\nc\n```\n\n## Deleted Directory: old\n";
+    let actions = parse_markdown(md).unwrap();
+
+    let preview =
+        preview_actions(temp_dir.path(), actions, true, &NullReporter).expect("Preview failed");
+
+    let deleted: Vec<_> = preview
+        .changes
+        .iter()
+        .filter(|change| change.kind == ChangeKind::Delete)
+        .map(|change| change.path.as_str())
+        .collect();
+    assert_eq!(deleted, vec!["old/a.txt", "old/c.txt", "old/sub/b.txt"]);
+    temp_dir.child("old/a.txt").assert("a\n");
+}
+
+#[test]
+fn test_preview_moved_directory_renames_every_file() {
+    let temp_dir = setup_temp_dir();
+    temp_dir.child("old/a.txt").write_str("a\n").unwrap();
+    temp_dir.child("old/sub/b.txt").write_str("b\n").unwrap();
+    let md = "## Moved Directory: old to new\n";
+    let actions = parse_markdown(md).unwrap();
+
+    let preview =
+        preview_actions(temp_dir.path(), actions, false, &NullReporter).expect("Preview failed");
+
+    let renames: Vec<_> = preview
+        .changes
+        .iter()
+        .map(|change| (change.path.as_str(), change.kind.clone()))
+        .collect();
+    assert_eq!(
+        renames,
+        vec![
+            (
+                "old/a.txt",
+                ChangeKind::Rename {
+                    to: "new/a.txt".to_string()
+                }
+            ),
+            (
+                "old/sub/b.txt",
+                ChangeKind::Rename {
+                    to: "new/sub/b.txt".to_string()
+                }
+            ),
+        ]
+    );
+    temp_dir.child("new").assert(predicate::path::missing());
+}
+
+#[test]
+fn test_preview_edit_file_shows_replaced_lines() {
+    let temp_dir = setup_temp_dir_with_files(&[("app.cfg", "host = a\nport = 80\n")]);
+    let md = "## Edit File: app.cfg\n```\n<<<<<<< SEARCH\nport = 80\n=======\nport = 8080\n>>>>>>> REPLACE\n```\n";
+    let actions = parse_markdown(md).unwrap();
+
+    let preview =
+        preview_actions(temp_dir.path(), actions, false, &NullReporter).expect("Preview failed");
+
+    assert_eq!(preview.changes.len(), 1);
+    let diff = preview.changes[0].unified_diff();
+    assert!(diff.contains("-port = 80"));
+    assert!(diff.contains("+port = 8080"));
+    temp_dir.child("app.cfg").assert("host = a\nport = 80\n");
+}
+
+#[test]
+fn test_preview_statuses_match_the_diffs() {
+    let temp_dir = setup_temp_dir();
+    let md = "## File: a.txt\n```\nA\n```\n\n## Moved File: a.txt to b.txt\n";
+    let actions = parse_markdown(md).unwrap();
+
+    let preview =
+        preview_actions(temp_dir.path(), actions, false, &NullReporter).expect("Preview failed");
+
+    assert!(matches!(
+        preview.report.outcomes[1],
+        ActionOutcome::Move(MoveStatus::Moved)
+    ));
+    assert_eq!(preview.report.summary.moved, 1);
+    assert_eq!(preview.changes.len(), 2);
+    assert_eq!(preview.changes[1].action_index, 1);
+    assert_eq!(
+        preview.changes[1].kind,
+        ChangeKind::Rename {
+            to: "b.txt".to_string()
+        }
+    );
+    assert_eq!(preview.changes[1].after.as_deref(), Some("A\n"));
+    temp_dir.child("a.txt").assert(predicate::path::missing());
+}
@@ -0,0 +1,59 @@
+//! Tests for the events the processor sends to a Reporter.
+
+use std::cell::RefCell;
+use strux::{
+    parse_markdown, process_actions_with, ActionOutcome, CreateStatus, Event, ProcessOptions,
+    Reporter,
+};
+// Use helpers from the top-level test_common module
+use crate::test_common::setup_temp_dir;
+
+/// Records action lifecycle events and warnings/errors.
+#[derive(Default)]
+struct RecordingReporter {
+    events: RefCell<Vec<String>>,
+}
+
+impl Reporter for RecordingReporter {
+    fn report(&self, event: &Event) {
+        let description = match event {
+            Event::ActionStarted { index, total, .. } => format!("start {}/{}", index, total),
+            Event::ActionOutcome { index, outcome, .. } => match outcome {
+                ActionOutcome::Create(CreateStatus::Created) => format!("done {} created", index),
+                ActionOutcome::Failed(_) => format!("done {} failed", index),
+                _ => format!("done {} other", index),
+            },
+            Event::Error(_) => "error".to_string(),
+            _ => return,
+        };
+        self.events.borrow_mut().push(description);
+    }
+}
+
+#[test]
+fn test_reporter_receives_action_lifecycle() {
+    let temp_dir = setup_temp_dir();
+    let md = "## File: a.txt\n```\nA\n```\n\n## File: ../escape.txt\n```\nX\n```\n";
+    let actions = parse_markdown(md).unwrap();
+    let reporter = RecordingReporter::default();
+
+    let report = process_actions_with(
+        temp_dir.path(),
+        actions,
+        &ProcessOptions::default(),
+        &reporter,
+    )
+    .expect("Processing failed");
+
+    assert_eq!(report.summary.created, 1);
+    assert_eq!(
+        *reporter.events.borrow(),
+        vec![
+            "start 0/2",
+            "done 0 created",
+            "start 1/2",
+            "error",
+            "done 1 failed"
+        ]
+    );
+}
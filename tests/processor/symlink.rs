@@ -0,0 +1,224 @@
+//! Tests for the 'Symlink' action in the processor.
+#![cfg(unix)]
+
+use assert_fs::prelude::*;
+use std::fs;
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+use strux::{
+    check_actions, parse_markdown, undo_last_run, ActionOutcome, CheckStatus, NullReporter,
+    ProcessError, ProcessOptions, SymlinkStatus, DEFAULT_PATCH_FUZZ,
+};
+// Use helpers from the top-level test_common module
+use crate::test_common::{run_processor, run_with, setup_temp_dir, setup_temp_dir_with_files};
+
+fn link_target(path: &Path) -> PathBuf {
+    fs::read_link(path).expect("Path should be a symlink")
+}
+
+#[test]
+fn test_process_symlink_creates_link_and_parents() {
+    let temp_dir = setup_temp_dir_with_files(&[("releases/v1/app", "v1\n")]);
+    let md = "## Symlink: current to releases/v1\n\n## Symlink: bin/app to ../current/app\n";
+
+    let (summary, _) = run_processor(md, &temp_dir, false).expect("Processing failed");
+
+    assert_eq!(summary.symlinks_created, 2);
+    assert_eq!(
+        link_target(temp_dir.child("current").path()),
+        Path::new("releases/v1")
+    );
+    assert_eq!(
+        link_target(temp_dir.child("bin/app").path()),
+        Path::new("../current/app")
+    );
+    // The links resolve relative to their own directory
+    temp_dir.child("bin/app").assert("v1\n");
+}
+
+#[test]
+fn test_process_symlink_dangling_target_is_allowed() {
+    let temp_dir = setup_temp_dir();
+    let md = "## Symlink: latest to not/yet/there\n";
+
+    let (summary, _) = run_processor(md, &temp_dir, false).expect("Processing failed");
+
+    assert_eq!(summary.symlinks_created, 1);
+    assert_eq!(
+        link_target(temp_dir.child("latest").path()),
+        Path::new("not/yet/there")
+    );
+}
+
+#[test]
+fn test_process_symlink_existing_link_needs_force() {
+    let temp_dir = setup_temp_dir_with_files(&[("v1/x", "1"), ("v2/x", "2")]);
+    symlink("v1", temp_dir.child("current").path()).unwrap();
+    let md = "## Symlink: current to v2\n";
+
+    let report = run_with(temp_dir.path(), md, &ProcessOptions::default());
+    assert_eq!(report.summary.failed_symlink_exists, 1);
+    assert!(matches!(
+        &report.outcomes[0],
+        ActionOutcome::Failed(ProcessError::SymlinkExists { target, .. }) if target == Path::new("v1")
+    ));
+    assert_eq!(
+        link_target(temp_dir.child("current").path()),
+        Path::new("v1")
+    );
+
+    let (summary, _) = run_processor(md, &temp_dir, true).expect("Processing failed");
+    assert_eq!(summary.symlinks_replaced, 1);
+    assert_eq!(
+        link_target(temp_dir.child("current").path()),
+        Path::new("v2")
+    );
+
+    // Pointing to the same target again is a no-op, with or without --force
+    let (summary, _) = run_processor(md, &temp_dir, false).expect("Processing failed");
+    assert_eq!(summary.skipped_symlink_unchanged, 1);
+}
+
+#[test]
+fn test_process_symlink_never_replaces_files_or_directories() {
+    let temp_dir = setup_temp_dir_with_files(&[("current", "a file\n"), ("dir/keep.txt", "k")]);
+    let md = "## Symlink: current to v1\n\n## Symlink: dir to v1\n";
+
+    let report = run_with(
+        temp_dir.path(),
+        md,
+        &ProcessOptions {
+            overwrite: true,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(report.summary.failed_symlink_exists, 2);
+    assert!(matches!(
+        &report.outcomes[1],
+        ActionOutcome::Failed(ProcessError::SymlinkPathOccupied {
+            kind: "directory",
+            ..
+        })
+    ));
+    temp_dir.child("current").assert("a file\n");
+    temp_dir.child("dir/keep.txt").assert("k");
+}
+
+#[test]
+fn test_process_symlink_target_outside_base_fails() {
+    let temp_dir = setup_temp_dir();
+    let base = temp_dir.child("base");
+    base.create_dir_all().unwrap();
+    let outside = temp_dir.child("outside");
+    outside.create_dir_all().unwrap();
+    // A link inside the base that leads outside it must not be usable as a stepping stone
+    symlink(outside.path(), base.child("escape").path()).unwrap();
+    let md = "## Symlink: a to ../outside\n\n\
+              ## Symlink: b to /etc/passwd\n\n\
+              ## Symlink: c to escape/file\n\n\
+              ## Symlink: d to escape/../../outside\n\n\
+              ## Symlink: e to sub/../ok\n";
+
+    let report = run_with(base.path(), md, &ProcessOptions::default());
+
+    assert_eq!(report.summary.failed_unsafe, 4);
+    for name in ["a", "b", "c", "d"] {
+        assert!(fs::symlink_metadata(base.child(name).path()).is_err());
+    }
+    assert_eq!(report.summary.symlinks_created, 1);
+    assert_eq!(link_target(base.child("e").path()), Path::new("sub/../ok"));
+}
+
+#[test]
+fn test_process_symlink_dry_run_creates_nothing() {
+    let temp_dir = setup_temp_dir();
+    let md = "## Symlink: nested/current to ../v1\n";
+
+    let report = run_with(
+        temp_dir.path(),
+        md,
+        &ProcessOptions {
+            dry_run: true,
+            ..Default::default()
+        },
+    );
+
+    assert!(matches!(
+        report.outcomes[0],
+        ActionOutcome::Symlink(SymlinkStatus::Created)
+    ));
+    assert!(!temp_dir.child("nested").path().exists());
+}
+
+#[test]
+fn test_process_symlink_rollback_and_undo_restore_links() {
+    let temp_dir = setup_temp_dir_with_files(&[("blocker", "file")]);
+    symlink("v1", temp_dir.child("current").path()).unwrap();
+    let md = "## Symlink: current to v2\n\n## Symlink: fresh to v2\n\n## File: blocker/x.txt\n```\nx\n```\n";
+
+    // --atomic: the failing last action rolls both links back
+    let report = run_with(
+        temp_dir.path(),
+        md,
+        &ProcessOptions {
+            overwrite: true,
+            atomic: true,
+            ..Default::default()
+        },
+    );
+    assert!(report.rolled_back);
+    assert_eq!(
+        link_target(temp_dir.child("current").path()),
+        Path::new("v1")
+    );
+    assert!(fs::symlink_metadata(temp_dir.child("fresh").path()).is_err());
+
+    // undo: the journal records the replaced link's old target
+    let md = "## Symlink: current to v2\n\n## Symlink: fresh to v2\n";
+    run_with(
+        temp_dir.path(),
+        md,
+        &ProcessOptions {
+            overwrite: true,
+            journal: true,
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        link_target(temp_dir.child("current").path()),
+        Path::new("v2")
+    );
+    undo_last_run(temp_dir.path(), &NullReporter).expect("Undo should succeed");
+    assert_eq!(
+        link_target(temp_dir.child("current").path()),
+        Path::new("v1")
+    );
+    assert!(fs::symlink_metadata(temp_dir.child("fresh").path()).is_err());
+}
+
+#[test]
+fn test_check_symlink_expectations() {
+    let temp_dir = setup_temp_dir_with_files(&[("not_a_link", "x")]);
+    symlink("v1", temp_dir.child("current").path()).unwrap();
+    symlink("v0", temp_dir.child("old").path()).unwrap();
+    let actions = parse_markdown(
+        "## Symlink: current to v1\n\n## Symlink: old to v1\n\n## Symlink: not_a_link to v1\n\n## Symlink: missing to v1\n",
+    )
+    .unwrap();
+    // `not_a_link` is a file the document never describes, so it is expected to be a link
+
+    let report =
+        check_actions(temp_dir.path(), &actions, DEFAULT_PATCH_FUZZ, &NullReporter).unwrap();
+    let statuses: Vec<CheckStatus> = report.entries.iter().map(|entry| entry.status).collect();
+
+    assert_eq!(
+        statuses,
+        vec![
+            CheckStatus::Match,
+            CheckStatus::LinkTargetDiffers,
+            CheckStatus::NotASymlink,
+            CheckStatus::Missing,
+        ]
+    );
+}
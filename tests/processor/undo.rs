@@ -0,0 +1,250 @@
+//! Tests for the undo journal (`ProcessOptions::journal`) and `undo_last_run`.
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use strux::{
+    parse_markdown, process_actions_with, undo_last_run, AppError, NullReporter, ProcessOptions,
+};
+// Use helpers from the top-level test_common module
+use crate::test_common::{setup_temp_dir, setup_temp_dir_with_files};
+
+fn journaled() -> ProcessOptions {
+    ProcessOptions {
+        overwrite: true,
+        journal: true,
+        ..ProcessOptions::default()
+    }
+}
+
+fn run(base: &std::path::Path, md: &str) {
+    let actions = parse_markdown(md).unwrap();
+    let report = process_actions_with(base, actions, &journaled(), &NullReporter)
+        .expect("Processing should not fail overall");
+    assert!(report.journal_dir.is_some());
+}
+
+#[test]
+fn test_undo_restores_every_change() {
+    let temp_dir = setup_temp_dir_with_files(&[
+        ("existing.txt", "old content\n"),
+        ("log.txt", "line 1\n"),
+        ("config.ini", "key=1\n"),
+        ("doomed.txt", "keep me\n"),
+        ("old/name.txt", "moving\n"),
+    ]);
+    run(
+        temp_dir.path(),
+        "## File: existing.txt\n```\nnew content\n```\n\n\
+         ## Append File: log.txt\n```\nline 2\n```\n\n\
+         ## Prepend File: config.ini\n```\n# header\n```\n\n\
+         ## Deleted File: doomed.txt\n\n\
+         ## Moved File: old/name.txt to renamed/name.txt\n\n\
+         ## File: brand/new/file.txt\n```\nfresh\n```\n",
+    );
+    temp_dir.child("log.txt").assert("line 1\nline 2\n");
+
+    let report = undo_last_run(temp_dir.path(), &NullReporter).expect("Undo should succeed");
+
+    assert_eq!(report.failed, 0);
+    temp_dir.child("existing.txt").assert("old content\n");
+    temp_dir.child("log.txt").assert("line 1\n");
+    temp_dir.child("config.ini").assert("key=1\n");
+    temp_dir.child("doomed.txt").assert("keep me\n");
+    temp_dir.child("old/name.txt").assert("moving\n");
+    temp_dir.child("renamed").assert(predicate::path::missing());
+    temp_dir.child("brand").assert(predicate::path::missing());
+    // The replayed journal is gone, and with it the journal directories
+    temp_dir.child(".strux").assert(predicate::path::missing());
+}
+
+#[test]
+fn test_undo_refuses_when_files_changed() {
+    let temp_dir = setup_temp_dir_with_files(&[("notes.txt", "v1\n")]);
+    run(
+        temp_dir.path(),
+        "## File: notes.txt\n```\nv2\n```\n\n## File: other.txt\n```\nx\n```\n",
+    );
+    temp_dir
+        .child("notes.txt")
+        .write_str("edited by hand\n")
+        .unwrap();
+
+    let result = undo_last_run(temp_dir.path(), &NullReporter);
+
+    match result {
+        Err(AppError::Undo(message)) => {
+            assert!(message.contains("notes.txt"), "{}", message);
+            assert!(!message.contains("other.txt"), "{}", message);
+        }
+        other => panic!("Expected an undo refusal, got {:?}", other),
+    }
+    // Nothing was touched
+    temp_dir.child("notes.txt").assert("edited by hand\n");
+    temp_dir.child("other.txt").assert("x\n");
+}
+
+#[test]
+fn test_undo_reverts_runs_newest_first() {
+    let temp_dir = setup_temp_dir();
+    run(temp_dir.path(), "## File: a.txt\n```\none\n```\n");
+    run(temp_dir.path(), "## File: a.txt\n```\ntwo\n```\n");
+
+    undo_last_run(temp_dir.path(), &NullReporter).expect("First undo should succeed");
+    temp_dir.child("a.txt").assert("one\n");
+
+    undo_last_run(temp_dir.path(), &NullReporter).expect("Second undo should succeed");
+    temp_dir.child("a.txt").assert(predicate::path::missing());
+
+    let result = undo_last_run(temp_dir.path(), &NullReporter);
+    assert!(matches!(result, Err(AppError::Undo(_))));
+}
+
+#[test]
+fn test_only_the_newest_journals_are_kept() {
+    let temp_dir = setup_temp_dir();
+    for run_number in 0..12 {
+        run(
+            temp_dir.path(),
+            &format!("## File: a.txt\n```\n{}\n```\n", run_number),
+        );
+    }
+
+    let journals = std::fs::read_dir(temp_dir.child(".strux/journal").path())
+        .unwrap()
+        .count();
+    assert_eq!(journals, 10);
+
+    // The kept journals still undo the newest runs in order
+    undo_last_run(temp_dir.path(), &NullReporter).expect("Undo should succeed");
+    temp_dir.child("a.txt").assert("10\n");
+}
+
+#[test]
+fn test_no_journal_without_option() {
+    let temp_dir = setup_temp_dir();
+    let actions = parse_markdown("## File: a.txt\n```\nA\n```\n").unwrap();
+    let report = process_actions_with(
+        temp_dir.path(),
+        actions,
+        &ProcessOptions::default(),
+        &NullReporter,
+    )
+    .expect("Processing should not fail overall");
+
+    assert!(report.journal_dir.is_none());
+    temp_dir.child(".strux").assert(predicate::path::missing());
+}
+
+/// Writes a hand-made journal under `base`, as a tampered document or user could.
+fn forge_journal(base: &std::path::Path, entries: serde_json::Value) -> std::path::PathBuf {
+    let journal_dir = base.join(".strux/journal/99999999999999");
+    std::fs::create_dir_all(journal_dir.join("blobs")).unwrap();
+    std::fs::write(journal_dir.join("blobs/0"), "forged\n").unwrap();
+    let manifest = serde_json::json!({ "version": 2, "entries": entries });
+    std::fs::write(journal_dir.join("journal.json"), manifest.to_string()).unwrap();
+    journal_dir
+}
+
+#[test]
+fn test_undo_refuses_blobs_outside_the_journal() {
+    let temp_dir = setup_temp_dir_with_files(&[("secret.txt", "secret\n")]);
+    let base = temp_dir.child("out");
+    base.create_dir_all().unwrap();
+    let secret = temp_dir.child("secret.txt");
+    forge_journal(
+        base.path(),
+        serde_json::json!([{
+            "path": "stolen.txt",
+            "before": { "state": "file", "blob": secret.path().to_string_lossy() },
+            "after": { "state": "missing" },
+        }]),
+    );
+
+    let result = undo_last_run(base.path(), &NullReporter);
+
+    match result {
+        Err(AppError::Undo(message)) => assert!(message.contains("invalid blob"), "{}", message),
+        other => panic!("Expected an undo refusal, got {:?}", other),
+    }
+    base.child("stolen.txt").assert(predicate::path::missing());
+}
+
+#[test]
+fn test_undo_never_restores_through_links_leaving_the_base() {
+    let temp_dir = setup_temp_dir();
+    let victim = temp_dir.child("victim");
+    victim.create_dir_all().unwrap();
+    let base = temp_dir.child("out");
+    base.create_dir_all().unwrap();
+    // Replayed newest first: the link comes back before the file "below" it
+    forge_journal(
+        base.path(),
+        serde_json::json!([
+            {
+                "path": "link/pwned.txt",
+                "before": { "state": "file", "blob": "0" },
+                "after": { "state": "missing" },
+            },
+            {
+                "path": "link",
+                "before": { "state": "symlink", "target": victim.path().to_string_lossy() },
+                "after": { "state": "missing" },
+            },
+        ]),
+    );
+
+    let report = undo_last_run(base.path(), &NullReporter).expect("Undo should run");
+
+    assert_eq!(report.failed, 1);
+    victim.child("pwned.txt").assert(predicate::path::missing());
+    base.child("link").assert(predicate::path::is_dir());
+}
+
+#[test]
+fn test_actions_under_strux_dir_are_refused() {
+    let temp_dir = setup_temp_dir();
+    let actions = parse_markdown(
+        "## File: .strux/journal/1/journal.json\n```\n{}\n```\n\n\
+         ## Symlink: journals to .strux\n",
+    )
+    .unwrap();
+    let report = process_actions_with(
+        temp_dir.path(),
+        actions,
+        &ProcessOptions::default(),
+        &NullReporter,
+    )
+    .expect("Processing should not fail overall");
+
+    assert_eq!(report.summary.failed_unsafe, 2);
+    temp_dir.child(".strux").assert(predicate::path::missing());
+    temp_dir
+        .child("journals")
+        .assert(predicate::path::missing());
+}
+
+#[test]
+fn test_no_journal_when_nothing_changed() {
+    let temp_dir = setup_temp_dir_with_files(&[("a.txt", "one\n")]);
+    run(temp_dir.path(), "## File: a.txt\n```\ntwo\n```\n");
+
+    // Run twice: the second run skips everything (content already appended, file already gone)
+    let actions =
+        parse_markdown("## Append File: b.txt\n```\nb\n```\n\n## Deleted File: missing.txt\n")
+            .unwrap();
+    let options = ProcessOptions {
+        idempotent: true,
+        ..journaled()
+    };
+    let actions_again = actions.clone();
+    process_actions_with(temp_dir.path(), actions, &options, &NullReporter).unwrap();
+    let report =
+        process_actions_with(temp_dir.path(), actions_again, &options, &NullReporter).unwrap();
+
+    assert!(report.journal_dir.is_none());
+    // Undo skips straight past the no-op run
+    undo_last_run(temp_dir.path(), &NullReporter).expect("Undo should succeed");
+    temp_dir.child("b.txt").assert(predicate::path::missing());
+    undo_last_run(temp_dir.path(), &NullReporter).expect("Undo should succeed");
+    temp_dir.child("a.txt").assert("one\n");
+}
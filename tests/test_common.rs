@@ -3,7 +3,10 @@
 use assert_fs::prelude::*;
 use assert_fs::TempDir;
 use std::path::Path;
-use strux::{parse_markdown, process_actions, Action, AppError, Summary};
+use strux::{
+    parse_markdown, process_actions, process_actions_with, Action, AppError, NullReporter,
+    ProcessOptions, ProcessReport, Summary,
+};
 
 /// Sets up a temporary directory and populates it with initial files if needed.
 pub fn setup_temp_dir() -> TempDir {
@@ -27,6 +30,14 @@ pub fn run_processor(
     Ok((summary, actions))
 }
 
+/// Parses `md` and processes it with explicit options, panicking on a parse or setup error.
+/// Returns the report with the outcome of every action.
+#[allow(dead_code)]
+pub fn run_with(base: &Path, md: &str, options: &ProcessOptions) -> ProcessReport {
+    let actions = parse_markdown(md).expect("Parsing failed");
+    process_actions_with(base, actions, options, &NullReporter).expect("Processing failed")
+}
+
 /// Helper to read file content, returning None if not found or error.
 #[allow(dead_code)]
 pub fn read_file_content(path: &Path) -> Option<String> {